@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TransferChecked};
+
+declare_id!("CtWYKhs2AAftJndipWv5RbCkmkHD8fxHxJ9HnkJpbZed");
+
+// ============================================================================
+// FLASH LOAN RECEIVER (test program for Module 3.5)
+// ============================================================================
+//
+// solrush_dex::flash_loan invokes its receiver with
+// `amount (u64 LE) || fee (u64 LE) || receiver_data` and no Anchor
+// discriminator, so every call lands in the fallback. The first byte of
+// receiver_data picks what the receiver does with the loan.
+
+/// Pay back amount + fee from the borrower's account
+pub const REPAY: u8 = 0;
+/// Swap the borrowed tokens in the lending pool before repaying
+pub const REENTER_SWAP: u8 = 1;
+
+#[program]
+pub mod flash_loan_receiver {
+    use super::*;
+
+    pub fn fallback<'info>(
+        _program_id: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+        data: &[u8],
+    ) -> Result<()> {
+        require!(data.len() > 16, ReceiverError::InvalidData);
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let fee = u64::from_le_bytes(data[8..16].try_into().unwrap());
+
+        match data[16] {
+            REPAY => repay(accounts, amount, fee),
+            REENTER_SWAP => reenter_swap(accounts, amount),
+            _ => err!(ReceiverError::InvalidData),
+        }
+    }
+}
+
+/// Accounts: borrower token account, mint, pool vault, borrower (signer), token program
+fn repay<'info>(accounts: &'info [AccountInfo<'info>], amount: u64, fee: u64) -> Result<()> {
+    let [source, mint, vault, borrower, token_program] = accounts else {
+        return err!(ReceiverError::InvalidAccounts);
+    };
+    let decimals = InterfaceAccount::<Mint>::try_from(mint)?.decimals;
+
+    transfer_checked(
+        CpiContext::new(
+            token_program.clone(),
+            TransferChecked {
+                from: source.clone(),
+                mint: mint.clone(),
+                to: vault.clone(),
+                authority: borrower.clone(),
+            },
+        ),
+        amount
+            .checked_add(fee)
+            .ok_or(error!(ReceiverError::InvalidData))?,
+        decimals,
+    )
+}
+
+/// Accounts: the solrush_dex `Swap` accounts in order, then the solrush_dex program
+/// The pool is locked for the loan, so the swap must fail
+fn reenter_swap<'info>(accounts: &'info [AccountInfo<'info>], amount: u64) -> Result<()> {
+    let [pool, user_token_in, user_token_out, pool_vault_in, pool_vault_out, token_in_mint, token_out_mint, user, token_in_program, token_out_program, dex_program] =
+        accounts
+    else {
+        return err!(ReceiverError::InvalidAccounts);
+    };
+
+    solrush_dex::cpi::swap(
+        CpiContext::new(
+            dex_program.clone(),
+            solrush_dex::cpi::accounts::Swap {
+                pool: pool.clone(),
+                user_token_in: user_token_in.clone(),
+                user_token_out: user_token_out.clone(),
+                pool_vault_in: pool_vault_in.clone(),
+                pool_vault_out: pool_vault_out.clone(),
+                token_in_mint: token_in_mint.clone(),
+                token_out_mint: token_out_mint.clone(),
+                user: user.clone(),
+                token_in_program: token_in_program.clone(),
+                token_out_program: token_out_program.clone(),
+            },
+        ),
+        amount,
+        0,
+        true,
+        None,
+    )
+}
+
+#[error_code]
+pub enum ReceiverError {
+    #[msg("Receiver data is malformed")]
+    InvalidData,
+
+    #[msg("Unexpected accounts for the requested action")]
+    InvalidAccounts,
+}
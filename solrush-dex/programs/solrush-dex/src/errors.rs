@@ -32,6 +32,27 @@ pub enum CustomError {
     #[msg("Insufficient pool reserves")]
     InsufficientPoolReserves,
     
+    #[msg("Initial deposit does not exceed the locked minimum liquidity")]
+    InitialLiquidityTooLow,
+    
+    #[msg("Pool mints must be distinct and in canonical order (token_a_mint < token_b_mint)")]
+    InvalidMintOrder,
+    
+    #[msg("Token account mint does not match the pool")]
+    InvalidTokenMint,
+    
+    #[msg("Vault balance is below the pool's accounted reserves")]
+    VaultInvariantViolated,
+    
+    #[msg("No surplus vault balance to skim")]
+    NoSurplus,
+    
+    #[msg("Mint uses a Token-2022 extension the pool does not support")]
+    UnsupportedMintExtension,
+    
+    #[msg("Token symbol must be 1-10 ASCII alphanumeric characters")]
+    InvalidTokenSymbol,
+    
     // ============================================================================
     // Module 3.4: Limit Order Errors
     // ============================================================================
@@ -66,4 +87,193 @@ pub enum CustomError {
     
     #[msg("Invalid authority - must be configured authority")]
     InvalidAuthority,
+    
+    // ============================================================================
+    // Module 5: Protocol Configuration Errors
+    // ============================================================================
+    
+    #[msg("Fee tier is not on the allowlist")]
+    FeeTierNotAllowed,
+    
+    #[msg("Fee tier is already on the allowlist")]
+    FeeTierAlreadyExists,
+    
+    #[msg("Fee tier allowlist is full")]
+    FeeTierListFull,
+    
+    #[msg("Protocol fee share exceeds the allowed maximum")]
+    InvalidProtocolFeeShare,
+    
+    #[msg("No protocol fees to collect")]
+    NoProtocolFees,
+    
+    #[msg("Token account is not owned by the protocol treasury")]
+    InvalidTreasury,
+    
+    #[msg("Pool registry page is full")]
+    RegistryPageFull,
+    
+    #[msg("Registry page has no live entry for this pool")]
+    RegistryEntryNotFound,
+    
+    #[msg("Account type cannot be migrated")]
+    UnsupportedAccountType,
+    
+    #[msg("Account already uses the current layout version")]
+    AccountAlreadyMigrated,
+    
+    #[msg("Account size does not match a known layout")]
+    UnexpectedAccountSize,
+    
+    #[msg("Legacy pool does not have the fixed 3/1000 fee or is not at its pre-fee-tier PDA")]
+    LegacyPoolNotMigratable,
+    
+    // ============================================================================
+    // Module 2.9: Pool Status Errors
+    // ============================================================================
+    
+    #[msg("Swaps are disabled while the pool is paused, withdraw-only or frozen")]
+    PoolSwapsDisabled,
+    
+    #[msg("Deposits are disabled while the pool is withdraw-only or frozen")]
+    PoolDepositsDisabled,
+    
+    #[msg("Withdrawals are disabled while the pool is frozen")]
+    PoolWithdrawalsDisabled,
+    
+    #[msg("Reserves cannot be synced or skimmed while the pool is frozen")]
+    PoolReconciliationDisabled,
+    
+    #[msg("Pool already has the requested status")]
+    PoolStatusUnchanged,
+    
+    // ============================================================================
+    // Module 5.5: Authority Transfer Errors
+    // ============================================================================
+    
+    #[msg("Proposed authority must be a new, non-default key")]
+    InvalidProposedAuthority,
+    
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+    
+    // ============================================================================
+    // Module 2.10: Close Pool Errors
+    // ============================================================================
+    
+    #[msg("Pool still has outstanding LP tokens")]
+    PoolNotEmpty,
+    
+    #[msg("Protocol fees must be collected before closing the pool")]
+    ProtocolFeesNotCollected,
+    
+    // ============================================================================
+    // Module 4.7: LP Staking Errors
+    // ============================================================================
+    
+    #[msg("Insufficient staked LP balance")]
+    InsufficientStakedBalance,
+    
+    // ============================================================================
+    // Module 2.11: StableSwap Errors
+    // ============================================================================
+    
+    #[msg("Amplification coefficient out of range for this pool type")]
+    InvalidAmpCoefficient,
+    
+    #[msg("StableSwap pools require both mints to use the same decimals")]
+    StableSwapDecimalsMismatch,
+    
+    #[msg("StableSwap invariant did not converge")]
+    StableSwapNotConverged,
+    
+    #[msg("Instruction is not supported for this pool type")]
+    UnsupportedPoolType,
+    
+    // ============================================================================
+    // Module 2.12: Weighted Pool Errors
+    // ============================================================================
+    
+    #[msg("Pool weights must each be at least 1% and sum to 100%")]
+    InvalidPoolWeights,
+    
+    #[msg("Weighted pool swaps may add at most 30% of the input reserve")]
+    WeightedSwapTooLarge,
+    
+    // ============================================================================
+    // Module 2.13: Concentrated Liquidity Errors
+    // ============================================================================
+    
+    #[msg("Tick spacing must be between 1 and 1000")]
+    InvalidTickSpacing,
+    
+    #[msg("Tick index is out of range or not a multiple of the tick spacing")]
+    InvalidTickIndex,
+    
+    #[msg("Lower tick must be below the upper tick")]
+    InvalidTickRange,
+    
+    #[msg("Sqrt price is outside the supported range")]
+    InvalidSqrtPrice,
+    
+    #[msg("Tick array does not belong to this pool or does not cover the tick")]
+    InvalidTickArray,
+    
+    #[msg("Swap ran past the supplied tick arrays")]
+    TickArrayMissing,
+    
+    #[msg("Position does not hold enough liquidity")]
+    InsufficientPositionLiquidity,
+    
+    #[msg("Position still holds liquidity or uncollected fees")]
+    PositionNotEmpty,
+    
+    // ============================================================================
+    // Module 2.14: Dynamic Fee Errors
+    // ============================================================================
+    
+    #[msg("Dynamic fee bounds must satisfy min <= max <= 1000 bps, or both be 0 when disabled")]
+    InvalidDynamicFeeBounds,
+    
+    // ============================================================================
+    // Transaction Deadline Errors
+    // ============================================================================
+    
+    #[msg("Transaction deadline has passed")]
+    DeadlineExceeded,
+    
+    // ============================================================================
+    // Account Validation Errors
+    // ============================================================================
+    
+    #[msg("Vault does not belong to this pool")]
+    InvalidVault,
+    
+    #[msg("Account does not belong to this pool")]
+    PoolMismatch,
+    
+    #[msg("Mint is not the configured RUSH mint")]
+    InvalidRushMint,
+    
+    // ============================================================================
+    // Module 3.5: Flash Loan Errors
+    // ============================================================================
+    
+    #[msg("Flash loans are disabled for this pool")]
+    FlashLoansDisabled,
+    
+    #[msg("Flash loan fee must be at most 1000 bps")]
+    InvalidFlashLoanFee,
+    
+    #[msg("Flash loan receiver cannot be this program")]
+    InvalidFlashLoanReceiver,
+    
+    #[msg("Flash loan was not repaid with its fee")]
+    FlashLoanNotRepaid,
+    
+    #[msg("Pool is locked by an outstanding flash loan")]
+    PoolLocked,
 }
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::state::{PoolStatus, PoolType};
+
 // ============================================================================
 // MODULE 2: LIQUIDITY POOL EVENTS
 // ============================================================================
@@ -13,7 +15,14 @@ pub struct PoolCreated {
     pub reserve_a: u64,
     pub reserve_b: u64,
     pub lp_token_supply: u64,
+    pub fee_tier_bps: u16,
     pub authority: Pubkey,
+    pub pool_type: PoolType,
+    pub amp_coefficient: u64,
+    pub weight_a_bps: u16,
+    pub weight_b_bps: u16,
+    pub tick_spacing: u16,
+    pub sqrt_price_x64: u128,
 }
 
 /// Event emitted when liquidity is added to a pool (Module 2.3)
@@ -29,6 +38,7 @@ pub struct LiquidityAdded {
 }
 
 /// Event emitted when liquidity is removed from a pool (Module 2.4)
+/// `output_mint` names the only token paid out by a single-sided withdrawal
 #[event]
 pub struct LiquidityRemoved {
     pub user: Pubkey,
@@ -38,6 +48,82 @@ pub struct LiquidityRemoved {
     pub amount_b_received: u64,
     pub new_reserve_a: u64,
     pub new_reserve_b: u64,
+    pub output_mint: Option<Pubkey>,
+}
+
+/// Event emitted when reserves are reset to the vault balances (Module 2.7)
+#[event]
+pub struct ReservesSynced {
+    pub pool: Pubkey,
+    pub previous_reserve_a: u64,
+    pub previous_reserve_b: u64,
+    pub new_reserve_a: u64,
+    pub new_reserve_b: u64,
+    pub synced_by: Pubkey,
+}
+
+/// Event emitted when surplus vault balances are sent out (Module 2.7)
+#[event]
+pub struct SurplusSkimmed {
+    pub pool: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub recipient_token_a: Pubkey,
+    pub recipient_token_b: Pubkey,
+    pub skimmed_by: Pubkey,
+}
+
+/// Event emitted when an empty pool is closed and its rent reclaimed (Module 2.10)
+#[event]
+pub struct PoolClosed {
+    pub pool: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub fee_tier_bps: u16,
+    pub swept_a: u64,
+    pub swept_b: u64,
+    pub closed_by: Pubkey,
+    pub closed_at: i64,
+}
+
+/// Event emitted when liquidity is added to a concentrated position (Module 2.13)
+#[event]
+pub struct ConcentratedLiquidityIncreased {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    pub liquidity_delta: u128,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub position_liquidity: u128,
+    pub active_liquidity: u128,
+}
+
+/// Event emitted when liquidity is withdrawn from a concentrated position (Module 2.13)
+#[event]
+pub struct ConcentratedLiquidityDecreased {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    pub liquidity_delta: u128,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub position_liquidity: u128,
+    pub active_liquidity: u128,
+}
+
+/// Event emitted when a concentrated position's swap fees are paid out (Module 2.13)
+#[event]
+pub struct ConcentratedFeesCollected {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub fees_a: u64,
+    pub fees_b: u64,
 }
 
 // ============================================================================
@@ -52,11 +138,24 @@ pub struct SwapExecuted {
     pub amount_in: u64,
     pub amount_out: u64,
     pub fee_amount: u64,
+    pub fee_bps: u16,
+    pub protocol_fee_amount: u64,
     pub is_a_to_b: bool,
     pub new_reserve_a: u64,
     pub new_reserve_b: u64,
 }
 
+/// Event emitted when accrued protocol fees are sent to the treasury (Module 5.2)
+#[event]
+pub struct ProtocolFeesCollected {
+    pub pool: Pubkey,
+    pub treasury: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub collected_by: Pubkey,
+    pub collected_at: i64,
+}
+
 /// Event emitted when a limit order is created (Module 3.4)
 #[event]
 pub struct LimitOrderCreated {
@@ -92,6 +191,31 @@ pub struct LimitOrderCancelled {
     pub cancelled_at: i64,
 }
 
+/// Event emitted when a flash loan is repaid (Module 3.5)
+/// `fee_amount` is what the vault grew by and was credited to reserves
+#[event]
+pub struct FlashLoanExecuted {
+    pub pool: Pubkey,
+    pub borrower: Pubkey,
+    pub receiver_program: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub fee_amount: u64,
+    pub new_reserve_a: u64,
+    pub new_reserve_b: u64,
+    pub executed_at: i64,
+}
+
+/// Event emitted when a pool authority changes the flash loan fee (Module 3.5)
+#[event]
+pub struct FlashLoanFeeUpdated {
+    pub pool: Pubkey,
+    pub previous_fee_bps: u16,
+    pub new_fee_bps: u16,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
 // ============================================================================
 // MODULE 4: RUSH REWARDS EVENTS
 // ============================================================================
@@ -117,8 +241,8 @@ pub struct RewardsClaimed {
     pub pool: Pubkey,
     pub rewards_amount: u64,
     pub rewards_display: f64,
-    pub time_elapsed: i64,
-    pub user_lp_share: f64,
+    pub staked_lp: u64,
+    pub total_staked_lp: u64,
     pub claimed_at: i64,
     pub total_claimed_lifetime: u64,
 }
@@ -141,3 +265,137 @@ pub struct RewardsPaused {
     pub paused_by: Pubkey,
     pub reason: String,
 }
+
+/// Event emitted when LP tokens are escrowed for RUSH rewards (Module 4.7)
+#[event]
+pub struct LpStaked {
+    pub user: Pubkey,
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub amount: u64,
+    pub staked_balance: u64,
+    pub total_staked: u64,
+    pub rewards_settled: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when staked LP tokens are returned to the owner (Module 4.7)
+#[event]
+pub struct LpUnstaked {
+    pub user: Pubkey,
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub amount: u64,
+    pub staked_balance: u64,
+    pub total_staked: u64,
+    pub rewards_settled: u64,
+    pub timestamp: i64,
+}
+
+// ============================================================================
+// MODULE 5: PROTOCOL CONFIGURATION EVENTS
+// ============================================================================
+
+/// Event emitted when the protocol config is initialized (Module 5.1)
+#[event]
+pub struct DexConfigInitialized {
+    pub dex_config: Pubkey,
+    pub authority: Pubkey,
+    pub treasury: Pubkey,
+    pub fee_tiers: Vec<u16>,
+}
+
+/// Event emitted when a fee tier is added to the allowlist (Module 5.1)
+#[event]
+pub struct FeeTierAdded {
+    pub fee_tier_bps: u16,
+    pub added_by: Pubkey,
+}
+
+/// Event emitted when a fee tier is removed from the allowlist (Module 5.1)
+#[event]
+pub struct FeeTierRemoved {
+    pub fee_tier_bps: u16,
+    pub removed_by: Pubkey,
+}
+
+/// Event emitted when a pool's protocol fee share changes (Module 5.2)
+#[event]
+pub struct ProtocolFeeShareUpdated {
+    pub pool: Pubkey,
+    pub previous_share_bps: u16,
+    pub new_share_bps: u16,
+    pub updated_by: Pubkey,
+}
+
+/// Event emitted when a new pool is appended to the on-chain registry (Module 5.3)
+#[event]
+pub struct PoolRegistered {
+    pub pool: Pubkey,
+    pub registry_page: Pubkey,
+    pub pool_index: u64,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub fee_tier_bps: u16,
+    pub created_at: i64,
+}
+
+/// Event emitted when a pre-versioning account is upgraded in place (Module 5.4)
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub account_type: String,
+    pub previous_size: u32,
+    pub new_size: u32,
+    pub version: u8,
+    pub migrated_by: Pubkey,
+}
+
+/// Event emitted when a pool authority changes the pool's emergency status (Module 2.9)
+#[event]
+pub struct PoolStatusChanged {
+    pub pool: Pubkey,
+    pub previous_status: PoolStatus,
+    pub new_status: PoolStatus,
+    pub changed_by: Pubkey,
+    pub changed_at: i64,
+}
+
+/// Event emitted when a pool authority changes dynamic fee mode (Module 2.14)
+#[event]
+pub struct DynamicFeeUpdated {
+    pub pool: Pubkey,
+    pub enabled: bool,
+    pub min_fee_bps: u16,
+    pub max_fee_bps: u16,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when an authority transfer is proposed (Module 5.5)
+/// `account` is the LiquidityPool or RushConfig whose admin is changing
+#[event]
+pub struct AuthorityProposed {
+    pub account: Pubkey,
+    pub current_authority: Pubkey,
+    pub proposed_authority: Pubkey,
+    pub proposed_at: i64,
+}
+
+/// Event emitted when the pending authority accepts the transfer (Module 5.5)
+#[event]
+pub struct AuthorityTransferred {
+    pub account: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub transferred_at: i64,
+}
+
+/// Event emitted when the current authority withdraws a pending proposal (Module 5.5)
+#[event]
+pub struct AuthorityProposalCancelled {
+    pub account: Pubkey,
+    pub authority: Pubkey,
+    pub cancelled_authority: Pubkey,
+    pub cancelled_at: i64,
+}
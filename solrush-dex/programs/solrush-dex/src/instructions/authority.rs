@@ -0,0 +1,225 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{LiquidityPool, RushConfig};
+use crate::errors::CustomError;
+use crate::events::{AuthorityProposed, AuthorityTransferred, AuthorityProposalCancelled};
+
+// ========================================================================
+// MODULE 5.5: TWO-STEP AUTHORITY TRANSFER
+// ========================================================================
+//
+// The current authority proposes a successor, which only takes over once it
+// signs accept_*. Until then the proposal can be replaced or cancelled, so a
+// mistyped key never receives admin rights.
+
+/// Propose a new pool authority (current pool authority only)
+pub fn propose_pool_authority(
+    ctx: Context<ManagePoolAuthority>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let account = ctx.accounts.pool.key();
+    let pool = &mut ctx.accounts.pool;
+    propose_authority(account, pool.authority, &mut pool.pending_authority, new_authority)
+}
+
+/// Accept a pending pool authority transfer (pending authority only)
+pub fn accept_pool_authority(ctx: Context<AcceptPoolAuthority>) -> Result<()> {
+    let account = ctx.accounts.pool.key();
+    let pool: &mut LiquidityPool = &mut ctx.accounts.pool;
+    accept_authority(
+        account,
+        &mut pool.authority,
+        &mut pool.pending_authority,
+        ctx.accounts.new_authority.key(),
+    )
+}
+
+/// Cancel a pending pool authority transfer (current pool authority only)
+pub fn cancel_pool_authority_transfer(ctx: Context<ManagePoolAuthority>) -> Result<()> {
+    let account = ctx.accounts.pool.key();
+    let pool = &mut ctx.accounts.pool;
+    cancel_authority_transfer(account, pool.authority, &mut pool.pending_authority)
+}
+
+/// Propose a new RUSH rewards authority (current authority only)
+pub fn propose_rush_authority(
+    ctx: Context<ManageRushAuthority>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let account = ctx.accounts.rush_config.key();
+    let rush_config = &mut ctx.accounts.rush_config;
+    propose_authority(
+        account,
+        rush_config.authority,
+        &mut rush_config.pending_authority,
+        new_authority,
+    )
+}
+
+/// Accept a pending RUSH rewards authority transfer (pending authority only)
+pub fn accept_rush_authority(ctx: Context<AcceptRushAuthority>) -> Result<()> {
+    let account = ctx.accounts.rush_config.key();
+    let rush_config: &mut RushConfig = &mut ctx.accounts.rush_config;
+    accept_authority(
+        account,
+        &mut rush_config.authority,
+        &mut rush_config.pending_authority,
+        ctx.accounts.new_authority.key(),
+    )
+}
+
+/// Cancel a pending RUSH rewards authority transfer (current authority only)
+pub fn cancel_rush_authority_transfer(ctx: Context<ManageRushAuthority>) -> Result<()> {
+    let account = ctx.accounts.rush_config.key();
+    let rush_config = &mut ctx.accounts.rush_config;
+    cancel_authority_transfer(account, rush_config.authority, &mut rush_config.pending_authority)
+}
+
+// ========================================================================
+// SHARED HELPERS
+// ========================================================================
+
+/// Record `new_authority` as pending; replaces any earlier proposal
+fn propose_authority(
+    account: Pubkey,
+    authority: Pubkey,
+    pending_authority: &mut Pubkey,
+    new_authority: Pubkey,
+) -> Result<()> {
+    require!(
+        new_authority != Pubkey::default() && new_authority != authority,
+        CustomError::InvalidProposedAuthority
+    );
+
+    *pending_authority = new_authority;
+
+    emit!(AuthorityProposed {
+        account,
+        current_authority: authority,
+        proposed_authority: new_authority,
+        proposed_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✓ Authority transfer proposed: {} -> {}", authority, new_authority);
+
+    Ok(())
+}
+
+/// Hand authority to the signer if it matches the pending proposal
+fn accept_authority(
+    account: Pubkey,
+    authority: &mut Pubkey,
+    pending_authority: &mut Pubkey,
+    signer: Pubkey,
+) -> Result<()> {
+    require!(
+        *pending_authority != Pubkey::default(),
+        CustomError::NoPendingAuthority
+    );
+    require_keys_eq!(signer, *pending_authority, CustomError::NotPendingAuthority);
+
+    let previous_authority = *authority;
+    *authority = signer;
+    *pending_authority = Pubkey::default();
+
+    emit!(AuthorityTransferred {
+        account,
+        previous_authority,
+        new_authority: signer,
+        transferred_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✓ Authority transferred: {} -> {}", previous_authority, signer);
+
+    Ok(())
+}
+
+/// Clear the pending proposal without changing the authority
+fn cancel_authority_transfer(
+    account: Pubkey,
+    authority: Pubkey,
+    pending_authority: &mut Pubkey,
+) -> Result<()> {
+    require!(
+        *pending_authority != Pubkey::default(),
+        CustomError::NoPendingAuthority
+    );
+
+    let cancelled_authority = *pending_authority;
+    *pending_authority = Pubkey::default();
+
+    emit!(AuthorityProposalCancelled {
+        account,
+        authority,
+        cancelled_authority,
+        cancelled_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✓ Authority transfer to {} cancelled", cancelled_authority);
+
+    Ok(())
+}
+
+// ============================================================================
+// CONTEXT STRUCTURES
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct ManagePoolAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = authority @ CustomError::InvalidAuthority
+    )]
+    pub pool: Account<'info, LiquidityPool>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptPoolAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, LiquidityPool>,
+
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageRushAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"rush_config"],
+        bump = rush_config.bump,
+        has_one = authority @ CustomError::InvalidAuthority
+    )]
+    pub rush_config: Account<'info, RushConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptRushAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"rush_config"],
+        bump = rush_config.bump
+    )]
+    pub rush_config: Account<'info, RushConfig>,
+
+    pub new_authority: Signer<'info>,
+}
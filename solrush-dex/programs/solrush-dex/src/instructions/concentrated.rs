@@ -0,0 +1,1299 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint,
+    TokenAccount,
+    TokenInterface,
+    TransferChecked,
+    transfer_checked,
+};
+
+use crate::state::{
+    LiquidityPool,
+    DexConfig,
+    PoolRegistry,
+    PoolRegistryPage,
+    ConcentratedPosition,
+    Tick,
+    TickArray,
+    FEE_DENOMINATOR_BPS,
+    PoolStatus,
+    PoolType,
+    MAX_TICK,
+    MAX_TICK_SPACING,
+    MIN_TICK,
+    TICK_ARRAY_SIZE,
+    ACCOUNT_VERSION,
+};
+use crate::errors::CustomError;
+use crate::events::{
+    PoolCreated,
+    SwapExecuted,
+    ConcentratedLiquidityIncreased,
+    ConcentratedLiquidityDecreased,
+    ConcentratedFeesCollected,
+};
+use crate::instructions::registry::register_pool;
+use crate::utils::{
+    add_liquidity_delta,
+    calculate_fee_growth,
+    calculate_fees_earned,
+    calculate_protocol_fee,
+    calculate_effective_fee_numerator,
+    calculate_spot_price,
+    update_volatility_accumulator,
+    calculate_amount_after_transfer_fee,
+    calculate_amount_before_transfer_fee,
+    compute_swap_step,
+    get_amounts_for_liquidity,
+    get_fee_growth_inside,
+    get_sqrt_price_at_tick,
+    get_tick_at_sqrt_price,
+    validate_supported_mint,
+    assert_vault_invariants,
+    MAX_SQRT_PRICE_X64,
+    MIN_SQRT_PRICE_X64,
+};
+
+// ========================================================================
+// MODULE 2.13: CONCENTRATED LIQUIDITY
+// ========================================================================
+//
+// Concentrated pools share the LiquidityPool account and PDA with the other
+// curves, so a pair supports one pool type per fee tier. Instead of LP tokens,
+// each ConcentratedPosition holds liquidity over a tick range; swap fees are
+// credited per unit of in-range liquidity through fee_growth_global_* and
+// collected per position. Tick state lives in TickArray accounts that callers
+// pass explicitly: the two arrays holding a position's bounds, or for swaps the
+// arrays the price walks through, in order, as remaining accounts.
+
+/// Create an empty concentrated liquidity pool at an allowlisted fee tier
+/// The initial price is supplied as sqrt(price) in Q64.64; liquidity is added
+/// afterwards through positions, so no deposit is taken here
+pub fn initialize_concentrated_pool(
+    ctx: Context<InitializeConcentratedPool>,
+    fee_tier_bps: u16,
+    tick_spacing: u16,
+    initial_sqrt_price_x64: u128,
+) -> Result<()> {
+    require!(
+        ctx.accounts.dex_config.is_fee_tier_allowed(fee_tier_bps),
+        CustomError::FeeTierNotAllowed
+    );
+    require!(
+        (1..=MAX_TICK_SPACING).contains(&tick_spacing),
+        CustomError::InvalidTickSpacing
+    );
+    validate_supported_mint(&ctx.accounts.token_a_mint)?;
+    validate_supported_mint(&ctx.accounts.token_b_mint)?;
+
+    // Also rejects prices outside [MIN_SQRT_PRICE_X64, MAX_SQRT_PRICE_X64)
+    let tick_current_index = get_tick_at_sqrt_price(initial_sqrt_price_x64)?;
+
+    let pool = &mut ctx.accounts.pool;
+
+    pool.authority = ctx.accounts.authority.key();
+    pool.token_a_mint = ctx.accounts.token_a_mint.key();
+    pool.token_b_mint = ctx.accounts.token_b_mint.key();
+    pool.token_a_vault = ctx.accounts.token_a_vault.key();
+    pool.token_b_vault = ctx.accounts.token_b_vault.key();
+    pool.lp_token_mint = Pubkey::default();
+
+    pool.fee_numerator = fee_tier_bps as u64;
+    pool.fee_denominator = FEE_DENOMINATOR_BPS;
+    pool.fee_tier_bps = fee_tier_bps;
+    pool.bump = ctx.bumps.pool;
+    pool.version = ACCOUNT_VERSION;
+    pool.status = PoolStatus::Active;
+    pool.pool_type = PoolType::Concentrated;
+    pool.tick_spacing = tick_spacing;
+    pool.tick_current_index = tick_current_index;
+    pool.sqrt_price_x64 = initial_sqrt_price_x64;
+
+    let pool_index = register_pool(
+        &mut ctx.accounts.pool_registry,
+        &mut ctx.accounts.registry_page,
+        ctx.bumps.registry_page,
+        pool.key(),
+        pool,
+    )?;
+
+    emit!(PoolCreated {
+        pool: pool.key(),
+        token_a_mint: ctx.accounts.token_a_mint.key(),
+        token_b_mint: ctx.accounts.token_b_mint.key(),
+        reserve_a: 0,
+        reserve_b: 0,
+        lp_token_supply: 0,
+        fee_tier_bps,
+        authority: ctx.accounts.authority.key(),
+        pool_type: PoolType::Concentrated,
+        amp_coefficient: 0,
+        weight_a_bps: 0,
+        weight_b_bps: 0,
+        tick_spacing,
+        sqrt_price_x64: initial_sqrt_price_x64,
+    });
+
+    msg!(
+        "✓ Concentrated pool initialized: Tick={} | Spacing={} | Fee={} bps | Registry index={}",
+        tick_current_index,
+        tick_spacing,
+        fee_tier_bps,
+        pool_index
+    );
+    Ok(())
+}
+
+/// Allocate the tick array starting at `start_tick_index`
+/// Anyone may pay for a tick array; it must exist before a position can use
+/// one of its ticks or a swap can walk through it
+pub fn initialize_tick_array(ctx: Context<InitializeTickArray>, start_tick_index: i32) -> Result<()> {
+    let span = tick_array_span(ctx.accounts.pool.tick_spacing);
+    require!(
+        start_tick_index.rem_euclid(span) == 0
+            && start_tick_index + span > MIN_TICK
+            && start_tick_index <= MAX_TICK,
+        CustomError::InvalidTickIndex
+    );
+
+    let tick_array = &mut ctx.accounts.tick_array;
+    tick_array.pool = ctx.accounts.pool.key();
+    tick_array.start_tick_index = start_tick_index;
+    tick_array.ticks = [Tick::default(); TICK_ARRAY_SIZE];
+    tick_array.bump = ctx.bumps.tick_array;
+    tick_array.version = ACCOUNT_VERSION;
+
+    msg!("✓ Tick array initialized at tick {}", start_tick_index);
+    Ok(())
+}
+
+/// Open an empty position over [tick_lower_index, tick_upper_index)
+/// Both bounds must be multiples of the pool's tick spacing
+pub fn open_concentrated_position(
+    ctx: Context<OpenConcentratedPosition>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+) -> Result<()> {
+    let tick_spacing = ctx.accounts.pool.tick_spacing as i32;
+    require!(tick_lower_index < tick_upper_index, CustomError::InvalidTickRange);
+    require!(
+        tick_lower_index >= MIN_TICK
+            && tick_upper_index <= MAX_TICK
+            && tick_lower_index % tick_spacing == 0
+            && tick_upper_index % tick_spacing == 0,
+        CustomError::InvalidTickIndex
+    );
+
+    let position = &mut ctx.accounts.position;
+    position.owner = ctx.accounts.owner.key();
+    position.pool = ctx.accounts.pool.key();
+    position.tick_lower_index = tick_lower_index;
+    position.tick_upper_index = tick_upper_index;
+    position.bump = ctx.bumps.position;
+    position.version = ACCOUNT_VERSION;
+
+    msg!("✓ Concentrated position opened: [{}, {})", tick_lower_index, tick_upper_index);
+    Ok(())
+}
+
+/// Add `liquidity_amount` to a position
+/// The token amounts follow from the range and the current price (token A
+/// only below the range, token B only above it) and are rounded up; max
+/// amounts bound what leaves the user's wallet, including transfer fees
+pub fn increase_concentrated_liquidity(
+    ctx: Context<ModifyConcentratedLiquidity>,
+    liquidity_amount: u128,
+    max_amount_a: u64,
+    max_amount_b: u64,
+) -> Result<()> {
+    require!(liquidity_amount > 0, CustomError::InvalidAmount);
+    let liquidity_delta = i128::try_from(liquidity_amount)
+        .map_err(|_| error!(CustomError::CalculationOverflow))?;
+
+    let pool = &mut ctx.accounts.pool;
+    require!(pool.status.allows_deposits(), CustomError::PoolDepositsDisabled);
+    require!(!pool.locked, CustomError::PoolLocked);
+
+    let position = &mut ctx.accounts.position;
+    modify_position(
+        pool,
+        position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        liquidity_delta,
+    )?;
+
+    let (amount_a, amount_b) = get_amounts_for_liquidity(
+        pool.sqrt_price_x64,
+        pool.tick_current_index,
+        position.tick_lower_index,
+        position.tick_upper_index,
+        liquidity_amount,
+        true,
+    )?;
+    require!(amount_a > 0 || amount_b > 0, CustomError::InvalidAmount);
+
+    // Transfer-fee mints: send enough that the vaults receive the full amounts owed
+    let send_a = calculate_amount_before_transfer_fee(&ctx.accounts.token_a_mint, amount_a)?;
+    let send_b = calculate_amount_before_transfer_fee(&ctx.accounts.token_b_mint, amount_b)?;
+    require!(
+        send_a <= max_amount_a && send_b <= max_amount_b,
+        CustomError::SlippageTooHigh
+    );
+    require!(
+        ctx.accounts.user_token_a.amount >= send_a && ctx.accounts.user_token_b.amount >= send_b,
+        CustomError::InsufficientBalance
+    );
+
+    if send_a > 0 {
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_a_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_token_a.to_account_info(),
+                    mint: ctx.accounts.token_a_mint.to_account_info(),
+                    to: ctx.accounts.token_a_vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            send_a,
+            ctx.accounts.token_a_mint.decimals,
+        )?;
+    }
+
+    if send_b > 0 {
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_b_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_token_b.to_account_info(),
+                    mint: ctx.accounts.token_b_mint.to_account_info(),
+                    to: ctx.accounts.token_b_vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            send_b,
+            ctx.accounts.token_b_mint.decimals,
+        )?;
+    }
+
+    pool.reserve_a = pool
+        .reserve_a
+        .checked_add(amount_a)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    pool.reserve_b = pool
+        .reserve_b
+        .checked_add(amount_b)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
+    assert_vault_invariants(
+        pool,
+        &mut ctx.accounts.token_a_vault,
+        &mut ctx.accounts.token_b_vault,
+    )?;
+
+    emit!(ConcentratedLiquidityIncreased {
+        owner: ctx.accounts.owner.key(),
+        pool: pool.key(),
+        position: position.key(),
+        tick_lower_index: position.tick_lower_index,
+        tick_upper_index: position.tick_upper_index,
+        liquidity_delta: liquidity_amount,
+        amount_a,
+        amount_b,
+        position_liquidity: position.liquidity,
+        active_liquidity: pool.active_liquidity,
+    });
+
+    msg!(
+        "✓ Concentrated liquidity added: L={} | A={} | B={} | Position L={}",
+        liquidity_amount,
+        amount_a,
+        amount_b,
+        position.liquidity
+    );
+    Ok(())
+}
+
+/// Withdraw `liquidity_amount` from a position and pay out its tokens
+/// Amounts are rounded down; fees earned so far stay owed to the position
+/// until collect_concentrated_fees. Minimums apply to what the user receives
+pub fn decrease_concentrated_liquidity(
+    ctx: Context<ModifyConcentratedLiquidity>,
+    liquidity_amount: u128,
+    min_amount_a: u64,
+    min_amount_b: u64,
+) -> Result<()> {
+    require!(liquidity_amount > 0, CustomError::InvalidAmount);
+    require!(
+        ctx.accounts.pool.status.allows_withdrawals(),
+        CustomError::PoolWithdrawalsDisabled
+    );
+    require!(!ctx.accounts.pool.locked, CustomError::PoolLocked);
+    require!(
+        ctx.accounts.position.liquidity >= liquidity_amount,
+        CustomError::InsufficientPositionLiquidity
+    );
+    let liquidity_delta = i128::try_from(liquidity_amount)
+        .map_err(|_| error!(CustomError::CalculationOverflow))?
+        .checked_neg()
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
+    let pool = &mut ctx.accounts.pool;
+    let position = &mut ctx.accounts.position;
+    modify_position(
+        pool,
+        position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        liquidity_delta,
+    )?;
+
+    let (amount_a, amount_b) = get_amounts_for_liquidity(
+        pool.sqrt_price_x64,
+        pool.tick_current_index,
+        position.tick_lower_index,
+        position.tick_upper_index,
+        liquidity_amount,
+        false,
+    )?;
+
+    // Slippage protection is checked against what the user receives after transfer fees
+    let received_a = calculate_amount_after_transfer_fee(&ctx.accounts.token_a_mint, amount_a)?;
+    let received_b = calculate_amount_after_transfer_fee(&ctx.accounts.token_b_mint, amount_b)?;
+    require!(
+        received_a >= min_amount_a && received_b >= min_amount_b,
+        CustomError::SlippageTooHigh
+    );
+
+    pool.reserve_a = pool
+        .reserve_a
+        .checked_sub(amount_a)
+        .ok_or(error!(CustomError::InsufficientPoolReserves))?;
+    pool.reserve_b = pool
+        .reserve_b
+        .checked_sub(amount_b)
+        .ok_or(error!(CustomError::InsufficientPoolReserves))?;
+
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    if amount_a > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_a_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.token_a_vault.to_account_info(),
+                    mint: ctx.accounts.token_a_mint.to_account_info(),
+                    to: ctx.accounts.user_token_a.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_a,
+            ctx.accounts.token_a_mint.decimals,
+        )?;
+    }
+
+    if amount_b > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_b_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.token_b_vault.to_account_info(),
+                    mint: ctx.accounts.token_b_mint.to_account_info(),
+                    to: ctx.accounts.user_token_b.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_b,
+            ctx.accounts.token_b_mint.decimals,
+        )?;
+    }
+
+    assert_vault_invariants(
+        pool,
+        &mut ctx.accounts.token_a_vault,
+        &mut ctx.accounts.token_b_vault,
+    )?;
+
+    emit!(ConcentratedLiquidityDecreased {
+        owner: ctx.accounts.owner.key(),
+        pool: pool.key(),
+        position: position.key(),
+        tick_lower_index: position.tick_lower_index,
+        tick_upper_index: position.tick_upper_index,
+        liquidity_delta: liquidity_amount,
+        amount_a,
+        amount_b,
+        position_liquidity: position.liquidity,
+        active_liquidity: pool.active_liquidity,
+    });
+
+    msg!(
+        "✓ Concentrated liquidity removed: L={} | A={} | B={} | Position L={}",
+        liquidity_amount,
+        amount_a,
+        amount_b,
+        position.liquidity
+    );
+    Ok(())
+}
+
+/// Pay out the swap fees a position has earned
+/// Fees are first brought up to date against the position's tick range
+pub fn collect_concentrated_fees(ctx: Context<ModifyConcentratedLiquidity>) -> Result<()> {
+    require!(
+        ctx.accounts.pool.status.allows_withdrawals(),
+        CustomError::PoolWithdrawalsDisabled
+    );
+    require!(!ctx.accounts.pool.locked, CustomError::PoolLocked);
+
+    let pool = &mut ctx.accounts.pool;
+    let position = &mut ctx.accounts.position;
+    if position.liquidity > 0 {
+        modify_position(
+            pool,
+            position,
+            &ctx.accounts.tick_array_lower,
+            &ctx.accounts.tick_array_upper,
+            0,
+        )?;
+    }
+
+    let fees_a = position.fees_owed_a;
+    let fees_b = position.fees_owed_b;
+    require!(fees_a > 0 || fees_b > 0, CustomError::InvalidAmount);
+
+    position.fees_owed_a = 0;
+    position.fees_owed_b = 0;
+    pool.reserve_a = pool
+        .reserve_a
+        .checked_sub(fees_a)
+        .ok_or(error!(CustomError::InsufficientPoolReserves))?;
+    pool.reserve_b = pool
+        .reserve_b
+        .checked_sub(fees_b)
+        .ok_or(error!(CustomError::InsufficientPoolReserves))?;
+
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    if fees_a > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_a_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.token_a_vault.to_account_info(),
+                    mint: ctx.accounts.token_a_mint.to_account_info(),
+                    to: ctx.accounts.user_token_a.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            fees_a,
+            ctx.accounts.token_a_mint.decimals,
+        )?;
+    }
+
+    if fees_b > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_b_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.token_b_vault.to_account_info(),
+                    mint: ctx.accounts.token_b_mint.to_account_info(),
+                    to: ctx.accounts.user_token_b.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            fees_b,
+            ctx.accounts.token_b_mint.decimals,
+        )?;
+    }
+
+    assert_vault_invariants(
+        pool,
+        &mut ctx.accounts.token_a_vault,
+        &mut ctx.accounts.token_b_vault,
+    )?;
+
+    emit!(ConcentratedFeesCollected {
+        owner: ctx.accounts.owner.key(),
+        pool: pool.key(),
+        position: position.key(),
+        fees_a,
+        fees_b,
+    });
+
+    msg!("✓ Concentrated fees collected: A={} | B={}", fees_a, fees_b);
+    Ok(())
+}
+
+/// Close an emptied position and return its rent to the owner
+pub fn close_concentrated_position(ctx: Context<CloseConcentratedPosition>) -> Result<()> {
+    let position = &ctx.accounts.position;
+    require!(
+        position.liquidity == 0 && position.fees_owed_a == 0 && position.fees_owed_b == 0,
+        CustomError::PositionNotEmpty
+    );
+
+    msg!("✓ Concentrated position closed: {}", position.key());
+    Ok(())
+}
+
+/// Exact-input swap against a concentrated pool
+/// Remaining accounts are the tick arrays the price may move through: the
+/// first holds the current price and each next one is adjacent in the swap
+/// direction. The swap fails rather than stopping early if they run out
+pub fn swap_concentrated<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapConcentrated<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    is_a_to_b: bool,
+) -> Result<()> {
+    require!(amount_in > 0, CustomError::InvalidAmount);
+
+    let pool = &mut ctx.accounts.pool;
+    require!(pool.status.allows_swaps(), CustomError::PoolSwapsDisabled);
+    require!(!pool.locked, CustomError::PoolLocked);
+
+    let (mint_in, mint_out) = if is_a_to_b {
+        (&ctx.accounts.token_a_mint, &ctx.accounts.token_b_mint)
+    } else {
+        (&ctx.accounts.token_b_mint, &ctx.accounts.token_a_mint)
+    };
+    let (user_token_in, user_token_out) = if is_a_to_b {
+        (&ctx.accounts.user_token_a, &ctx.accounts.user_token_b)
+    } else {
+        (&ctx.accounts.user_token_b, &ctx.accounts.user_token_a)
+    };
+    require!(user_token_in.amount >= amount_in, CustomError::InsufficientBalance);
+
+    // The curve runs on what the vault receives after any transfer fee
+    let amount_in_received = calculate_amount_after_transfer_fee(mint_in, amount_in)?;
+    require!(amount_in_received > 0, CustomError::InvalidAmount);
+
+    let pool_key = pool.key();
+    let mut tick_arrays = ctx
+        .remaining_accounts
+        .iter()
+        .map(|info| Ok((info, load_tick_array(info, &pool_key)?)))
+        .collect::<Result<Vec<_>>>()?;
+    require!(!tick_arrays.is_empty(), CustomError::TickArrayMissing);
+
+    let tick_spacing = pool.tick_spacing as i32;
+    let span = tick_array_span(pool.tick_spacing);
+    for pair in tick_arrays.windows(2) {
+        let expected_start = if is_a_to_b {
+            pair[0].1.start_tick_index - span
+        } else {
+            pair[0].1.start_tick_index + span
+        };
+        require!(
+            pair[1].1.start_tick_index == expected_start,
+            CustomError::InvalidTickArray
+        );
+    }
+
+    // Dynamic fee mode prices the whole swap on volatility before it
+    let now = Clock::get()?.unix_timestamp;
+    let fee_numerator = calculate_effective_fee_numerator(pool, now);
+    let price_before = calculate_spot_price(pool)?;
+
+    let mut amount_remaining = amount_in_received;
+    let mut amount_out: u64 = 0;
+    let mut fee_amount: u64 = 0;
+    let mut protocol_fee: u64 = 0;
+    let mut sqrt_price = pool.sqrt_price_x64;
+    let mut tick_current = pool.tick_current_index;
+    let mut liquidity = pool.active_liquidity;
+    let mut fee_growth_global_a = pool.fee_growth_global_a;
+    let mut fee_growth_global_b = pool.fee_growth_global_b;
+    let mut array_index = 0;
+
+    // The first array must hold the starting search tick so no initialized tick is skipped
+    require!(
+        tick_array_covers(
+            &tick_arrays[0].1,
+            search_tick_index(tick_current, tick_spacing, is_a_to_b),
+            span
+        ),
+        CustomError::InvalidTickArray
+    );
+
+    while amount_remaining > 0 {
+        let search_tick = search_tick_index(tick_current, tick_spacing, is_a_to_b);
+        if !tick_array_covers(&tick_arrays[array_index].1, search_tick, span) {
+            // Arrays are contiguous, so the next one picks up where this one ended
+            array_index += 1;
+            require!(array_index < tick_arrays.len(), CustomError::TickArrayMissing);
+        }
+
+        let (next_tick, initialized) = next_initialized_tick(
+            &tick_arrays[array_index].1,
+            search_tick,
+            tick_spacing,
+            is_a_to_b,
+        );
+        let target_tick = next_tick.clamp(MIN_TICK, MAX_TICK);
+        let sqrt_price_target = get_sqrt_price_at_tick(target_tick)?;
+
+        let step = compute_swap_step(
+            sqrt_price,
+            sqrt_price_target,
+            liquidity,
+            amount_remaining,
+            fee_numerator,
+            pool.fee_denominator,
+        )?;
+        amount_remaining -= step.amount_in + step.fee_amount;
+        amount_out = amount_out
+            .checked_add(step.amount_out)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+
+        // LP share of the fee accrues to in-range liquidity; the protocol share is held aside
+        let step_protocol_fee = calculate_protocol_fee(step.fee_amount, pool.protocol_fee_share_bps)?;
+        let fee_growth = calculate_fee_growth(step.fee_amount - step_protocol_fee, liquidity);
+        if is_a_to_b {
+            fee_growth_global_a = fee_growth_global_a.wrapping_add(fee_growth);
+        } else {
+            fee_growth_global_b = fee_growth_global_b.wrapping_add(fee_growth);
+        }
+        fee_amount += step.fee_amount;
+        protocol_fee += step_protocol_fee;
+
+        sqrt_price = step.sqrt_price_next;
+        require!(
+            sqrt_price > MIN_SQRT_PRICE_X64 && sqrt_price < MAX_SQRT_PRICE_X64,
+            CustomError::InsufficientLiquidity
+        );
+
+        if sqrt_price == sqrt_price_target {
+            if initialized {
+                let tick_array = &mut tick_arrays[array_index].1;
+                let offset = tick_offset(tick_array, target_tick, pool.tick_spacing)?;
+                let liquidity_net = cross_tick(
+                    &mut tick_array.ticks[offset],
+                    fee_growth_global_a,
+                    fee_growth_global_b,
+                );
+                let liquidity_delta = if is_a_to_b {
+                    liquidity_net
+                        .checked_neg()
+                        .ok_or(error!(CustomError::CalculationOverflow))?
+                } else {
+                    liquidity_net
+                };
+                liquidity = add_liquidity_delta(liquidity, liquidity_delta)?;
+            }
+            tick_current = if is_a_to_b { target_tick - 1 } else { target_tick };
+        } else {
+            tick_current = get_tick_at_sqrt_price(sqrt_price)?;
+        }
+    }
+
+    // Slippage protection is checked against what the user receives after transfer fees
+    let amount_out_received = calculate_amount_after_transfer_fee(mint_out, amount_out)?;
+    require!(
+        amount_out_received >= minimum_amount_out,
+        CustomError::SlippageTooHigh
+    );
+
+    pool.sqrt_price_x64 = sqrt_price;
+    pool.tick_current_index = tick_current;
+    pool.active_liquidity = liquidity;
+    pool.fee_growth_global_a = fee_growth_global_a;
+    pool.fee_growth_global_b = fee_growth_global_b;
+    update_volatility_accumulator(pool, price_before, now)?;
+
+    let amount_in_to_reserve = amount_in_received - protocol_fee;
+    if is_a_to_b {
+        pool.reserve_a = pool
+            .reserve_a
+            .checked_add(amount_in_to_reserve)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+        pool.protocol_fees_a = pool
+            .protocol_fees_a
+            .checked_add(protocol_fee)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+        pool.reserve_b = pool
+            .reserve_b
+            .checked_sub(amount_out)
+            .ok_or(error!(CustomError::InsufficientPoolReserves))?;
+    } else {
+        pool.reserve_b = pool
+            .reserve_b
+            .checked_add(amount_in_to_reserve)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+        pool.protocol_fees_b = pool
+            .protocol_fees_b
+            .checked_add(protocol_fee)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+        pool.reserve_a = pool
+            .reserve_a
+            .checked_sub(amount_out)
+            .ok_or(error!(CustomError::InsufficientPoolReserves))?;
+    }
+
+    for (info, tick_array) in tick_arrays.iter().take(array_index + 1) {
+        store_tick_array(info, tick_array)?;
+    }
+
+    let (vault_in, vault_out) = if is_a_to_b {
+        (&ctx.accounts.token_a_vault, &ctx.accounts.token_b_vault)
+    } else {
+        (&ctx.accounts.token_b_vault, &ctx.accounts.token_a_vault)
+    };
+    let (token_in_program, token_out_program) = if is_a_to_b {
+        (&ctx.accounts.token_a_program, &ctx.accounts.token_b_program)
+    } else {
+        (&ctx.accounts.token_b_program, &ctx.accounts.token_a_program)
+    };
+
+    transfer_checked(
+        CpiContext::new(
+            token_in_program.to_account_info(),
+            TransferChecked {
+                from: user_token_in.to_account_info(),
+                mint: mint_in.to_account_info(),
+                to: vault_in.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount_in,
+        mint_in.decimals,
+    )?;
+
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    if amount_out > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                token_out_program.to_account_info(),
+                TransferChecked {
+                    from: vault_out.to_account_info(),
+                    mint: mint_out.to_account_info(),
+                    to: user_token_out.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_out,
+            mint_out.decimals,
+        )?;
+    }
+
+    assert_vault_invariants(
+        pool,
+        &mut ctx.accounts.token_a_vault,
+        &mut ctx.accounts.token_b_vault,
+    )?;
+
+    emit!(SwapExecuted {
+        user: ctx.accounts.user.key(),
+        pool: pool_key,
+        amount_in: amount_in_received,
+        amount_out,
+        fee_amount,
+        fee_bps: fee_numerator as u16,
+        protocol_fee_amount: protocol_fee,
+        is_a_to_b,
+        new_reserve_a: pool.reserve_a,
+        new_reserve_b: pool.reserve_b,
+    });
+
+    msg!(
+        "✓ Concentrated swap executed: Direction={} | In={} | Out={} | Fee={} | Tick={}",
+        if is_a_to_b { "A→B" } else { "B→A" },
+        amount_in,
+        amount_out,
+        fee_amount,
+        tick_current
+    );
+    Ok(())
+}
+
+// ========================================================================
+// TICK HELPERS
+// ========================================================================
+
+/// Number of ticks covered by one tick array
+fn tick_array_span(tick_spacing: u16) -> i32 {
+    tick_spacing as i32 * TICK_ARRAY_SIZE as i32
+}
+
+fn tick_array_covers(tick_array: &TickArray, tick_index: i32, span: i32) -> bool {
+    tick_index >= tick_array.start_tick_index && tick_index < tick_array.start_tick_index + span
+}
+
+/// Usable tick a swap step searches from: at or below the current tick for
+/// A to B, the next one above it for B to A
+fn search_tick_index(tick_current: i32, tick_spacing: i32, is_a_to_b: bool) -> i32 {
+    let usable_tick = tick_current.div_euclid(tick_spacing) * tick_spacing;
+    if is_a_to_b {
+        usable_tick
+    } else {
+        usable_tick + tick_spacing
+    }
+}
+
+/// Slot of a usable tick within its tick array
+fn tick_offset(tick_array: &TickArray, tick_index: i32, tick_spacing: u16) -> Result<usize> {
+    let spacing = tick_spacing as i32;
+    let distance = tick_index - tick_array.start_tick_index;
+    require!(
+        distance >= 0 && distance % spacing == 0 && distance / spacing < TICK_ARRAY_SIZE as i32,
+        CustomError::InvalidTickArray
+    );
+    Ok((distance / spacing) as usize)
+}
+
+/// Tick arrays are passed unchecked so one account can serve both position
+/// bounds; ownership, discriminator and pool are verified here instead
+fn load_tick_array(info: &AccountInfo, pool: &Pubkey) -> Result<TickArray> {
+    require_keys_eq!(*info.owner, crate::ID, CustomError::InvalidTickArray);
+    let data = info.try_borrow_data()?;
+    let tick_array = TickArray::try_deserialize(&mut &data[..])?;
+    require_keys_eq!(tick_array.pool, *pool, CustomError::InvalidTickArray);
+    Ok(tick_array)
+}
+
+fn store_tick_array(info: &AccountInfo, tick_array: &TickArray) -> Result<()> {
+    let mut data = info.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data[..];
+    tick_array.try_serialize(&mut writer)
+}
+
+/// Nearest initialized tick in the swap direction within one tick array,
+/// starting at `search_tick`; falls back to the array's last tick in that
+/// direction, reported as uninitialized, so the swap moves on to the next array
+fn next_initialized_tick(
+    tick_array: &TickArray,
+    search_tick: i32,
+    tick_spacing: i32,
+    is_a_to_b: bool,
+) -> (i32, bool) {
+    let start = tick_array.start_tick_index;
+    let offset = ((search_tick - start) / tick_spacing) as usize;
+
+    let found = if is_a_to_b {
+        (0..=offset).rev().find(|&i| tick_array.ticks[i].initialized)
+    } else {
+        (offset..TICK_ARRAY_SIZE).find(|&i| tick_array.ticks[i].initialized)
+    };
+
+    match found {
+        Some(i) => (start + i as i32 * tick_spacing, true),
+        None if is_a_to_b => (start, false),
+        None => (start + (TICK_ARRAY_SIZE as i32 - 1) * tick_spacing, false),
+    }
+}
+
+/// Apply a liquidity change to one of a position's boundary ticks
+fn update_tick(
+    tick: &mut Tick,
+    tick_index: i32,
+    tick_current: i32,
+    fee_growth_global_a: u128,
+    fee_growth_global_b: u128,
+    liquidity_delta: i128,
+    is_upper: bool,
+) -> Result<()> {
+    if tick.liquidity_gross == 0 {
+        // By convention all fee growth before a tick is initialized happened below it
+        if tick_index <= tick_current {
+            tick.fee_growth_outside_a = fee_growth_global_a;
+            tick.fee_growth_outside_b = fee_growth_global_b;
+        }
+        tick.initialized = true;
+    }
+
+    tick.liquidity_gross = add_liquidity_delta(tick.liquidity_gross, liquidity_delta)?;
+    tick.liquidity_net = if is_upper {
+        tick.liquidity_net.checked_sub(liquidity_delta)
+    } else {
+        tick.liquidity_net.checked_add(liquidity_delta)
+    }
+    .ok_or(error!(CustomError::CalculationOverflow))?;
+
+    Ok(())
+}
+
+/// Flip a tick's outside fee growth as the price crosses it; returns liquidity_net
+fn cross_tick(tick: &mut Tick, fee_growth_global_a: u128, fee_growth_global_b: u128) -> i128 {
+    tick.fee_growth_outside_a = fee_growth_global_a.wrapping_sub(tick.fee_growth_outside_a);
+    tick.fee_growth_outside_b = fee_growth_global_b.wrapping_sub(tick.fee_growth_outside_b);
+    tick.liquidity_net
+}
+
+/// Apply `liquidity_delta` to a position, its boundary ticks and the pool's
+/// active liquidity. Fees earned at the old liquidity are settled into
+/// fees_owed first; a zero delta only settles fees
+fn modify_position(
+    pool: &mut LiquidityPool,
+    position: &mut ConcentratedPosition,
+    tick_array_lower: &AccountInfo,
+    tick_array_upper: &AccountInfo,
+    liquidity_delta: i128,
+) -> Result<()> {
+    let same_array = tick_array_lower.key() == tick_array_upper.key();
+    let mut lower_array = load_tick_array(tick_array_lower, &position.pool)?;
+    let mut upper_array = if same_array {
+        None
+    } else {
+        Some(load_tick_array(tick_array_upper, &position.pool)?)
+    };
+    let lower_offset = tick_offset(&lower_array, position.tick_lower_index, pool.tick_spacing)?;
+    let upper_offset = tick_offset(
+        upper_array.as_ref().unwrap_or(&lower_array),
+        position.tick_upper_index,
+        pool.tick_spacing,
+    )?;
+
+    if liquidity_delta != 0 {
+        update_tick(
+            &mut lower_array.ticks[lower_offset],
+            position.tick_lower_index,
+            pool.tick_current_index,
+            pool.fee_growth_global_a,
+            pool.fee_growth_global_b,
+            liquidity_delta,
+            false,
+        )?;
+        update_tick(
+            &mut upper_array.as_mut().unwrap_or(&mut lower_array).ticks[upper_offset],
+            position.tick_upper_index,
+            pool.tick_current_index,
+            pool.fee_growth_global_a,
+            pool.fee_growth_global_b,
+            liquidity_delta,
+            true,
+        )?;
+    }
+
+    let (fee_growth_inside_a, fee_growth_inside_b) = get_fee_growth_inside(
+        position.tick_lower_index,
+        &lower_array.ticks[lower_offset],
+        position.tick_upper_index,
+        &upper_array.as_ref().unwrap_or(&lower_array).ticks[upper_offset],
+        pool.tick_current_index,
+        pool.fee_growth_global_a,
+        pool.fee_growth_global_b,
+    );
+
+    let fees_a = calculate_fees_earned(
+        position.liquidity,
+        fee_growth_inside_a,
+        position.fee_growth_inside_last_a,
+    )?;
+    let fees_b = calculate_fees_earned(
+        position.liquidity,
+        fee_growth_inside_b,
+        position.fee_growth_inside_last_b,
+    )?;
+    position.fees_owed_a = position
+        .fees_owed_a
+        .checked_add(fees_a)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    position.fees_owed_b = position
+        .fees_owed_b
+        .checked_add(fees_b)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    position.fee_growth_inside_last_a = fee_growth_inside_a;
+    position.fee_growth_inside_last_b = fee_growth_inside_b;
+
+    if liquidity_delta == 0 {
+        return Ok(());
+    }
+
+    position.liquidity = add_liquidity_delta(position.liquidity, liquidity_delta)?;
+    if (position.tick_lower_index..position.tick_upper_index).contains(&pool.tick_current_index) {
+        pool.active_liquidity = add_liquidity_delta(pool.active_liquidity, liquidity_delta)?;
+    }
+
+    // Ticks no longer referenced by any position are cleared for reuse
+    if lower_array.ticks[lower_offset].liquidity_gross == 0 {
+        lower_array.ticks[lower_offset] = Tick::default();
+    }
+    let upper_tick = &mut upper_array.as_mut().unwrap_or(&mut lower_array).ticks[upper_offset];
+    if upper_tick.liquidity_gross == 0 {
+        *upper_tick = Tick::default();
+    }
+
+    store_tick_array(tick_array_lower, &lower_array)?;
+    if let Some(upper_array) = &upper_array {
+        store_tick_array(tick_array_upper, upper_array)?;
+    }
+
+    Ok(())
+}
+
+// ========================================================================
+// ACCOUNT CONTEXTS
+// ========================================================================
+
+#[derive(Accounts)]
+#[instruction(fee_tier_bps: u16)]
+pub struct InitializeConcentratedPool<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = LiquidityPool::SIZE,
+        seeds = [
+            b"pool",
+            token_a_mint.key().as_ref(),
+            token_b_mint.key().as_ref(),
+            fee_tier_bps.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    #[account(seeds = [b"dex_config"], bump = dex_config.bump)]
+    pub dex_config: Box<Account<'info, DexConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_registry"],
+        bump = pool_registry.bump
+    )]
+    pub pool_registry: Box<Account<'info, PoolRegistry>>,
+
+    /// Registry page the new pool is appended to, allocated on first use
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = PoolRegistryPage::SIZE,
+        seeds = [
+            b"registry_page",
+            pool_registry.current_page_index().to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub registry_page: Box<Account<'info, PoolRegistryPage>>,
+
+    /// Mints are ordered by bytes so each pair maps to exactly one pool per fee tier
+    #[account(
+        mint::token_program = token_a_program,
+        constraint = token_a_mint.key() < token_b_mint.key() @ CustomError::InvalidMintOrder
+    )]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = token_a_mint,
+        token::authority = pool,
+        token::token_program = token_a_program
+    )]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = token_b_mint,
+        token::authority = pool,
+        token::token_program = token_b_program
+    )]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_a_program: Interface<'info, TokenInterface>,
+    pub token_b_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(start_tick_index: i32)]
+pub struct InitializeTickArray<'info> {
+    #[account(
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        constraint = pool.pool_type == PoolType::Concentrated @ CustomError::UnsupportedPoolType
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = TickArray::SIZE,
+        seeds = [b"tick_array", pool.key().as_ref(), start_tick_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tick_array: Box<Account<'info, TickArray>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(tick_lower_index: i32, tick_upper_index: i32)]
+pub struct OpenConcentratedPosition<'info> {
+    #[account(
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        constraint = pool.pool_type == PoolType::Concentrated @ CustomError::UnsupportedPoolType
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ConcentratedPosition::SIZE,
+        seeds = [
+            b"cl_position",
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            tick_lower_index.to_le_bytes().as_ref(),
+            tick_upper_index.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub position: Box<Account<'info, ConcentratedPosition>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Shared by increase_concentrated_liquidity, decrease_concentrated_liquidity
+/// and collect_concentrated_fees
+#[derive(Accounts)]
+pub struct ModifyConcentratedLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            token_a_mint.key().as_ref(),
+            token_b_mint.key().as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = token_a_vault,
+        has_one = token_b_vault,
+        constraint = pool.pool_type == PoolType::Concentrated @ CustomError::UnsupportedPoolType
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    #[account(mut, has_one = pool, has_one = owner)]
+    pub position: Box<Account<'info, ConcentratedPosition>>,
+
+    /// CHECK: Tick array holding the position's lower tick; validated by load_tick_array
+    #[account(mut)]
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Tick array holding the position's upper tick (may equal tick_array_lower); validated by load_tick_array
+    #[account(mut)]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = token_a_mint,
+        token::authority = owner,
+        token::token_program = token_a_program
+    )]
+    pub user_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = token_b_mint,
+        token::authority = owner,
+        token::token_program = token_b_program
+    )]
+    pub user_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub owner: Signer<'info>,
+    pub token_a_program: Interface<'info, TokenInterface>,
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseConcentratedPosition<'info> {
+    #[account(mut, has_one = owner, close = owner)]
+    pub position: Box<Account<'info, ConcentratedPosition>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SwapConcentrated<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            token_a_mint.key().as_ref(),
+            token_b_mint.key().as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = token_a_vault,
+        has_one = token_b_vault,
+        constraint = pool.pool_type == PoolType::Concentrated @ CustomError::UnsupportedPoolType
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = token_a_mint,
+        token::authority = user,
+        token::token_program = token_a_program
+    )]
+    pub user_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = token_b_mint,
+        token::authority = user,
+        token::token_program = token_b_program
+    )]
+    pub user_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub user: Signer<'info>,
+    pub token_a_program: Interface<'info, TokenInterface>,
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
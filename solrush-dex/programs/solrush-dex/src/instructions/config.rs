@@ -0,0 +1,357 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint,
+    TokenAccount,
+    TokenInterface,
+    TransferChecked,
+    transfer_checked,
+};
+
+use crate::state::{DexConfig, LiquidityPool, MAX_FEE_TIERS};
+use crate::errors::CustomError;
+use crate::program::SolrushDex;
+use crate::utils::assert_vault_invariants;
+use crate::events::{
+    DexConfigInitialized,
+    FeeTierAdded,
+    FeeTierRemoved,
+    ProtocolFeeShareUpdated,
+    ProtocolFeesCollected,
+};
+
+// ========================================================================
+// MODULE 5.1: INITIALIZE PROTOCOL CONFIG
+// ========================================================================
+
+/// Initialize the global protocol config with the default fee tiers
+/// Gated on the program's upgrade authority so the admin role cannot be front-run
+pub fn initialize_dex_config(
+    ctx: Context<InitializeDexConfig>,
+    treasury: Pubkey,
+) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_config;
+
+    dex_config.authority = ctx.accounts.authority.key();
+    dex_config.treasury = treasury;
+    dex_config.fee_tiers = [0; MAX_FEE_TIERS];
+    dex_config.fee_tiers[..DexConfig::DEFAULT_FEE_TIERS.len()]
+        .copy_from_slice(&DexConfig::DEFAULT_FEE_TIERS);
+    dex_config.fee_tier_count = DexConfig::DEFAULT_FEE_TIERS.len() as u8;
+    dex_config.bump = ctx.bumps.dex_config;
+
+    emit!(DexConfigInitialized {
+        dex_config: dex_config.key(),
+        authority: dex_config.authority,
+        treasury,
+        fee_tiers: dex_config.active_fee_tiers().to_vec(),
+    });
+
+    msg!(
+        "✓ Protocol config initialized: Fee tiers (bps)={:?}",
+        dex_config.active_fee_tiers()
+    );
+
+    Ok(())
+}
+
+// ========================================================================
+// MODULE 5.1: FEE TIER ALLOWLIST (admin only)
+// ========================================================================
+
+/// Add a fee tier to the allowlist
+pub fn add_fee_tier(
+    ctx: Context<UpdateFeeTiers>,
+    fee_tier_bps: u16,
+) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_config;
+
+    require!(
+        fee_tier_bps > 0 && fee_tier_bps <= DexConfig::MAX_FEE_TIER_BPS,
+        CustomError::InvalidFeeParameters
+    );
+    require!(
+        !dex_config.is_fee_tier_allowed(fee_tier_bps),
+        CustomError::FeeTierAlreadyExists
+    );
+
+    let count = dex_config.fee_tier_count as usize;
+    require!(count < MAX_FEE_TIERS, CustomError::FeeTierListFull);
+
+    dex_config.fee_tiers[count] = fee_tier_bps;
+    dex_config.fee_tier_count += 1;
+
+    emit!(FeeTierAdded {
+        fee_tier_bps,
+        added_by: ctx.accounts.authority.key(),
+    });
+
+    msg!("✓ Fee tier added: {} bps", fee_tier_bps);
+
+    Ok(())
+}
+
+/// Remove a fee tier from the allowlist
+/// Existing pools at this tier keep trading; only new pools are affected
+pub fn remove_fee_tier(
+    ctx: Context<UpdateFeeTiers>,
+    fee_tier_bps: u16,
+) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_config;
+
+    let count = dex_config.fee_tier_count as usize;
+    let index = dex_config
+        .active_fee_tiers()
+        .iter()
+        .position(|tier| *tier == fee_tier_bps)
+        .ok_or(error!(CustomError::FeeTierNotAllowed))?;
+
+    // Swap-remove keeps the active entries packed at the front
+    dex_config.fee_tiers[index] = dex_config.fee_tiers[count - 1];
+    dex_config.fee_tiers[count - 1] = 0;
+    dex_config.fee_tier_count -= 1;
+
+    emit!(FeeTierRemoved {
+        fee_tier_bps,
+        removed_by: ctx.accounts.authority.key(),
+    });
+
+    msg!("✓ Fee tier removed: {} bps", fee_tier_bps);
+
+    Ok(())
+}
+
+// ========================================================================
+// MODULE 5.2: PROTOCOL FEE SWITCH (admin only)
+// ========================================================================
+
+/// Set the share of each swap fee a pool routes to the protocol
+/// 0 turns the switch off; the rest of the fee always stays with LPs
+pub fn set_protocol_fee_share(
+    ctx: Context<SetProtocolFeeShare>,
+    protocol_fee_share_bps: u16,
+) -> Result<()> {
+    require!(
+        protocol_fee_share_bps <= DexConfig::MAX_PROTOCOL_FEE_SHARE_BPS,
+        CustomError::InvalidProtocolFeeShare
+    );
+
+    let pool = &mut ctx.accounts.pool;
+    let previous_share_bps = pool.protocol_fee_share_bps;
+    pool.protocol_fee_share_bps = protocol_fee_share_bps;
+
+    emit!(ProtocolFeeShareUpdated {
+        pool: pool.key(),
+        previous_share_bps,
+        new_share_bps: protocol_fee_share_bps,
+        updated_by: ctx.accounts.authority.key(),
+    });
+
+    msg!(
+        "✓ Protocol fee share updated: {} bps -> {} bps",
+        previous_share_bps,
+        protocol_fee_share_bps
+    );
+
+    Ok(())
+}
+
+/// Transfer accrued protocol fees from the pool vaults to the treasury
+pub fn collect_protocol_fees(
+    ctx: Context<CollectProtocolFees>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let amount_a = pool.protocol_fees_a;
+    let amount_b = pool.protocol_fees_b;
+    require!(!pool.locked, CustomError::PoolLocked);
+
+    require!(amount_a > 0 || amount_b > 0, CustomError::NoProtocolFees);
+
+    let pool_key = pool.key();
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    if amount_a > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_a_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.token_a_vault.to_account_info(),
+                    mint: ctx.accounts.token_a_mint.to_account_info(),
+                    to: ctx.accounts.treasury_token_a.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_a,
+            ctx.accounts.token_a_mint.decimals,
+        )?;
+    }
+
+    if amount_b > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_b_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.token_b_vault.to_account_info(),
+                    mint: ctx.accounts.token_b_mint.to_account_info(),
+                    to: ctx.accounts.treasury_token_b.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_b,
+            ctx.accounts.token_b_mint.decimals,
+        )?;
+    }
+
+    pool.protocol_fees_a = 0;
+    pool.protocol_fees_b = 0;
+
+    assert_vault_invariants(
+        pool,
+        &mut ctx.accounts.token_a_vault,
+        &mut ctx.accounts.token_b_vault,
+    )?;
+
+    emit!(ProtocolFeesCollected {
+        pool: pool_key,
+        treasury: ctx.accounts.dex_config.treasury,
+        amount_a,
+        amount_b,
+        collected_by: ctx.accounts.authority.key(),
+        collected_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "✓ Protocol fees collected: A={} | B={}",
+        amount_a,
+        amount_b
+    );
+
+    Ok(())
+}
+
+// ========================================================================
+// ACCOUNT CONTEXTS
+// ========================================================================
+
+#[derive(Accounts)]
+pub struct InitializeDexConfig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = DexConfig::SIZE,
+        seeds = [b"dex_config"],
+        bump
+    )]
+    pub dex_config: Account<'info, DexConfig>,
+
+    /// Only the program's upgrade authority may become the protocol admin
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, SolrushDex>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ CustomError::InvalidAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFeeTiers<'info> {
+    #[account(
+        mut,
+        seeds = [b"dex_config"],
+        bump = dex_config.bump,
+        has_one = authority @ CustomError::InvalidAuthority
+    )]
+    pub dex_config: Account<'info, DexConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetProtocolFeeShare<'info> {
+    #[account(
+        seeds = [b"dex_config"],
+        bump = dex_config.bump,
+        has_one = authority @ CustomError::InvalidAuthority
+    )]
+    pub dex_config: Account<'info, DexConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, LiquidityPool>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CollectProtocolFees<'info> {
+    #[account(
+        seeds = [b"dex_config"],
+        bump = dex_config.bump,
+        has_one = authority @ CustomError::InvalidAuthority
+    )]
+    pub dex_config: Box<Account<'info, DexConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = token_a_vault,
+        has_one = token_b_vault
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    #[account(
+        address = pool.token_a_mint,
+        mint::token_program = token_a_program
+    )]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        address = pool.token_b_mint,
+        mint::token_program = token_b_program
+    )]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = pool.token_a_mint,
+        constraint = treasury_token_a.owner == dex_config.treasury @ CustomError::InvalidTreasury
+    )]
+    pub treasury_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = pool.token_b_mint,
+        constraint = treasury_token_b.owner == dex_config.treasury @ CustomError::InvalidTreasury
+    )]
+    pub treasury_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub authority: Signer<'info>,
+    pub token_a_program: Interface<'info, TokenInterface>,
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
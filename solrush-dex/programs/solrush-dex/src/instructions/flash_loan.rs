@@ -0,0 +1,262 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+use anchor_spl::token_interface::{
+    Mint,
+    TokenAccount,
+    TokenInterface,
+    TransferChecked,
+    transfer_checked,
+};
+
+use crate::state::{LiquidityPool, PoolType, MAX_FLASH_LOAN_FEE_BPS};
+use crate::errors::CustomError;
+use crate::events::{FlashLoanExecuted, FlashLoanFeeUpdated};
+use crate::utils::{accrue_lp_fee_growth, assert_vault_invariants, calculate_flash_loan_fee};
+
+// ========================================================================
+// MODULE 3.5: FLASH LOANS
+// ========================================================================
+
+/// Lend from a pool vault for the duration of one CPI into the receiver program
+///
+/// The principal is sent to `borrower_token_account`, then the receiver is invoked
+/// with every remaining account (signer and writable flags preserved) and data
+/// `amount (u64 LE) || fee (u64 LE) || receiver_data`. Before returning, the receiver
+/// must transfer the principal plus fee back into the vault; whatever the vault grew
+/// by is credited to reserves as LP fees. The pool is locked while the receiver runs,
+/// so no swap, deposit or withdrawal can touch its reserves mid-loan.
+pub fn flash_loan<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FlashLoan<'info>>,
+    amount: u64,
+    is_token_a: bool,
+    receiver_data: Vec<u8>,
+) -> Result<()> {
+    require!(amount > 0, CustomError::InvalidAmount);
+
+    let pool = &mut ctx.accounts.pool;
+    require!(pool.status.allows_swaps(), CustomError::PoolSwapsDisabled);
+    require!(!pool.locked, CustomError::PoolLocked);
+    // Concentrated fees accrue per unit of in-range liquidity, not to reserves
+    require!(
+        pool.pool_type != PoolType::Concentrated,
+        CustomError::UnsupportedPoolType
+    );
+    require!(pool.flash_loan_fee_bps > 0, CustomError::FlashLoansDisabled);
+
+    // Only curve reserves are lent; uncollected protocol fees stay in the vault
+    let reserve = if is_token_a { pool.reserve_a } else { pool.reserve_b };
+    require!(amount <= reserve, CustomError::InsufficientLiquidity);
+
+    let fee_amount = calculate_flash_loan_fee(amount, pool.flash_loan_fee_bps)?;
+
+    let vault = if is_token_a {
+        &ctx.accounts.token_a_vault
+    } else {
+        &ctx.accounts.token_b_vault
+    };
+    let balance_before = vault.amount;
+    let required_balance = balance_before
+        .checked_add(fee_amount)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
+    // Persist the lock before any CPI so a re-entrant call sees it
+    pool.locked = true;
+    pool.exit(&crate::ID)?;
+
+    let pool_key = pool.key();
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: vault.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.borrower_token_account.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    // The receiver runs without the pool's signature, so it can only repay
+    let mut data = Vec::with_capacity(16 + receiver_data.len());
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&fee_amount.to_le_bytes());
+    data.extend_from_slice(&receiver_data);
+
+    let receiver_accounts = ctx
+        .remaining_accounts
+        .iter()
+        .map(|info| AccountMeta {
+            pubkey: info.key(),
+            is_signer: info.is_signer,
+            is_writable: info.is_writable,
+        })
+        .collect();
+    let mut receiver_infos = ctx.remaining_accounts.to_vec();
+    receiver_infos.push(ctx.accounts.receiver_program.to_account_info());
+
+    invoke(
+        &Instruction {
+            program_id: ctx.accounts.receiver_program.key(),
+            accounts: receiver_accounts,
+            data,
+        },
+        &receiver_infos,
+    )?;
+
+    // Verify repayment against the vault's actual balance
+    let vault = if is_token_a {
+        &mut ctx.accounts.token_a_vault
+    } else {
+        &mut ctx.accounts.token_b_vault
+    };
+    vault.reload()?;
+    require!(
+        vault.amount >= required_balance,
+        CustomError::FlashLoanNotRepaid
+    );
+    let fee_received = vault.amount - balance_before;
+
+    // The whole fee belongs to LPs
+    if is_token_a {
+        pool.reserve_a = pool
+            .reserve_a
+            .checked_add(fee_received)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+    } else {
+        pool.reserve_b = pool
+            .reserve_b
+            .checked_add(fee_received)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+    }
+    accrue_lp_fee_growth(pool, fee_received, is_token_a);
+    pool.locked = false;
+
+    assert_vault_invariants(
+        pool,
+        &mut ctx.accounts.token_a_vault,
+        &mut ctx.accounts.token_b_vault,
+    )?;
+
+    emit!(FlashLoanExecuted {
+        pool: pool_key,
+        borrower: ctx.accounts.borrower.key(),
+        receiver_program: ctx.accounts.receiver_program.key(),
+        mint: ctx.accounts.token_mint.key(),
+        amount,
+        fee_amount: fee_received,
+        new_reserve_a: pool.reserve_a,
+        new_reserve_b: pool.reserve_b,
+        executed_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "⚡ Flash loan repaid: Token={} | Amount={} | Fee={}",
+        if is_token_a { "A" } else { "B" },
+        amount,
+        fee_received
+    );
+
+    Ok(())
+}
+
+/// Set the flash loan fee in bps of the principal (pool authority only)
+/// 0 disables flash loans, which is the state every pool starts in
+pub fn set_flash_loan_fee(ctx: Context<SetFlashLoanFee>, fee_bps: u16) -> Result<()> {
+    require!(
+        fee_bps <= MAX_FLASH_LOAN_FEE_BPS,
+        CustomError::InvalidFlashLoanFee
+    );
+
+    let pool = &mut ctx.accounts.pool;
+    let previous_fee_bps = pool.flash_loan_fee_bps;
+    pool.flash_loan_fee_bps = fee_bps;
+
+    emit!(FlashLoanFeeUpdated {
+        pool: pool.key(),
+        previous_fee_bps,
+        new_fee_bps: fee_bps,
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✓ Flash loan fee: {} -> {} bps", previous_fee_bps, fee_bps);
+
+    Ok(())
+}
+
+// ========================================================================
+// ACCOUNT CONTEXTS
+// ========================================================================
+
+#[derive(Accounts)]
+#[instruction(amount: u64, is_token_a: bool)]
+pub struct FlashLoan<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = token_a_vault @ CustomError::InvalidVault,
+        has_one = token_b_vault @ CustomError::InvalidVault
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+    
+    #[account(mut)]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Mint of the borrowed token (token A or B per is_token_a)
+    #[account(
+        address = if is_token_a { pool.token_a_mint } else { pool.token_b_mint } @ CustomError::InvalidTokenMint,
+        mint::token_program = token_program
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// Receives the principal; may belong to the receiver program
+    #[account(mut, token::mint = token_mint)]
+    pub borrower_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// CHECK: Any executable program except this one; it is only invoked and
+    /// must repay the vault before returning
+    #[account(
+        executable,
+        constraint = receiver_program.key() != crate::ID @ CustomError::InvalidFlashLoanReceiver
+    )]
+    pub receiver_program: UncheckedAccount<'info>,
+    
+    pub borrower: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetFlashLoanFee<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = authority @ CustomError::InvalidAuthority
+    )]
+    pub pool: Account<'info, LiquidityPool>,
+    
+    pub authority: Signer<'info>,
+}
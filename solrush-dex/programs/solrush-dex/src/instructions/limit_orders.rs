@@ -1,13 +1,29 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Token, TokenAccount, Mint, Transfer, transfer},
+    token_interface::{
+        Mint,
+        TokenAccount,
+        TokenInterface,
+        TransferChecked,
+        transfer_checked,
+    },
 };
 
-use crate::state::{LiquidityPool, LimitOrder, OrderStatus};
+use crate::state::{LiquidityPool, LimitOrder, OrderStatus, ACCOUNT_VERSION};
 use crate::errors::CustomError;
 use crate::events::{LimitOrderCreated, LimitOrderExecuted, LimitOrderCancelled};
-use crate::utils::{calculate_output_amount, calculate_pool_price, check_price_condition};
+use crate::utils::{
+    calculate_swap_output,
+    calculate_protocol_fee,
+    calculate_spot_price,
+    calculate_effective_fee_numerator,
+    update_volatility_accumulator,
+    accrue_lp_fee_growth,
+    check_price_condition,
+    calculate_amount_after_transfer_fee,
+    assert_vault_invariants,
+};
 
 // ========================================================================
 // MODULE 3.4: CREATE LIMIT ORDER
@@ -33,6 +49,13 @@ pub fn create_limit_order(
         CustomError::InsufficientBalance
     );
 
+    // The order tracks what lands in escrow, net of any transfer fee
+    let escrowed_amount = calculate_amount_after_transfer_fee(
+        &ctx.accounts.sell_token_mint,
+        sell_amount,
+    )?;
+    require!(escrowed_amount > 0, CustomError::InvalidAmount);
+
     // Create limit order account
     let order = &mut ctx.accounts.limit_order;
     let now = Clock::get()?.unix_timestamp;
@@ -41,25 +64,28 @@ pub fn create_limit_order(
     order.pool = ctx.accounts.pool.key();
     order.sell_token = ctx.accounts.user_token_in.mint;
     order.buy_token = ctx.accounts.user_token_out.mint;
-    order.sell_amount = sell_amount;
+    order.sell_amount = escrowed_amount;
     order.target_price = target_price;
     order.minimum_receive = minimum_receive;
     order.created_at = now;
     order.expires_at = now + (expiry_days * 86400); // 86400 seconds per day
     order.status = OrderStatus::Pending;
     order.bump = ctx.bumps.limit_order;
+    order.version = ACCOUNT_VERSION;
 
     // Transfer sell tokens from user to escrow vault
-    transfer(
+    transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.user_token_in.to_account_info(),
+                mint: ctx.accounts.sell_token_mint.to_account_info(),
                 to: ctx.accounts.order_vault.to_account_info(),
                 authority: ctx.accounts.user.to_account_info(),
             },
         ),
         sell_amount,
+        ctx.accounts.sell_token_mint.decimals,
     )?;
 
     emit!(LimitOrderCreated {
@@ -68,7 +94,7 @@ pub fn create_limit_order(
         pool: ctx.accounts.pool.key(),
         sell_token: ctx.accounts.user_token_in.mint,
         buy_token: ctx.accounts.user_token_out.mint,
-        sell_amount,
+        sell_amount: escrowed_amount,
         target_price,
         minimum_receive,
         expires_at: order.expires_at,
@@ -97,6 +123,10 @@ pub fn execute_limit_order(
     let pool = &mut ctx.accounts.pool;
     let now = Clock::get()?.unix_timestamp;
 
+    // Fills trade against the pool, so they stop with swaps
+    require!(pool.status.allows_swaps(), CustomError::PoolSwapsDisabled);
+    require!(!pool.locked, CustomError::PoolLocked);
+
     // Verify order status
     require!(
         order.status == OrderStatus::Pending,
@@ -107,7 +137,7 @@ pub fn execute_limit_order(
     require!(now < order.expires_at, CustomError::OrderExpired);
 
     // Get current pool price
-    let current_price = calculate_pool_price(pool.reserve_a, pool.reserve_b)?;
+    let current_price = calculate_spot_price(pool)?;
 
     // Determine if this is a sell order (selling token_a) or buy order
     let is_sell = order.sell_token == pool.token_a_mint;
@@ -118,26 +148,47 @@ pub fn execute_limit_order(
         CustomError::PriceConditionNotMet
     );
 
-    // Calculate output amount using AMM
-    let output_amount = calculate_output_amount(
+    // Moving the escrow into the pool may incur another transfer fee
+    let amount_in_received = calculate_amount_after_transfer_fee(
+        &ctx.accounts.sell_token_mint,
         order.sell_amount,
-        if is_sell { pool.reserve_a } else { pool.reserve_b },
-        if is_sell { pool.reserve_b } else { pool.reserve_a },
-        pool.fee_numerator,
-        pool.fee_denominator,
     )?;
 
-    // Verify meets minimum receive requirement
+    // Calculate output amount on the pool's curve, at the dynamic fee if enabled
+    let fee_numerator = calculate_effective_fee_numerator(pool, now);
+    let output_amount = calculate_swap_output(pool, amount_in_received, is_sell, fee_numerator)?;
+
+    // Verify the owner receives at least the minimum after transfer fees
+    let output_received = calculate_amount_after_transfer_fee(
+        &ctx.accounts.buy_token_mint,
+        output_amount,
+    )?;
     require!(
-        output_amount >= order.minimum_receive,
+        output_received >= order.minimum_receive,
         CustomError::SlippageTooHigh
     );
 
+    let fee_amount = (amount_in_received as u128)
+        .checked_mul(fee_numerator as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))?
+        .checked_div(pool.fee_denominator as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))? as u64;
+
+    // Fills pay the protocol share of the fee exactly like swaps
+    let protocol_fee = calculate_protocol_fee(fee_amount, pool.protocol_fee_share_bps)?;
+    let amount_in_to_reserve = amount_in_received
+        .checked_sub(protocol_fee)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
     // Update pool reserves
     if is_sell {
         pool.reserve_a = pool
             .reserve_a
-            .checked_add(order.sell_amount)
+            .checked_add(amount_in_to_reserve)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+        pool.protocol_fees_a = pool
+            .protocol_fees_a
+            .checked_add(protocol_fee)
             .ok_or(error!(CustomError::CalculationOverflow))?;
         pool.reserve_b = pool
             .reserve_b
@@ -146,39 +197,74 @@ pub fn execute_limit_order(
     } else {
         pool.reserve_b = pool
             .reserve_b
-            .checked_add(order.sell_amount)
+            .checked_add(amount_in_to_reserve)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+        pool.protocol_fees_b = pool
+            .protocol_fees_b
+            .checked_add(protocol_fee)
             .ok_or(error!(CustomError::CalculationOverflow))?;
         pool.reserve_a = pool
             .reserve_a
             .checked_sub(output_amount)
             .ok_or(error!(CustomError::InsufficientPoolReserves))?;
     }
+    // LPs earn the fee net of the protocol share
+    accrue_lp_fee_growth(pool, fee_amount - protocol_fee, is_sell);
+    update_volatility_accumulator(pool, current_price, now)?;
+
+    // Move escrowed sell tokens into the pool vault
+    let owner_key = order.owner;
+    let order_bump = order.bump;
+    let order_pool_key = order.pool;
+    let order_signer_seeds: &[&[&[u8]]] = &[&[
+        b"limit_order",
+        order_pool_key.as_ref(),
+        owner_key.as_ref(),
+        &[order_bump],
+    ]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.sell_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.order_vault.to_account_info(),
+                mint: ctx.accounts.sell_token_mint.to_account_info(),
+                to: ctx.accounts.pool_vault_in.to_account_info(),
+                authority: order.to_account_info(),
+            },
+            order_signer_seeds,
+        ),
+        order.sell_amount,
+        ctx.accounts.sell_token_mint.decimals,
+    )?;
 
     // Transfer output tokens to order owner
     let pool_key = pool.key();
-    let token_a_mint = pool.token_a_mint;
-    let token_b_mint = pool.token_b_mint;
-    let bump_seed = pool.bump;
-    let signer_seeds: &[&[&[u8]]] = &[&[
-        b"pool",
-        token_a_mint.as_ref(),
-        token_b_mint.as_ref(),
-        &[bump_seed],
-    ]];
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.buy_token_program.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.pool_vault_out.to_account_info(),
+                mint: ctx.accounts.buy_token_mint.to_account_info(),
                 to: ctx.accounts.user_token_out.to_account_info(),
                 authority: pool.to_account_info(),
             },
             signer_seeds,
         ),
         output_amount,
+        ctx.accounts.buy_token_mint.decimals,
     )?;
 
+    let (vault_a, vault_b) = if is_sell {
+        (&mut ctx.accounts.pool_vault_in, &mut ctx.accounts.pool_vault_out)
+    } else {
+        (&mut ctx.accounts.pool_vault_out, &mut ctx.accounts.pool_vault_in)
+    };
+    assert_vault_invariants(pool, vault_a, vault_b)?;
+
     // Update order status
     order.status = OrderStatus::Executed;
 
@@ -187,7 +273,7 @@ pub fn execute_limit_order(
         owner: order.owner,
         pool: pool_key,
         sell_amount: order.sell_amount,
-        receive_amount: output_amount,
+        receive_amount: output_received,
         execution_price: current_price,
         executed_at: now,
     });
@@ -195,7 +281,7 @@ pub fn execute_limit_order(
     msg!(
         "✅ Limit order executed: Sold={} | Received={} | Price={}",
         order.sell_amount,
-        output_amount,
+        output_received,
         current_price
     );
 
@@ -213,12 +299,6 @@ pub fn cancel_limit_order(
 ) -> Result<()> {
     let order = &mut ctx.accounts.limit_order;
 
-    // Verify caller is order owner
-    require!(
-        ctx.accounts.user.key() == order.owner,
-        CustomError::UnauthorizedOrderOwner
-    );
-
     // Verify order is still pending
     require!(
         order.status == OrderStatus::Pending,
@@ -240,17 +320,19 @@ pub fn cancel_limit_order(
         &[bump_seed],
     ]];
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.order_vault.to_account_info(),
+                mint: ctx.accounts.sell_token_mint.to_account_info(),
                 to: ctx.accounts.user_token_in.to_account_info(),
                 authority: order.to_account_info(),
             },
             signer_seeds,
         ),
         order.sell_amount,
+        ctx.accounts.sell_token_mint.decimals,
     )?;
 
     // Update order status
@@ -278,7 +360,16 @@ pub fn cancel_limit_order(
 #[derive(Accounts)]
 #[instruction(sell_amount: u64, target_price: u64, minimum_receive: u64, expiry_days: i64)]
 pub struct CreateLimitOrder<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump
+    )]
     pub pool: Account<'info, LiquidityPool>,
     
     #[account(
@@ -290,25 +381,45 @@ pub struct CreateLimitOrder<'info> {
     )]
     pub limit_order: Account<'info, LimitOrder>,
     
-    pub sell_token_mint: Account<'info, Mint>,
+    /// Orders sell one side of the pool for the other
+    #[account(
+        mint::token_program = token_program,
+        constraint = sell_token_mint.key() == pool.token_a_mint
+            || sell_token_mint.key() == pool.token_b_mint @ CustomError::InvalidTokenMint
+    )]
+    pub sell_token_mint: InterfaceAccount<'info, Mint>,
     
-    #[account(mut, token::mint = sell_token_mint, token::authority = user)]
-    pub user_token_in: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = sell_token_mint,
+        token::authority = user,
+        token::token_program = token_program
+    )]
+    pub user_token_in: InterfaceAccount<'info, TokenAccount>,
     
-    #[account(mut)]
-    pub user_token_out: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::authority = user,
+        constraint = user_token_out.mint == if sell_token_mint.key() == pool.token_a_mint {
+            pool.token_b_mint
+        } else {
+            pool.token_a_mint
+        } @ CustomError::InvalidTokenMint
+    )]
+    pub user_token_out: InterfaceAccount<'info, TokenAccount>,
     
     #[account(
         init,
         payer = user,
         token::mint = sell_token_mint,
-        token::authority = limit_order
+        token::authority = limit_order,
+        token::token_program = token_program
     )]
-    pub order_vault: Account<'info, TokenAccount>,
+    pub order_vault: InterfaceAccount<'info, TokenAccount>,
     
     #[account(mut)]
     pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
@@ -316,33 +427,110 @@ pub struct CreateLimitOrder<'info> {
 
 #[derive(Accounts)]
 pub struct ExecuteLimitOrder<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump
+    )]
     pub pool: Account<'info, LiquidityPool>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"limit_order", pool.key().as_ref(), limit_order.owner.as_ref()],
+        bump = limit_order.bump,
+        has_one = pool @ CustomError::PoolMismatch
+    )]
     pub limit_order: Account<'info, LimitOrder>,
     
-    #[account(mut)]
-    pub user_token_out: Account<'info, TokenAccount>,
+    /// Escrow holding the order's sell tokens
+    #[account(
+        mut,
+        token::mint = sell_token_mint,
+        token::authority = limit_order
+    )]
+    pub order_vault: InterfaceAccount<'info, TokenAccount>,
     
-    #[account(mut)]
-    pub pool_vault_out: Account<'info, TokenAccount>,
+    /// Fills are permissionless, so proceeds can only go to the order owner
+    #[account(
+        mut,
+        token::mint = buy_token_mint,
+        constraint = user_token_out.owner == limit_order.owner @ CustomError::UnauthorizedOrderOwner
+    )]
+    pub user_token_out: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Pool vault receiving the sell tokens
+    #[account(
+        mut,
+        address = if limit_order.sell_token == pool.token_a_mint {
+            pool.token_a_vault
+        } else {
+            pool.token_b_vault
+        } @ CustomError::InvalidVault
+    )]
+    pub pool_vault_in: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = if limit_order.sell_token == pool.token_a_mint {
+            pool.token_b_vault
+        } else {
+            pool.token_a_vault
+        } @ CustomError::InvalidVault
+    )]
+    pub pool_vault_out: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        address = limit_order.sell_token,
+        mint::token_program = sell_token_program
+    )]
+    pub sell_token_mint: InterfaceAccount<'info, Mint>,
     
-    pub token_program: Program<'info, Token>,
+    #[account(
+        address = limit_order.buy_token,
+        mint::token_program = buy_token_program
+    )]
+    pub buy_token_mint: InterfaceAccount<'info, Mint>,
+    
+    pub sell_token_program: Interface<'info, TokenInterface>,
+    pub buy_token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 pub struct CancelLimitOrder<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"limit_order", limit_order.pool.as_ref(), user.key().as_ref()],
+        bump = limit_order.bump,
+        constraint = limit_order.owner == user.key() @ CustomError::UnauthorizedOrderOwner
+    )]
     pub limit_order: Account<'info, LimitOrder>,
     
-    #[account(mut)]
-    pub order_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = sell_token_mint,
+        token::authority = limit_order
+    )]
+    pub order_vault: InterfaceAccount<'info, TokenAccount>,
     
-    #[account(mut)]
-    pub user_token_in: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = sell_token_mint,
+        token::authority = user
+    )]
+    pub user_token_in: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        address = limit_order.sell_token,
+        mint::token_program = token_program
+    )]
+    pub sell_token_mint: InterfaceAccount<'info, Mint>,
     
     #[account(mut)]
     pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
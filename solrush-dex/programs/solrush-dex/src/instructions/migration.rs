@@ -0,0 +1,254 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+
+use crate::state::{
+    DexConfig,
+    LimitOrder,
+    LiquidityPool,
+    PoolRegistry,
+    PoolRegistryPage,
+    PoolStatus,
+    PoolType,
+    RushConfig,
+    UserLiquidityPosition,
+    ACCOUNT_VERSION,
+    FEE_DENOMINATOR_BPS,
+};
+use crate::errors::CustomError;
+use crate::events::AccountMigrated;
+use crate::instructions::registry::register_pool;
+
+// ========================================================================
+// MODULE 5.4: ACCOUNT MIGRATION
+// ========================================================================
+
+/// Upgrade a pre-versioning program account to the current layout (admin only)
+/// Grows the account to SIZE (new bytes are zero-filled), tops up rent from the
+/// authority and stamps ACCOUNT_VERSION. Existing field offsets are unchanged.
+///
+/// Pre-versioning pools are rewritten instead: fee_tier_bps now sits where
+/// `bump` used to be. They take fee tier LEGACY_FEE_TIER_BPS, keep their
+/// pre-fee-tier PDA `[b"pool", mint_a, mint_b]` (the only signer for their
+/// vaults and LP mint) through `legacy_pda`, and are appended to the registry.
+pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+    let account_info = ctx.accounts.account.to_account_info();
+    let previous_size = account_info.data_len();
+
+    let discriminator: [u8; 8] = {
+        let data = account_info.try_borrow_data()?;
+        require!(data.len() >= 8, CustomError::UnsupportedAccountType);
+        data[..8].try_into().unwrap()
+    };
+
+    let (account_type, legacy_size, new_size, stamp): (&str, usize, usize, StampFn) =
+        match discriminator {
+            d if d == LiquidityPool::DISCRIMINATOR => (
+                "LiquidityPool",
+                LiquidityPool::LEGACY_SIZE,
+                LiquidityPool::SIZE,
+                rewrite_legacy_pool,
+            ),
+            d if d == UserLiquidityPosition::DISCRIMINATOR => (
+                "UserLiquidityPosition",
+                UserLiquidityPosition::LEGACY_SIZE,
+                UserLiquidityPosition::SIZE,
+                |info| stamp_version::<UserLiquidityPosition>(info, |a| a.version = ACCOUNT_VERSION),
+            ),
+            d if d == LimitOrder::DISCRIMINATOR => (
+                "LimitOrder",
+                LimitOrder::LEGACY_SIZE,
+                LimitOrder::SIZE,
+                |info| stamp_version::<LimitOrder>(info, |a| a.version = ACCOUNT_VERSION),
+            ),
+            d if d == RushConfig::DISCRIMINATOR => (
+                "RushConfig",
+                RushConfig::LEGACY_SIZE,
+                RushConfig::SIZE,
+                |info| stamp_version::<RushConfig>(info, |a| a.version = ACCOUNT_VERSION),
+            ),
+            _ => return err!(CustomError::UnsupportedAccountType),
+        };
+
+    require!(previous_size != new_size, CustomError::AccountAlreadyMigrated);
+    require!(previous_size == legacy_size, CustomError::UnexpectedAccountSize);
+
+    // Keep the account rent-exempt at its new size
+    let required_lamports = Rent::get()?.minimum_balance(new_size);
+    let top_up = required_lamports.saturating_sub(account_info.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: account_info.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+
+    account_info.resize(new_size)?;
+
+    stamp(&account_info)?;
+
+    if discriminator == LiquidityPool::DISCRIMINATOR {
+        let pool = LiquidityPool::try_deserialize(&mut &account_info.try_borrow_data()?[..])?;
+        register_pool(
+            &mut ctx.accounts.pool_registry,
+            &mut ctx.accounts.registry_page,
+            ctx.bumps.registry_page,
+            account_info.key(),
+            &pool,
+        )?;
+    }
+
+    emit!(AccountMigrated {
+        account: account_info.key(),
+        account_type: account_type.to_string(),
+        previous_size: previous_size as u32,
+        new_size: new_size as u32,
+        version: ACCOUNT_VERSION,
+        migrated_by: ctx.accounts.authority.key(),
+    });
+
+    msg!(
+        "✓ Migrated {} {}: {} -> {} bytes (v{})",
+        account_type,
+        account_info.key(),
+        previous_size,
+        new_size,
+        ACCOUNT_VERSION
+    );
+
+    Ok(())
+}
+
+type StampFn = fn(&AccountInfo) -> Result<()>;
+
+/// Round-trip a resized account through its current layout and set the version
+/// Legacy fields decode at their original offsets; the grown tail reads as zeroes
+fn stamp_version<T: AccountSerialize + AccountDeserialize>(
+    account_info: &AccountInfo,
+    set_version: impl FnOnce(&mut T),
+) -> Result<()> {
+    let mut data = account_info.try_borrow_mut_data()?;
+    let mut account = T::try_deserialize(&mut &data[..])?;
+    set_version(&mut account);
+    let mut writer: &mut [u8] = &mut data[..];
+    account.try_serialize(&mut writer)
+}
+
+/// Pool layout before versioning and fee tiers, as the previous program wrote it
+#[derive(AnchorDeserialize)]
+struct LegacyLiquidityPool {
+    authority: Pubkey,
+    token_a_mint: Pubkey,
+    token_b_mint: Pubkey,
+    token_a_vault: Pubkey,
+    token_b_vault: Pubkey,
+    lp_token_mint: Pubkey,
+    reserve_a: u64,
+    reserve_b: u64,
+    total_lp_supply: u64,
+    fee_numerator: u64,
+    fee_denominator: u64,
+    bump: u8,
+}
+
+/// Rewrite a resized pre-versioning pool into the current layout
+/// Only pools with the fixed 3/1000 fee at their pre-fee-tier PDA are accepted
+fn rewrite_legacy_pool(account_info: &AccountInfo) -> Result<()> {
+    let mut data = account_info.try_borrow_mut_data()?;
+    let legacy = LegacyLiquidityPool::deserialize(&mut &data[8..LiquidityPool::LEGACY_SIZE])?;
+
+    require!(
+        legacy.fee_numerator == 3 && legacy.fee_denominator == 1000,
+        CustomError::LegacyPoolNotMigratable
+    );
+    let legacy_address = Pubkey::create_program_address(
+        &[
+            b"pool",
+            legacy.token_a_mint.as_ref(),
+            legacy.token_b_mint.as_ref(),
+            &[legacy.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| error!(CustomError::LegacyPoolNotMigratable))?;
+    require_keys_eq!(
+        legacy_address,
+        account_info.key(),
+        CustomError::LegacyPoolNotMigratable
+    );
+
+    // Start from an all-zero pool (Active, constant product) and carry the legacy fields over
+    data[8..].fill(0);
+    let mut pool = LiquidityPool::try_deserialize(&mut &data[..])?;
+    pool.authority = legacy.authority;
+    pool.token_a_mint = legacy.token_a_mint;
+    pool.token_b_mint = legacy.token_b_mint;
+    pool.token_a_vault = legacy.token_a_vault;
+    pool.token_b_vault = legacy.token_b_vault;
+    pool.lp_token_mint = legacy.lp_token_mint;
+    pool.reserve_a = legacy.reserve_a;
+    pool.reserve_b = legacy.reserve_b;
+    pool.total_lp_supply = legacy.total_lp_supply;
+    pool.fee_numerator = LiquidityPool::LEGACY_FEE_TIER_BPS as u64;
+    pool.fee_denominator = FEE_DENOMINATOR_BPS;
+    pool.fee_tier_bps = LiquidityPool::LEGACY_FEE_TIER_BPS;
+    pool.bump = legacy.bump;
+    pool.legacy_pda = true;
+    pool.version = ACCOUNT_VERSION;
+    pool.status = PoolStatus::Active;
+    pool.pool_type = PoolType::ConstantProduct;
+
+    let mut writer: &mut [u8] = &mut data[..];
+    pool.try_serialize(&mut writer)
+}
+
+// ============================================================================
+// CONTEXT STRUCTURES
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    #[account(
+        seeds = [b"dex_config"],
+        bump = dex_config.bump,
+        has_one = authority @ CustomError::InvalidAuthority
+    )]
+    pub dex_config: Account<'info, DexConfig>,
+
+    /// CHECK: Any account owned by this program; the layout is identified by
+    /// its discriminator and size inside the handler
+    #[account(mut, owner = crate::ID @ CustomError::UnsupportedAccountType)]
+    pub account: UncheckedAccount<'info>,
+
+    /// Migrated legacy pools are appended to the registry like newly created ones
+    #[account(
+        mut,
+        seeds = [b"pool_registry"],
+        bump = pool_registry.bump
+    )]
+    pub pool_registry: Box<Account<'info, PoolRegistry>>,
+
+    /// Current registry page, allocated on first use; untouched for other account types
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = PoolRegistryPage::SIZE,
+        seeds = [
+            b"registry_page",
+            pool_registry.current_page_index().to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub registry_page: Box<Account<'info, PoolRegistryPage>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
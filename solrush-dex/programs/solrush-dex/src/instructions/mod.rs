@@ -6,10 +6,24 @@
 pub mod pool;
 pub mod swap;
 pub mod limit_orders;
+pub mod flash_loan;
 pub mod rewards;
+pub mod staking;
+pub mod config;
+pub mod registry;
+pub mod migration;
+pub mod authority;
+pub mod concentrated;
 
 // Re-export instruction functions
 pub use pool::*;
 pub use swap::*;
 pub use limit_orders::*;
+pub use flash_loan::*;
 pub use rewards::*;
+pub use staking::*;
+pub use config::*;
+pub use registry::*;
+pub use migration::*;
+pub use authority::*;
+pub use concentrated::*;
@@ -1,17 +1,81 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Token, TokenAccount, Mint, MintTo, mint_to, Transfer, transfer, Burn, burn},
+    token_interface::{
+        Mint,
+        TokenAccount,
+        TokenInterface,
+        Token2022,
+        MintTo,
+        mint_to,
+        TransferChecked,
+        transfer_checked,
+        Burn,
+        burn,
+        CloseAccount,
+        close_account,
+    },
 };
 
-use crate::state::{LiquidityPool, UserLiquidityPosition};
+use crate::state::{
+    LiquidityPool,
+    UserLiquidityPosition,
+    PositionEarnings,
+    DexConfig,
+    PoolRegistry,
+    PoolRegistryPage,
+    FEE_DENOMINATOR_BPS,
+    MINIMUM_LIQUIDITY,
+    PoolStatus,
+    PoolType,
+    MIN_AMP_COEFFICIENT,
+    MAX_AMP_COEFFICIENT,
+    MIN_WEIGHT_BPS,
+    TOTAL_WEIGHT_BPS,
+    MAX_DYNAMIC_FEE_BPS,
+    ACCOUNT_VERSION,
+};
 use crate::errors::CustomError;
-use crate::events::{PoolCreated, LiquidityAdded, LiquidityRemoved};
+use crate::events::{
+    PoolCreated,
+    LiquidityAdded,
+    LiquidityRemoved,
+    SwapExecuted,
+    ReservesSynced,
+    SurplusSkimmed,
+    PoolStatusChanged,
+    PoolClosed,
+    DynamicFeeUpdated,
+};
+use crate::instructions::registry::{mark_pool_closed, register_pool};
 use crate::utils::{
-    calculate_lp_tokens,
+    calculate_initial_lp_tokens,
+    calculate_swap_output,
+    calculate_effective_fee_numerator,
+    calculate_spot_price,
+    update_volatility_accumulator,
+    calculate_deposit_lp_tokens,
     calculate_lp_tokens_for_add_liquidity,
+    calculate_optimal_liquidity_amounts,
     calculate_remove_liquidity_amounts,
-    validate_ratio_imbalance,
+    calculate_output_amount,
+    calculate_protocol_fee,
+    calculate_single_sided_swap_amount,
+    calculate_vault_surplus,
+    calculate_amount_after_transfer_fee,
+    mint_has_close_authority,
+    calculate_amount_before_transfer_fee,
+    calculate_exact_out_lp_tokens,
+    validate_supported_mint,
+    validate_pair_symbol,
+    initialize_token_metadata,
+    assert_vault_invariants,
+    accrue_lp_fee_growth,
+    record_position_deposit,
+    record_position_withdrawal,
+    calculate_pending_position_fees,
+    calculate_impermanent_loss,
+    check_deadline,
 };
 
 // ========================================================================
@@ -19,15 +83,75 @@ use crate::utils::{
 // ========================================================================
 
 /// Initialize a new liquidity pool with initial deposits
+/// The fee tier must be on the DexConfig allowlist and is part of the pool PDA,
+/// so the same pair can exist once per fee tier
+/// Either side may be a legacy SPL Token or a Token-2022 mint
+/// The pair symbols label the LP token (e.g. "SolRush SOL-USDC LP") and are
+/// supplied by the creator, since legacy mints carry no on-chain symbol
+/// StableSwap pools take an amplification coefficient and require both mints
+/// to share decimals; weighted pools take token A's weight in bps (token B gets
+/// the rest). Parameters that do not apply to the pool type must be 0
+#[allow(clippy::too_many_arguments)]
 pub fn initialize_pool(
     ctx: Context<InitializePool>,
     initial_deposit_a: u64,
     initial_deposit_b: u64,
+    fee_tier_bps: u16,
+    token_a_symbol: String,
+    token_b_symbol: String,
+    pool_type: PoolType,
+    amp_coefficient: u64,
+    weight_a_bps: u16,
 ) -> Result<()> {
     require!(
         initial_deposit_a > 0 && initial_deposit_b > 0,
         CustomError::InvalidInitialDeposit
     );
+    require!(
+        ctx.accounts.dex_config.is_fee_tier_allowed(fee_tier_bps),
+        CustomError::FeeTierNotAllowed
+    );
+    validate_supported_mint(&ctx.accounts.token_a_mint)?;
+    validate_supported_mint(&ctx.accounts.token_b_mint)?;
+    validate_pair_symbol(&token_a_symbol)?;
+    validate_pair_symbol(&token_b_symbol)?;
+    let weight_b_bps = match pool_type {
+        PoolType::ConstantProduct => {
+            require!(amp_coefficient == 0, CustomError::InvalidAmpCoefficient);
+            require!(weight_a_bps == 0, CustomError::InvalidPoolWeights);
+            0
+        }
+        PoolType::StableSwap => {
+            require!(
+                (MIN_AMP_COEFFICIENT..=MAX_AMP_COEFFICIENT).contains(&amp_coefficient),
+                CustomError::InvalidAmpCoefficient
+            );
+            require!(
+                ctx.accounts.token_a_mint.decimals == ctx.accounts.token_b_mint.decimals,
+                CustomError::StableSwapDecimalsMismatch
+            );
+            require!(weight_a_bps == 0, CustomError::InvalidPoolWeights);
+            0
+        }
+        PoolType::Weighted => {
+            require!(amp_coefficient == 0, CustomError::InvalidAmpCoefficient);
+            require!(
+                (MIN_WEIGHT_BPS..=TOTAL_WEIGHT_BPS - MIN_WEIGHT_BPS).contains(&weight_a_bps),
+                CustomError::InvalidPoolWeights
+            );
+            TOTAL_WEIGHT_BPS - weight_a_bps
+        }
+        // Concentrated pools have no LP mint; see initialize_concentrated_pool
+        PoolType::Concentrated => return err!(CustomError::UnsupportedPoolType),
+    };
+
+    // Transfer-fee mints deliver less than was sent; reserves track what the vaults receive
+    let received_a = calculate_amount_after_transfer_fee(&ctx.accounts.token_a_mint, initial_deposit_a)?;
+    let received_b = calculate_amount_after_transfer_fee(&ctx.accounts.token_b_mint, initial_deposit_b)?;
+    require!(
+        received_a > 0 && received_b > 0,
+        CustomError::InvalidInitialDeposit
+    );
 
     let pool = &mut ctx.accounts.pool;
     
@@ -38,25 +162,45 @@ pub fn initialize_pool(
     pool.token_b_vault = ctx.accounts.token_b_vault.key();
     pool.lp_token_mint = ctx.accounts.lp_token_mint.key();
     
-    pool.reserve_a = initial_deposit_a;
-    pool.reserve_b = initial_deposit_b;
+    pool.reserve_a = received_a;
+    pool.reserve_b = received_b;
     
-    pool.fee_numerator = 3;
-    pool.fee_denominator = 1000;
+    pool.fee_numerator = fee_tier_bps as u64;
+    pool.fee_denominator = FEE_DENOMINATOR_BPS;
+    pool.fee_tier_bps = fee_tier_bps;
+    pool.protocol_fee_share_bps = 0;
+    pool.protocol_fees_a = 0;
+    pool.protocol_fees_b = 0;
     pool.bump = ctx.bumps.pool;
+    pool.version = ACCOUNT_VERSION;
+    pool.status = PoolStatus::Active;
+    pool.pool_type = pool_type;
+    pool.amp_coefficient = amp_coefficient;
+    pool.weight_a_bps = weight_a_bps;
+    pool.weight_b_bps = weight_b_bps;
     
-    let lp_tokens = calculate_lp_tokens(initial_deposit_a, initial_deposit_b)?;
+    let lp_tokens = calculate_initial_lp_tokens(pool, received_a, received_b)?;
+    require!(lp_tokens > MINIMUM_LIQUIDITY, CustomError::InitialLiquidityTooLow);
+
+    // MINIMUM_LIQUIDITY stays in total_lp_supply but is never minted to anyone
+    let creator_lp_tokens = lp_tokens - MINIMUM_LIQUIDITY;
     pool.total_lp_supply = lp_tokens;
     
-    let token_a_mint_key = ctx.accounts.token_a_mint.key();
-    let token_b_mint_key = ctx.accounts.token_b_mint.key();
-    let bump_seed = pool.bump;
-    let signer_seeds: &[&[&[u8]]] = &[&[
-        b"pool",
-        token_a_mint_key.as_ref(),
-        token_b_mint_key.as_ref(),
-        &[bump_seed],
-    ]];
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    // LP metadata lives on the mint itself via the Token-2022 metadata extension
+    initialize_token_metadata(
+        &ctx.accounts.token_program,
+        &ctx.accounts.system_program,
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.lp_token_mint.to_account_info(),
+        pool.to_account_info(),
+        signer_seeds,
+        format!("SolRush {}-{} LP", token_a_symbol, token_b_symbol),
+        format!("{}-{}-LP", token_a_symbol, token_b_symbol),
+        String::new(),
+    )?;
     
     mint_to(
         CpiContext::new_with_signer(
@@ -68,48 +212,76 @@ pub fn initialize_pool(
             },
             signer_seeds,
         ),
-        lp_tokens,
+        creator_lp_tokens,
     )?;
     
-    transfer(
+    transfer_checked(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_a_program.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.user_token_a.to_account_info(),
+                mint: ctx.accounts.token_a_mint.to_account_info(),
                 to: ctx.accounts.token_a_vault.to_account_info(),
                 authority: ctx.accounts.authority.to_account_info(),
             },
         ),
         initial_deposit_a,
+        ctx.accounts.token_a_mint.decimals,
     )?;
 
-    transfer(
+    transfer_checked(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_b_program.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.user_token_b.to_account_info(),
+                mint: ctx.accounts.token_b_mint.to_account_info(),
                 to: ctx.accounts.token_b_vault.to_account_info(),
                 authority: ctx.accounts.authority.to_account_info(),
             },
         ),
         initial_deposit_b,
+        ctx.accounts.token_b_mint.decimals,
     )?;
     
+    assert_vault_invariants(
+        pool,
+        &mut ctx.accounts.token_a_vault,
+        &mut ctx.accounts.token_b_vault,
+    )?;
+
+    let pool_index = register_pool(
+        &mut ctx.accounts.pool_registry,
+        &mut ctx.accounts.registry_page,
+        ctx.bumps.registry_page,
+        pool.key(),
+        pool,
+    )?;
+
     emit!(PoolCreated {
         pool: pool.key(),
         token_a_mint: ctx.accounts.token_a_mint.key(),
         token_b_mint: ctx.accounts.token_b_mint.key(),
-        reserve_a: initial_deposit_a,
-        reserve_b: initial_deposit_b,
+        reserve_a: received_a,
+        reserve_b: received_b,
         lp_token_supply: lp_tokens,
+        fee_tier_bps,
         authority: ctx.accounts.authority.key(),
+        pool_type,
+        amp_coefficient,
+        weight_a_bps,
+        weight_b_bps,
+        tick_spacing: 0,
+        sqrt_price_x64: 0,
     });
 
     msg!(
-        "✓ Pool initialized: A={} | B={} | LP={} | Fee=0.3%",
-        initial_deposit_a,
-        initial_deposit_b,
-        lp_tokens
+        "✓ Pool initialized: A={} | B={} | LP={} (locked={}) | Fee={} bps | Registry index={}",
+        received_a,
+        received_b,
+        creator_lp_tokens,
+        MINIMUM_LIQUIDITY,
+        fee_tier_bps,
+        pool_index
     );
     Ok(())
 }
@@ -118,70 +290,89 @@ pub fn initialize_pool(
 // MODULE 2.3: ADD LIQUIDITY
 // ========================================================================
 
+/// Add liquidity at the pool's current ratio
+/// Only the optimal pair derived from the desired amounts is transferred;
+/// the excess on the other side stays with the user
 pub fn add_liquidity(
     ctx: Context<AddLiquidity>,
-    amount_a: u64,
-    amount_b: u64,
-    min_lp_tokens: u64,
+    amount_a_desired: u64,
+    amount_b_desired: u64,
+    amount_a_min: u64,
+    amount_b_min: u64,
+    deadline: Option<i64>,
 ) -> Result<()> {
-    require!(amount_a > 0 && amount_b > 0, CustomError::InvalidAmount);
-    require!(
-        ctx.accounts.user_token_a.amount >= amount_a,
-        CustomError::InsufficientBalance
-    );
+    check_deadline(deadline)?;
+
     require!(
-        ctx.accounts.user_token_b.amount >= amount_b,
-        CustomError::InsufficientBalance
+        amount_a_desired > 0 && amount_b_desired > 0,
+        CustomError::InvalidAmount
     );
 
     let pool = &mut ctx.accounts.pool;
+    require!(pool.status.allows_deposits(), CustomError::PoolDepositsDisabled);
+    require!(!pool.locked, CustomError::PoolLocked);
 
-    validate_ratio_imbalance(amount_a, amount_b, pool.reserve_a, pool.reserve_b)?;
-
-    let lp_tokens_to_mint = calculate_lp_tokens_for_add_liquidity(
-        amount_a,
-        amount_b,
+    let (amount_a, amount_b) = calculate_optimal_liquidity_amounts(
+        amount_a_desired,
+        amount_b_desired,
+        amount_a_min,
+        amount_b_min,
         pool.reserve_a,
         pool.reserve_b,
-        pool.total_lp_supply,
     )?;
 
     require!(
-        lp_tokens_to_mint >= min_lp_tokens,
-        CustomError::SlippageTooHigh
+        ctx.accounts.user_token_a.amount >= amount_a,
+        CustomError::InsufficientBalance
+    );
+    require!(
+        ctx.accounts.user_token_b.amount >= amount_b,
+        CustomError::InsufficientBalance
     );
 
-    transfer(
+    // LP tokens are priced on what the vaults receive after transfer fees
+    let received_a = calculate_amount_after_transfer_fee(&ctx.accounts.token_a_mint, amount_a)?;
+    let received_b = calculate_amount_after_transfer_fee(&ctx.accounts.token_b_mint, amount_b)?;
+
+    let lp_tokens_to_mint = calculate_deposit_lp_tokens(pool, received_a, received_b)?;
+
+    require!(lp_tokens_to_mint > 0, CustomError::InvalidAmount);
+
+    transfer_checked(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_a_program.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.user_token_a.to_account_info(),
+                mint: ctx.accounts.token_a_mint.to_account_info(),
                 to: ctx.accounts.token_a_vault.to_account_info(),
                 authority: ctx.accounts.user.to_account_info(),
             },
         ),
         amount_a,
+        ctx.accounts.token_a_mint.decimals,
     )?;
 
-    transfer(
+    transfer_checked(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_b_program.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.user_token_b.to_account_info(),
+                mint: ctx.accounts.token_b_mint.to_account_info(),
                 to: ctx.accounts.token_b_vault.to_account_info(),
                 authority: ctx.accounts.user.to_account_info(),
             },
         ),
         amount_b,
+        ctx.accounts.token_b_mint.decimals,
     )?;
 
     pool.reserve_a = pool
         .reserve_a
-        .checked_add(amount_a)
+        .checked_add(received_a)
         .ok_or(error!(CustomError::CalculationOverflow))?;
     pool.reserve_b = pool
         .reserve_b
-        .checked_add(amount_b)
+        .checked_add(received_b)
         .ok_or(error!(CustomError::CalculationOverflow))?;
     pool.total_lp_supply = pool
         .total_lp_supply
@@ -189,15 +380,8 @@ pub fn add_liquidity(
         .ok_or(error!(CustomError::CalculationOverflow))?;
 
     let pool_key = pool.key();
-    let token_a_mint_key = pool.token_a_mint;
-    let token_b_mint_key = pool.token_b_mint;
-    let bump_seed = pool.bump;
-    let signer_seeds: &[&[&[u8]]] = &[&[
-        b"pool",
-        token_a_mint_key.as_ref(),
-        token_b_mint_key.as_ref(),
-        &[bump_seed],
-    ]];
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
 
     mint_to(
         CpiContext::new_with_signer(
@@ -212,34 +396,265 @@ pub fn add_liquidity(
         lp_tokens_to_mint,
     )?;
 
+    assert_vault_invariants(
+        pool,
+        &mut ctx.accounts.token_a_vault,
+        &mut ctx.accounts.token_b_vault,
+    )?;
+
     let user_position = &mut ctx.accounts.user_position;
     if user_position.owner == Pubkey::default() {
         user_position.owner = ctx.accounts.user.key();
         user_position.pool = pool_key;
         user_position.deposit_timestamp = Clock::get()?.unix_timestamp;
+        user_position.last_claim_timestamp = user_position.deposit_timestamp;
         user_position.bump = ctx.bumps.user_position;
+        user_position.version = ACCOUNT_VERSION;
     }
 
-    user_position.lp_tokens = user_position
-        .lp_tokens
+    // Rewards follow staked LP only, so deposits leave the claim clock alone
+    record_position_deposit(user_position, pool, lp_tokens_to_mint, received_a, received_b)?;
+
+    emit!(LiquidityAdded {
+        user: ctx.accounts.user.key(),
+        pool: pool_key,
+        amount_a: received_a,
+        amount_b: received_b,
+        lp_tokens_minted: lp_tokens_to_mint,
+        new_reserve_a: pool.reserve_a,
+        new_reserve_b: pool.reserve_b,
+    });
+
+    msg!(
+        "✓ Liquidity added: A={} | B={} | LP minted={} | New reserves: A={}, B={}",
+        received_a,
+        received_b,
+        lp_tokens_to_mint,
+        pool.reserve_a,
+        pool.reserve_b
+    );
+
+    Ok(())
+}
+
+// ========================================================================
+// MODULE 2.6: SINGLE-SIDED ADD LIQUIDITY (ZAP)
+// ========================================================================
+
+/// Add liquidity with a single token
+/// Swaps the optimal portion of the deposit through the pool curve, then deposits
+/// the remainder together with the swap output in the post-swap reserve ratio
+pub fn add_liquidity_single_sided(
+    ctx: Context<AddLiquiditySingleSided>,
+    amount_in: u64,
+    is_token_a: bool,
+    min_lp_tokens: u64,
+    deadline: Option<i64>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+
+    require!(amount_in > 0, CustomError::InvalidAmount);
+    require!(
+        ctx.accounts.user_token_in.amount >= amount_in,
+        CustomError::InsufficientBalance
+    );
+
+    let pool = &mut ctx.accounts.pool;
+    // The zap swaps part of the deposit against the pool, so it needs both
+    require!(pool.status.allows_deposits(), CustomError::PoolDepositsDisabled);
+    require!(pool.status.allows_swaps(), CustomError::PoolSwapsDisabled);
+    require!(!pool.locked, CustomError::PoolLocked);
+    // The swap split below is solved for x*y=k only
+    require!(
+        pool.pool_type == PoolType::ConstantProduct,
+        CustomError::UnsupportedPoolType
+    );
+
+    let expected_mint = if is_token_a { pool.token_a_mint } else { pool.token_b_mint };
+    require_keys_eq!(
+        ctx.accounts.user_token_in.mint,
+        expected_mint,
+        CustomError::InvalidTokenMint
+    );
+
+    let (reserve_in, reserve_out) = if is_token_a {
+        (pool.reserve_a, pool.reserve_b)
+    } else {
+        (pool.reserve_b, pool.reserve_a)
+    };
+    let (mint_in, token_program_in, vault_in) = if is_token_a {
+        (&ctx.accounts.token_a_mint, &ctx.accounts.token_a_program, &ctx.accounts.token_a_vault)
+    } else {
+        (&ctx.accounts.token_b_mint, &ctx.accounts.token_b_program, &ctx.accounts.token_b_vault)
+    };
+
+    // The zap is sized on what reaches the vault after any transfer fee
+    let received_in = calculate_amount_after_transfer_fee(mint_in, amount_in)?;
+    require!(received_in > 0, CustomError::InvalidAmount);
+
+    // The swap leg pays the dynamic fee like any other swap
+    let now = Clock::get()?.unix_timestamp;
+    let fee_numerator = calculate_effective_fee_numerator(pool, now);
+    let price_before = calculate_spot_price(pool)?;
+
+    // Step 1: Swap leg through the constant product curve
+    let swap_amount = calculate_single_sided_swap_amount(
+        received_in,
+        reserve_in,
+        fee_numerator,
+        pool.fee_denominator,
+    )?;
+    let swap_amount_out = calculate_output_amount(
+        swap_amount,
+        reserve_in,
+        reserve_out,
+        fee_numerator,
+        pool.fee_denominator,
+    )?;
+    let fee_amount = (swap_amount as u128)
+        .checked_mul(fee_numerator as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))?
+        .checked_div(pool.fee_denominator as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))? as u64;
+    let protocol_fee = calculate_protocol_fee(fee_amount, pool.protocol_fee_share_bps)?;
+
+    let reserve_in_after_swap = reserve_in
+        .checked_add(swap_amount)
+        .and_then(|r| r.checked_sub(protocol_fee))
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    let reserve_out_after_swap = reserve_out
+        .checked_sub(swap_amount_out)
+        .ok_or(error!(CustomError::InsufficientPoolReserves))?;
+
+    // Step 2: Deposit leg with the remaining input and the swap output
+    let deposit_in = received_in
+        .checked_sub(swap_amount)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    let (deposit_a, deposit_b, reserve_a_after_swap, reserve_b_after_swap) = if is_token_a {
+        (deposit_in, swap_amount_out, reserve_in_after_swap, reserve_out_after_swap)
+    } else {
+        (swap_amount_out, deposit_in, reserve_out_after_swap, reserve_in_after_swap)
+    };
+
+    let lp_tokens_to_mint = calculate_lp_tokens_for_add_liquidity(
+        deposit_a,
+        deposit_b,
+        reserve_a_after_swap,
+        reserve_b_after_swap,
+        pool.total_lp_supply,
+    )?;
+
+    require!(
+        lp_tokens_to_mint >= min_lp_tokens,
+        CustomError::SlippageTooHigh
+    );
+
+    // The swap output never leaves the vault, so only the input token moves
+    transfer_checked(
+        CpiContext::new(
+            token_program_in.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_token_in.to_account_info(),
+                mint: mint_in.to_account_info(),
+                to: vault_in.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount_in,
+        mint_in.decimals,
+    )?;
+
+    pool.reserve_a = reserve_a_after_swap
+        .checked_add(deposit_a)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    pool.reserve_b = reserve_b_after_swap
+        .checked_add(deposit_b)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    if is_token_a {
+        pool.protocol_fees_a = pool
+            .protocol_fees_a
+            .checked_add(protocol_fee)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+    } else {
+        pool.protocol_fees_b = pool
+            .protocol_fees_b
+            .checked_add(protocol_fee)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+    }
+    // Existing LPs earn the swap leg's fee, so it accrues before the mint
+    accrue_lp_fee_growth(pool, fee_amount - protocol_fee, is_token_a);
+    pool.total_lp_supply = pool
+        .total_lp_supply
         .checked_add(lp_tokens_to_mint)
         .ok_or(error!(CustomError::CalculationOverflow))?;
-    user_position.last_claim_timestamp = Clock::get()?.unix_timestamp;
+    // The deposit leg keeps the post-swap ratio, so the price moved by the swap leg only
+    update_volatility_accumulator(pool, price_before, now)?;
+
+    let pool_key = pool.key();
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.lp_token_mint.to_account_info(),
+                to: ctx.accounts.user_lp_token_account.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        lp_tokens_to_mint,
+    )?;
+
+    assert_vault_invariants(
+        pool,
+        &mut ctx.accounts.token_a_vault,
+        &mut ctx.accounts.token_b_vault,
+    )?;
+
+    let user_position = &mut ctx.accounts.user_position;
+    if user_position.owner == Pubkey::default() {
+        user_position.owner = ctx.accounts.user.key();
+        user_position.pool = pool_key;
+        user_position.deposit_timestamp = now;
+        user_position.last_claim_timestamp = user_position.deposit_timestamp;
+        user_position.bump = ctx.bumps.user_position;
+        user_position.version = ACCOUNT_VERSION;
+    }
+
+    // Rewards follow staked LP only, so deposits leave the claim clock alone
+    let (deposited_a, deposited_b) = if is_token_a { (received_in, 0) } else { (0, received_in) };
+    record_position_deposit(user_position, pool, lp_tokens_to_mint, deposited_a, deposited_b)?;
+
+    emit!(SwapExecuted {
+        user: ctx.accounts.user.key(),
+        pool: pool_key,
+        amount_in: swap_amount,
+        amount_out: swap_amount_out,
+        fee_amount,
+        fee_bps: fee_numerator as u16,
+        protocol_fee_amount: protocol_fee,
+        is_a_to_b: is_token_a,
+        new_reserve_a: reserve_a_after_swap,
+        new_reserve_b: reserve_b_after_swap,
+    });
 
     emit!(LiquidityAdded {
         user: ctx.accounts.user.key(),
         pool: pool_key,
-        amount_a,
-        amount_b,
+        amount_a: if is_token_a { received_in } else { 0 },
+        amount_b: if is_token_a { 0 } else { received_in },
         lp_tokens_minted: lp_tokens_to_mint,
         new_reserve_a: pool.reserve_a,
         new_reserve_b: pool.reserve_b,
     });
 
     msg!(
-        "✓ Liquidity added: A={} | B={} | LP minted={} | New reserves: A={}, B={}",
-        amount_a,
-        amount_b,
+        "✓ Single-sided liquidity added: In={} | Swapped={} -> {} | LP minted={} | New reserves: A={}, B={}",
+        received_in,
+        swap_amount,
+        swap_amount_out,
         lp_tokens_to_mint,
         pool.reserve_a,
         pool.reserve_b
@@ -257,19 +672,34 @@ pub fn remove_liquidity(
     lp_tokens_to_burn: u64,
     min_amount_a: u64,
     min_amount_b: u64,
+    deadline: Option<i64>,
 ) -> Result<()> {
-    require!(lp_tokens_to_burn > 0, CustomError::InvalidAmount);
+    check_deadline(deadline)?;
+
     require!(
-        ctx.accounts.user_lp_token_account.amount >= lp_tokens_to_burn,
-        CustomError::InsufficientLPBalance
+        ctx.accounts.pool.status.allows_withdrawals(),
+        CustomError::PoolWithdrawalsDisabled
     );
+    require!(!ctx.accounts.pool.locked, CustomError::PoolLocked);
+    require!(lp_tokens_to_burn > 0, CustomError::InvalidAmount);
     require!(
-        ctx.accounts.user_position.lp_tokens >= lp_tokens_to_burn,
+        ctx.accounts.user_lp_token_account.amount >= lp_tokens_to_burn,
         CustomError::InsufficientLPBalance
     );
 
     let pool = &mut ctx.accounts.pool;
 
+    // The locked MINIMUM_LIQUIDITY share can never be withdrawn
+    let remaining_lp_supply = pool
+        .total_lp_supply
+        .checked_sub(lp_tokens_to_burn)
+        .ok_or(error!(CustomError::InsufficientLPBalance))?;
+    require!(
+        remaining_lp_supply >= MINIMUM_LIQUIDITY,
+        CustomError::InsufficientLiquidity
+    );
+
+    // Proportional withdrawals are curve-independent: both sides scale with the share burned
     let (amount_a, amount_b) = calculate_remove_liquidity_amounts(
         lp_tokens_to_burn,
         pool.total_lp_supply,
@@ -277,8 +707,11 @@ pub fn remove_liquidity(
         pool.reserve_b,
     )?;
 
-    require!(amount_a >= min_amount_a, CustomError::SlippageTooHigh);
-    require!(amount_b >= min_amount_b, CustomError::SlippageTooHigh);
+    // Slippage is checked against what the user receives after transfer fees
+    let received_a = calculate_amount_after_transfer_fee(&ctx.accounts.token_a_mint, amount_a)?;
+    let received_b = calculate_amount_after_transfer_fee(&ctx.accounts.token_b_mint, amount_b)?;
+    require!(received_a >= min_amount_a, CustomError::SlippageTooHigh);
+    require!(received_b >= min_amount_b, CustomError::SlippageTooHigh);
     
     require!(
         ctx.accounts.token_a_vault.amount >= amount_a,
@@ -309,69 +742,67 @@ pub fn remove_liquidity(
         .reserve_b
         .checked_sub(amount_b)
         .ok_or(error!(CustomError::InsufficientPoolReserves))?;
-    pool.total_lp_supply = pool
-        .total_lp_supply
-        .checked_sub(lp_tokens_to_burn)
-        .ok_or(error!(CustomError::CalculationOverflow))?;
+    pool.total_lp_supply = remaining_lp_supply;
 
     let pool_key = pool.key();
-    let token_a_mint = pool.token_a_mint;
-    let token_b_mint = pool.token_b_mint;
-    let bump_seed = pool.bump;
-    let signer_seeds: &[&[&[u8]]] = &[&[
-        b"pool",
-        token_a_mint.as_ref(),
-        token_b_mint.as_ref(),
-        &[bump_seed],
-    ]];
-
-    transfer(
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    transfer_checked(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_a_program.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.token_a_vault.to_account_info(),
+                mint: ctx.accounts.token_a_mint.to_account_info(),
                 to: ctx.accounts.user_token_a.to_account_info(),
                 authority: pool.to_account_info(),
             },
             signer_seeds,
         ),
         amount_a,
+        ctx.accounts.token_a_mint.decimals,
     )?;
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_b_program.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.token_b_vault.to_account_info(),
+                mint: ctx.accounts.token_b_mint.to_account_info(),
                 to: ctx.accounts.user_token_b.to_account_info(),
                 authority: pool.to_account_info(),
             },
             signer_seeds,
         ),
         amount_b,
+        ctx.accounts.token_b_mint.decimals,
+    )?;
+
+    assert_vault_invariants(
+        pool,
+        &mut ctx.accounts.token_a_vault,
+        &mut ctx.accounts.token_b_vault,
     )?;
 
     let user_position = &mut ctx.accounts.user_position;
-    user_position.lp_tokens = user_position
-        .lp_tokens
-        .checked_sub(lp_tokens_to_burn)
-        .ok_or(error!(CustomError::InsufficientLPBalance))?;
+    record_position_withdrawal(user_position, pool, lp_tokens_to_burn, amount_a, amount_b)?;
 
     emit!(LiquidityRemoved {
         user: ctx.accounts.user.key(),
         pool: pool_key,
         lp_tokens_burned: lp_tokens_to_burn,
-        amount_a_received: amount_a,
-        amount_b_received: amount_b,
+        amount_a_received: received_a,
+        amount_b_received: received_b,
         new_reserve_a: pool.reserve_a,
         new_reserve_b: pool.reserve_b,
+        output_mint: None,
     });
 
     msg!(
         "✓ Liquidity removed: LP burned={} | Received: A={}, B={} | New reserves: A={}, B={}",
         lp_tokens_to_burn,
-        amount_a,
-        amount_b,
+        received_a,
+        received_b,
         pool.reserve_a,
         pool.reserve_b
     );
@@ -379,176 +810,1437 @@ pub fn remove_liquidity(
     Ok(())
 }
 
-// ========================================================================
-// ACCOUNT CONTEXTS
-// ========================================================================
+/// Remove liquidity into a single token
+/// Burns LP for a proportional share of both reserves, swaps the unwanted side back
+/// into the pool on its curve and pays out only the chosen token. The swapped side
+/// never leaves the vault; the swap leg pays the pool fee like any other swap.
+pub fn remove_liquidity_single_sided(
+    ctx: Context<RemoveLiquiditySingleSided>,
+    lp_tokens_to_burn: u64,
+    is_token_a_out: bool,
+    min_amount_out: u64,
+    deadline: Option<i64>,
+) -> Result<()> {
+    check_deadline(deadline)?;
 
-#[derive(Accounts)]
-#[instruction(initial_deposit_a: u64, initial_deposit_b: u64)]
-pub struct InitializePool<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = LiquidityPool::SIZE,
-        seeds = [b"pool", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
-        bump
-    )]
-    pub pool: Box<Account<'info, LiquidityPool>>,
-    
-    pub token_a_mint: Box<Account<'info, Mint>>,
-    pub token_b_mint: Box<Account<'info, Mint>>,
-    
-    #[account(
-        init,
-        payer = authority,
-        mint::decimals = 6,
-        mint::authority = pool,
-        seeds = [b"lp_mint", pool.key().as_ref()],
-        bump
-    )]
-    pub lp_token_mint: Box<Account<'info, Mint>>,
-    
-    #[account(
-        init,
-        payer = authority,
-        token::mint = token_a_mint,
-        token::authority = pool
-    )]
-    pub token_a_vault: Box<Account<'info, TokenAccount>>,
-    
-    #[account(
-        init,
-        payer = authority,
-        token::mint = token_b_mint,
-        token::authority = pool
-    )]
-    pub token_b_vault: Box<Account<'info, TokenAccount>>,
-    
-    #[account(mut)]
-    pub user_token_a: Box<Account<'info, TokenAccount>>,
-    
-    #[account(mut)]
-    pub user_token_b: Box<Account<'info, TokenAccount>>,
-    
-    #[account(
-        init,
-        payer = authority,
-        associated_token::mint = lp_token_mint,
-        associated_token::authority = authority
-    )]
-    pub lp_token_account: Box<Account<'info, TokenAccount>>,
-    
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
+    require!(lp_tokens_to_burn > 0, CustomError::InvalidAmount);
+    require!(
+        ctx.accounts.user_lp_token_account.amount >= lp_tokens_to_burn,
+        CustomError::InsufficientLPBalance
+    );
 
-#[derive(Accounts)]
-pub struct AddLiquidity<'info> {
-    #[account(
-        mut,
-        seeds = [b"pool", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
-        bump
-    )]
-    pub pool: Box<Account<'info, LiquidityPool>>,
-    
-    pub token_a_mint: Box<Account<'info, Mint>>,
-    pub token_b_mint: Box<Account<'info, Mint>>,
-    
-    #[account(mut)]
-    pub lp_token_mint: Box<Account<'info, Mint>>,
-    
-    #[account(
-        init_if_needed,
-        payer = user,
-        space = UserLiquidityPosition::SIZE,
-        seeds = [b"position", pool.key().as_ref(), user.key().as_ref()],
-        bump
-    )]
-    pub user_position: Box<Account<'info, UserLiquidityPosition>>,
-    
-    #[account(mut)]
-    pub token_a_vault: Box<Account<'info, TokenAccount>>,
-    
-    #[account(mut)]
-    pub token_b_vault: Box<Account<'info, TokenAccount>>,
-    
-    #[account(
-        mut,
+    let pool = &mut ctx.accounts.pool;
+    // The withdrawal swaps one side back through the pool, so it needs both
+    require!(
+        pool.status.allows_withdrawals(),
+        CustomError::PoolWithdrawalsDisabled
+    );
+    require!(pool.status.allows_swaps(), CustomError::PoolSwapsDisabled);
+    require!(!pool.locked, CustomError::PoolLocked);
+
+    let expected_mint = if is_token_a_out {
+        pool.token_a_mint
+    } else {
+        pool.token_b_mint
+    };
+    require_keys_eq!(
+        ctx.accounts.user_token_out.mint,
+        expected_mint,
+        CustomError::InvalidTokenMint
+    );
+
+    // The locked MINIMUM_LIQUIDITY share can never be withdrawn
+    let remaining_lp_supply = pool
+        .total_lp_supply
+        .checked_sub(lp_tokens_to_burn)
+        .ok_or(error!(CustomError::InsufficientLPBalance))?;
+    require!(
+        remaining_lp_supply >= MINIMUM_LIQUIDITY,
+        CustomError::InsufficientLiquidity
+    );
+
+    // Step 1: Proportional withdrawal of both sides
+    let (amount_a, amount_b) = calculate_remove_liquidity_amounts(
+        lp_tokens_to_burn,
+        pool.total_lp_supply,
+        pool.reserve_a,
+        pool.reserve_b,
+    )?;
+    pool.reserve_a = pool
+        .reserve_a
+        .checked_sub(amount_a)
+        .ok_or(error!(CustomError::InsufficientPoolReserves))?;
+    pool.reserve_b = pool
+        .reserve_b
+        .checked_sub(amount_b)
+        .ok_or(error!(CustomError::InsufficientPoolReserves))?;
+
+    // Step 2: Swap the unwanted side back against the reduced reserves
+    // The swap leg pays the dynamic fee like any other swap
+    let now = Clock::get()?.unix_timestamp;
+    let fee_numerator = calculate_effective_fee_numerator(pool, now);
+    let price_before = calculate_spot_price(pool)?;
+
+    let (withdrawn_out, swap_amount_in) = if is_token_a_out {
+        (amount_a, amount_b)
+    } else {
+        (amount_b, amount_a)
+    };
+    // Swapping token B in pays out token A, and vice versa
+    let swap_is_a_to_b = !is_token_a_out;
+    let swap_amount_out = if swap_amount_in > 0 {
+        calculate_swap_output(pool, swap_amount_in, swap_is_a_to_b, fee_numerator)?
+    } else {
+        0
+    };
+    let fee_amount = (swap_amount_in as u128)
+        .checked_mul(fee_numerator as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))?
+        .checked_div(pool.fee_denominator as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))? as u64;
+    let protocol_fee = calculate_protocol_fee(fee_amount, pool.protocol_fee_share_bps)?;
+    let swap_amount_to_reserve = swap_amount_in
+        .checked_sub(protocol_fee)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
+    let amount_out = withdrawn_out
+        .checked_add(swap_amount_out)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    require!(amount_out > 0, CustomError::InvalidAmount);
+
+    // Slippage is checked against what the user receives after the transfer fee
+    let (mint_out, token_program_out, vault_out) = if is_token_a_out {
+        (
+            &ctx.accounts.token_a_mint,
+            &ctx.accounts.token_a_program,
+            &ctx.accounts.token_a_vault,
+        )
+    } else {
+        (
+            &ctx.accounts.token_b_mint,
+            &ctx.accounts.token_b_program,
+            &ctx.accounts.token_b_vault,
+        )
+    };
+    let amount_out_received = calculate_amount_after_transfer_fee(mint_out, amount_out)?;
+    require!(
+        amount_out_received >= min_amount_out,
+        CustomError::SlippageTooHigh
+    );
+    require!(
+        vault_out.amount >= amount_out,
+        CustomError::InsufficientPoolReserves
+    );
+
+    if is_token_a_out {
+        pool.reserve_b = pool
+            .reserve_b
+            .checked_add(swap_amount_to_reserve)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+        pool.protocol_fees_b = pool
+            .protocol_fees_b
+            .checked_add(protocol_fee)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+        pool.reserve_a = pool
+            .reserve_a
+            .checked_sub(swap_amount_out)
+            .ok_or(error!(CustomError::InsufficientPoolReserves))?;
+    } else {
+        pool.reserve_a = pool
+            .reserve_a
+            .checked_add(swap_amount_to_reserve)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+        pool.protocol_fees_a = pool
+            .protocol_fees_a
+            .checked_add(protocol_fee)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+        pool.reserve_b = pool
+            .reserve_b
+            .checked_sub(swap_amount_out)
+            .ok_or(error!(CustomError::InsufficientPoolReserves))?;
+    }
+    pool.total_lp_supply = remaining_lp_supply;
+    // The proportional leg keeps the ratio, so the price moved by the swap leg only
+    update_volatility_accumulator(pool, price_before, now)?;
+
+    burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.lp_token_mint.to_account_info(),
+                from: ctx.accounts.user_lp_token_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        lp_tokens_to_burn,
+    )?;
+
+    let pool_key = pool.key();
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program_out.to_account_info(),
+            TransferChecked {
+                from: vault_out.to_account_info(),
+                mint: mint_out.to_account_info(),
+                to: ctx.accounts.user_token_out.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount_out,
+        mint_out.decimals,
+    )?;
+
+    assert_vault_invariants(
+        pool,
+        &mut ctx.accounts.token_a_vault,
+        &mut ctx.accounts.token_b_vault,
+    )?;
+
+    let user_position = &mut ctx.accounts.user_position;
+    let (withdrawn_a, withdrawn_b) = if is_token_a_out { (amount_out, 0) } else { (0, amount_out) };
+    record_position_withdrawal(user_position, pool, lp_tokens_to_burn, withdrawn_a, withdrawn_b)?;
+
+    // The swap leg's fee goes to the LPs that remain, after the withdrawer has settled
+    accrue_lp_fee_growth(pool, fee_amount - protocol_fee, swap_is_a_to_b);
+
+    if swap_amount_in > 0 {
+        emit!(SwapExecuted {
+            user: ctx.accounts.user.key(),
+            pool: pool_key,
+            amount_in: swap_amount_in,
+            amount_out: swap_amount_out,
+            fee_amount,
+            fee_bps: fee_numerator as u16,
+            protocol_fee_amount: protocol_fee,
+            is_a_to_b: swap_is_a_to_b,
+            new_reserve_a: pool.reserve_a,
+            new_reserve_b: pool.reserve_b,
+        });
+    }
+
+    emit!(LiquidityRemoved {
+        user: ctx.accounts.user.key(),
+        pool: pool_key,
+        lp_tokens_burned: lp_tokens_to_burn,
+        amount_a_received: if is_token_a_out {
+            amount_out_received
+        } else {
+            0
+        },
+        amount_b_received: if is_token_a_out {
+            0
+        } else {
+            amount_out_received
+        },
+        new_reserve_a: pool.reserve_a,
+        new_reserve_b: pool.reserve_b,
+        output_mint: Some(expected_mint),
+    });
+
+    msg!(
+        "✓ Single-sided liquidity removed: LP burned={} | Withdrawn={} + swapped {} -> {} | Received={} | New reserves: A={}, B={}",
+        lp_tokens_to_burn,
+        withdrawn_out,
+        swap_amount_in,
+        swap_amount_out,
+        amount_out_received,
+        pool.reserve_a,
+        pool.reserve_b
+    );
+
+    Ok(())
+}
+
+/// Remove liquidity for exact amounts of each token (imbalanced withdrawal)
+/// Burns whatever LP the requested amounts cost on the pool's curve, including the
+/// swap fee on the part of the withdrawal that is not proportional to the reserves.
+/// Amounts are what the user receives; Token-2022 transfer fees are added on top.
+pub fn remove_liquidity_exact_out(
+    ctx: Context<RemoveLiquidity>,
+    amount_a_out: u64,
+    amount_b_out: u64,
+    max_lp_tokens_to_burn: u64,
+    deadline: Option<i64>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+
+    require!(
+        amount_a_out > 0 || amount_b_out > 0,
+        CustomError::InvalidAmount
+    );
+
+    let pool = &mut ctx.accounts.pool;
+    // An imbalanced withdrawal moves the price like a swap, so it needs both
+    require!(pool.status.allows_withdrawals(), CustomError::PoolWithdrawalsDisabled);
+    require!(pool.status.allows_swaps(), CustomError::PoolSwapsDisabled);
+    require!(!pool.locked, CustomError::PoolLocked);
+
+    // The vault sends enough that the exact amounts land after any transfer fee
+    let amount_a = calculate_amount_before_transfer_fee(&ctx.accounts.token_a_mint, amount_a_out)?;
+    let amount_b = calculate_amount_before_transfer_fee(&ctx.accounts.token_b_mint, amount_b_out)?;
+
+    // The imbalance fee follows the dynamic fee like any other swap
+    let now = Clock::get()?.unix_timestamp;
+    let fee_numerator = calculate_effective_fee_numerator(pool, now);
+    let price_before = calculate_spot_price(pool)?;
+
+    let (lp_tokens_to_burn, fee_a, fee_b) =
+        calculate_exact_out_lp_tokens(pool, amount_a, amount_b, fee_numerator)?;
+
+    require!(
+        lp_tokens_to_burn <= max_lp_tokens_to_burn,
+        CustomError::SlippageTooHigh
+    );
+    require!(
+        ctx.accounts.user_lp_token_account.amount >= lp_tokens_to_burn,
+        CustomError::InsufficientLPBalance
+    );
+
+    // The locked MINIMUM_LIQUIDITY share can never be withdrawn
+    let remaining_lp_supply = pool
+        .total_lp_supply
+        .checked_sub(lp_tokens_to_burn)
+        .ok_or(error!(CustomError::InsufficientLPBalance))?;
+    require!(
+        remaining_lp_supply >= MINIMUM_LIQUIDITY,
+        CustomError::InsufficientLiquidity
+    );
+
+    require!(
+        ctx.accounts.token_a_vault.amount >= amount_a,
+        CustomError::InsufficientPoolReserves
+    );
+    require!(
+        ctx.accounts.token_b_vault.amount >= amount_b,
+        CustomError::InsufficientPoolReserves
+    );
+
+    // LPs keep the imbalance fee except for the protocol share, which leaves the reserves
+    let protocol_fee_a = calculate_protocol_fee(fee_a, pool.protocol_fee_share_bps)?;
+    let protocol_fee_b = calculate_protocol_fee(fee_b, pool.protocol_fee_share_bps)?;
+
+    burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.lp_token_mint.to_account_info(),
+                from: ctx.accounts.user_lp_token_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        lp_tokens_to_burn,
+    )?;
+
+    pool.reserve_a = pool
+        .reserve_a
+        .checked_sub(amount_a)
+        .and_then(|r| r.checked_sub(protocol_fee_a))
+        .ok_or(error!(CustomError::InsufficientPoolReserves))?;
+    pool.reserve_b = pool
+        .reserve_b
+        .checked_sub(amount_b)
+        .and_then(|r| r.checked_sub(protocol_fee_b))
+        .ok_or(error!(CustomError::InsufficientPoolReserves))?;
+    pool.protocol_fees_a = pool
+        .protocol_fees_a
+        .checked_add(protocol_fee_a)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    pool.protocol_fees_b = pool
+        .protocol_fees_b
+        .checked_add(protocol_fee_b)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    pool.total_lp_supply = remaining_lp_supply;
+    update_volatility_accumulator(pool, price_before, now)?;
+
+    let pool_key = pool.key();
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    if amount_a > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_a_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.token_a_vault.to_account_info(),
+                    mint: ctx.accounts.token_a_mint.to_account_info(),
+                    to: ctx.accounts.user_token_a.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_a,
+            ctx.accounts.token_a_mint.decimals,
+        )?;
+    }
+
+    if amount_b > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_b_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.token_b_vault.to_account_info(),
+                    mint: ctx.accounts.token_b_mint.to_account_info(),
+                    to: ctx.accounts.user_token_b.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_b,
+            ctx.accounts.token_b_mint.decimals,
+        )?;
+    }
+
+    assert_vault_invariants(
+        pool,
+        &mut ctx.accounts.token_a_vault,
+        &mut ctx.accounts.token_b_vault,
+    )?;
+
+    let user_position = &mut ctx.accounts.user_position;
+    record_position_withdrawal(user_position, pool, lp_tokens_to_burn, amount_a, amount_b)?;
+
+    // The imbalance fee goes to the LPs that remain, after the withdrawer has settled
+    accrue_lp_fee_growth(pool, fee_a - protocol_fee_a, true);
+    accrue_lp_fee_growth(pool, fee_b - protocol_fee_b, false);
+
+    emit!(LiquidityRemoved {
+        user: ctx.accounts.user.key(),
+        pool: pool_key,
+        lp_tokens_burned: lp_tokens_to_burn,
+        amount_a_received: amount_a_out,
+        amount_b_received: amount_b_out,
+        new_reserve_a: pool.reserve_a,
+        new_reserve_b: pool.reserve_b,
+        output_mint: None,
+    });
+
+    msg!(
+        "✓ Exact-out liquidity removed: LP burned={} | Received: A={}, B={} | Imbalance fee: A={}, B={} | New reserves: A={}, B={}",
+        lp_tokens_to_burn,
+        amount_a_out,
+        amount_b_out,
+        fee_a,
+        fee_b,
+        pool.reserve_a,
+        pool.reserve_b
+    );
+
+    Ok(())
+}
+
+// ========================================================================
+// MODULE 2.7: RESERVE RECONCILIATION
+// ========================================================================
+
+/// Reset bookkeeping reserves to the actual vault balances
+/// Uncollected protocol fees are excluded so they remain claimable by the treasury
+pub fn sync(ctx: Context<SyncReserves>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    require!(
+        pool.status.allows_reconciliation(),
+        CustomError::PoolReconciliationDisabled
+    );
+    require!(!pool.locked, CustomError::PoolLocked);
+
+    let new_reserve_a = ctx
+        .accounts
+        .token_a_vault
+        .amount
+        .checked_sub(pool.protocol_fees_a)
+        .ok_or(error!(CustomError::VaultInvariantViolated))?;
+    let new_reserve_b = ctx
+        .accounts
+        .token_b_vault
+        .amount
+        .checked_sub(pool.protocol_fees_b)
+        .ok_or(error!(CustomError::VaultInvariantViolated))?;
+
+    require!(
+        new_reserve_a > 0 && new_reserve_b > 0,
+        CustomError::InsufficientLiquidity
+    );
+
+    let previous_reserve_a = pool.reserve_a;
+    let previous_reserve_b = pool.reserve_b;
+    pool.reserve_a = new_reserve_a;
+    pool.reserve_b = new_reserve_b;
+
+    emit!(ReservesSynced {
+        pool: pool.key(),
+        previous_reserve_a,
+        previous_reserve_b,
+        new_reserve_a,
+        new_reserve_b,
+        synced_by: ctx.accounts.caller.key(),
+    });
+
+    msg!(
+        "✓ Reserves synced: A={} -> {} | B={} -> {}",
+        previous_reserve_a,
+        new_reserve_a,
+        previous_reserve_b,
+        new_reserve_b
+    );
+
+    Ok(())
+}
+
+/// Send any vault balance above reserves + protocol fees to the recipient accounts
+pub fn skim(ctx: Context<SkimReserves>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    require!(
+        pool.status.allows_reconciliation(),
+        CustomError::PoolReconciliationDisabled
+    );
+    require!(!pool.locked, CustomError::PoolLocked);
+
+    let (surplus_a, surplus_b) = calculate_vault_surplus(
+        pool,
+        ctx.accounts.token_a_vault.amount,
+        ctx.accounts.token_b_vault.amount,
+    )?;
+
+    require!(surplus_a > 0 || surplus_b > 0, CustomError::NoSurplus);
+
+    let pool_key = pool.key();
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    if surplus_a > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_a_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.token_a_vault.to_account_info(),
+                    mint: ctx.accounts.token_a_mint.to_account_info(),
+                    to: ctx.accounts.recipient_token_a.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            surplus_a,
+            ctx.accounts.token_a_mint.decimals,
+        )?;
+    }
+
+    if surplus_b > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_b_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.token_b_vault.to_account_info(),
+                    mint: ctx.accounts.token_b_mint.to_account_info(),
+                    to: ctx.accounts.recipient_token_b.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            surplus_b,
+            ctx.accounts.token_b_mint.decimals,
+        )?;
+    }
+
+    assert_vault_invariants(
+        pool,
+        &mut ctx.accounts.token_a_vault,
+        &mut ctx.accounts.token_b_vault,
+    )?;
+
+    emit!(SurplusSkimmed {
+        pool: pool_key,
+        amount_a: surplus_a,
+        amount_b: surplus_b,
+        recipient_token_a: ctx.accounts.recipient_token_a.key(),
+        recipient_token_b: ctx.accounts.recipient_token_b.key(),
+        skimmed_by: ctx.accounts.caller.key(),
+    });
+
+    msg!("✓ Surplus skimmed: A={} | B={}", surplus_a, surplus_b);
+
+    Ok(())
+}
+
+// ========================================================================
+// MODULE 2.9: POOL STATUS (EMERGENCY MODES)
+// ========================================================================
+
+/// Set the pool's emergency status (pool authority only)
+/// SwapsPaused stops swaps and limit fills, WithdrawOnly leaves only
+/// remove_liquidity, Frozen blocks every reserve-moving instruction
+pub fn set_pool_status(ctx: Context<SetPoolStatus>, status: PoolStatus) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let previous_status = pool.status;
+
+    require!(previous_status != status, CustomError::PoolStatusUnchanged);
+
+    pool.status = status;
+
+    emit!(PoolStatusChanged {
+        pool: pool.key(),
+        previous_status,
+        new_status: status,
+        changed_by: ctx.accounts.authority.key(),
+        changed_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("⚠️ Pool status: {:?} -> {:?}", previous_status, status);
+
+    Ok(())
+}
+
+// ========================================================================
+// MODULE 2.10: CLOSE POOL
+// ========================================================================
+
+/// Close an empty pool and return its rent to the authority
+/// A pool is empty once every minted LP token has been burned, leaving only
+/// the MINIMUM_LIQUIDITY lock. The dust backing that lock (and any donations)
+/// is swept to the authority so both vaults can be closed.
+/// The LP mint (via its close authority) and the LP stake vault, if staking was
+/// ever used, are closed too, so the pair and fee tier can be created again.
+/// The registry entry is kept as history and stamped with `closed_at`.
+pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    require!(!pool.locked, CustomError::PoolLocked);
+
+    require!(
+        ctx.accounts.lp_token_mint.supply == 0
+            && pool.total_lp_supply == MINIMUM_LIQUIDITY,
+        CustomError::PoolNotEmpty
+    );
+    require!(
+        pool.protocol_fees_a == 0 && pool.protocol_fees_b == 0,
+        CustomError::ProtocolFeesNotCollected
+    );
+
+    let pool_key = pool.key();
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    // Token accounts can only be closed once empty
+    let swept_a = ctx.accounts.token_a_vault.amount;
+    let swept_b = ctx.accounts.token_b_vault.amount;
+
+    if swept_a > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_a_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.token_a_vault.to_account_info(),
+                    mint: ctx.accounts.token_a_mint.to_account_info(),
+                    to: ctx.accounts.recipient_token_a.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            swept_a,
+            ctx.accounts.token_a_mint.decimals,
+        )?;
+    }
+
+    if swept_b > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_b_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.token_b_vault.to_account_info(),
+                    mint: ctx.accounts.token_b_mint.to_account_info(),
+                    to: ctx.accounts.recipient_token_b.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            swept_b,
+            ctx.accounts.token_b_mint.decimals,
+        )?;
+    }
+
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_a_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.token_a_vault.to_account_info(),
+            destination: ctx.accounts.authority.to_account_info(),
+            authority: pool.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_b_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.token_b_vault.to_account_info(),
+            destination: ctx.accounts.authority.to_account_info(),
+            authority: pool.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    // Zero supply means the stake vault is empty too
+    let lp_stake_vault = &ctx.accounts.lp_stake_vault;
+    if *lp_stake_vault.owner == ctx.accounts.token_program.key() && lp_stake_vault.lamports() > 0 {
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: lp_stake_vault.to_account_info(),
+                destination: ctx.accounts.authority.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
+
+    // LP mints created before they carried a close authority stay allocated
+    if mint_has_close_authority(&ctx.accounts.lp_token_mint)? {
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.lp_token_mint.to_account_info(),
+                destination: ctx.accounts.authority.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
+
+    let closed_at = Clock::get()?.unix_timestamp;
+    mark_pool_closed(&mut ctx.accounts.registry_page, pool_key, closed_at)?;
+
+    // The pool account itself is closed to the authority by the `close` constraint
+
+    emit!(PoolClosed {
+        pool: pool_key,
+        token_a_mint: pool.token_a_mint,
+        token_b_mint: pool.token_b_mint,
+        fee_tier_bps: pool.fee_tier_bps,
+        swept_a,
+        swept_b,
+        closed_by: ctx.accounts.authority.key(),
+        closed_at,
+    });
+
+    msg!("✓ Pool {} closed: swept A={} | B={}", pool_key, swept_a, swept_b);
+
+    Ok(())
+}
+
+// ========================================================================
+// MODULE 2.14: DYNAMIC FEES
+// ========================================================================
+
+/// Turn dynamic fee mode on or off (pool authority only)
+/// While enabled the swap fee moves between min_fee_bps and max_fee_bps with
+/// recent volatility instead of the fee tier; both bounds must be 0 to disable.
+/// The volatility accumulator restarts from zero on every change
+pub fn set_dynamic_fee(
+    ctx: Context<SetDynamicFee>,
+    enabled: bool,
+    min_fee_bps: u16,
+    max_fee_bps: u16,
+) -> Result<()> {
+    if enabled {
+        require!(
+            min_fee_bps <= max_fee_bps && max_fee_bps <= MAX_DYNAMIC_FEE_BPS,
+            CustomError::InvalidDynamicFeeBounds
+        );
+    } else {
+        require!(
+            min_fee_bps == 0 && max_fee_bps == 0,
+            CustomError::InvalidDynamicFeeBounds
+        );
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let pool = &mut ctx.accounts.pool;
+    pool.dynamic_fee_enabled = enabled;
+    pool.min_fee_bps = min_fee_bps;
+    pool.max_fee_bps = max_fee_bps;
+    pool.volatility_accumulator = 0;
+    pool.last_swap_timestamp = now;
+
+    emit!(DynamicFeeUpdated {
+        pool: pool.key(),
+        enabled,
+        min_fee_bps,
+        max_fee_bps,
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: now,
+    });
+
+    msg!(
+        "✓ Dynamic fee {}: {}-{} bps",
+        if enabled { "enabled" } else { "disabled" },
+        min_fee_bps,
+        max_fee_bps
+    );
+
+    Ok(())
+}
+
+// ========================================================================
+// MODULE 2.15: POSITION EARNINGS
+// ========================================================================
+
+/// Report a position's fee earnings and impermanent loss (view)
+/// Fees include those pending since the position's last checkpoint. Impermanent
+/// loss compares the position's current share, less fees, with holding its net
+/// deposits, valued in token B at the pool's spot price.
+pub fn get_position_earnings(ctx: Context<GetPositionEarnings>) -> Result<PositionEarnings> {
+    let position = &ctx.accounts.position;
+    let pool = &ctx.accounts.pool;
+
+    let (current_amount_a, current_amount_b) = if position.lp_tokens > 0 {
+        calculate_remove_liquidity_amounts(
+            position.lp_tokens,
+            pool.total_lp_supply,
+            pool.reserve_a,
+            pool.reserve_b,
+        )?
+    } else {
+        (0, 0)
+    };
+
+    let (pending_fees_a, pending_fees_b) = calculate_pending_position_fees(position, pool)?;
+    let fees_earned_a = position
+        .fees_earned_a
+        .checked_add(pending_fees_a)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    let fees_earned_b = position
+        .fees_earned_b
+        .checked_add(pending_fees_b)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
+    let spot_price = calculate_spot_price(pool)?;
+    let impermanent_loss_b = calculate_impermanent_loss(
+        position.deposited_a as i128 - position.withdrawn_a as i128,
+        position.deposited_b as i128 - position.withdrawn_b as i128,
+        current_amount_a,
+        current_amount_b,
+        fees_earned_a,
+        fees_earned_b,
+        spot_price,
+    )?;
+
+    msg!(
+        "📊 Position earnings: Fees A={}, B={} | Impermanent loss={} (token B)",
+        fees_earned_a,
+        fees_earned_b,
+        impermanent_loss_b
+    );
+
+    Ok(PositionEarnings {
+        lp_tokens: position.lp_tokens,
+        current_amount_a,
+        current_amount_b,
+        deposited_a: position.deposited_a,
+        deposited_b: position.deposited_b,
+        withdrawn_a: position.withdrawn_a,
+        withdrawn_b: position.withdrawn_b,
+        fees_earned_a,
+        fees_earned_b,
+        spot_price,
+        impermanent_loss_b,
+    })
+}
+
+// ========================================================================
+// ACCOUNT CONTEXTS
+// ========================================================================
+
+#[derive(Accounts)]
+#[instruction(initial_deposit_a: u64, initial_deposit_b: u64, fee_tier_bps: u16)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = LiquidityPool::SIZE,
+        seeds = [
+            b"pool",
+            token_a_mint.key().as_ref(),
+            token_b_mint.key().as_ref(),
+            fee_tier_bps.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+    
+    #[account(seeds = [b"dex_config"], bump = dex_config.bump)]
+    pub dex_config: Box<Account<'info, DexConfig>>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool_registry"],
+        bump = pool_registry.bump
+    )]
+    pub pool_registry: Box<Account<'info, PoolRegistry>>,
+    
+    /// Registry page the new pool is appended to, allocated on first use
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = PoolRegistryPage::SIZE,
+        seeds = [
+            b"registry_page",
+            pool_registry.current_page_index().to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub registry_page: Box<Account<'info, PoolRegistryPage>>,
+    
+    /// Mints are ordered by bytes so each pair maps to exactly one pool per fee tier
+    #[account(
+        mint::token_program = token_a_program,
+        constraint = token_a_mint.key() < token_b_mint.key() @ CustomError::InvalidMintOrder
+    )]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 6,
+        mint::authority = pool,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = pool,
+        extensions::metadata_pointer::metadata_address = lp_token_mint,
+        extensions::close_authority::authority = pool,
+        seeds = [b"lp_mint", pool.key().as_ref()],
+        bump
+    )]
+    pub lp_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        init,
+        payer = authority,
         token::mint = token_a_mint,
-        token::authority = user
+        token::authority = pool,
+        token::token_program = token_a_program
+    )]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = token_b_mint,
+        token::authority = pool,
+        token::token_program = token_b_program
+    )]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        token::mint = token_a_mint,
+        token::authority = authority,
+        token::token_program = token_a_program
+    )]
+    pub user_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        token::mint = token_b_mint,
+        token::authority = authority,
+        token::token_program = token_b_program
+    )]
+    pub user_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// May survive from an earlier pool at this address that was closed
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = lp_token_mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program
+    )]
+    pub lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// LP mints are Token-2022 so they can carry the metadata extension
+    pub token_program: Program<'info, Token2022>,
+    pub token_a_program: Interface<'info, TokenInterface>,
+    pub token_b_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            token_a_mint.key().as_ref(),
+            token_b_mint.key().as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = lp_token_mint,
+        has_one = token_a_vault @ CustomError::InvalidVault,
+        has_one = token_b_vault @ CustomError::InvalidVault
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+    
+    #[account(
+        mint::token_program = token_a_program,
+        constraint = token_a_mint.key() < token_b_mint.key() @ CustomError::InvalidMintOrder
+    )]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(mut)]
+    pub lp_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserLiquidityPosition::SIZE,
+        seeds = [b"position", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_position: Box<Account<'info, UserLiquidityPosition>>,
+    
+    #[account(mut)]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        token::mint = token_a_mint,
+        token::authority = user,
+        token::token_program = token_a_program
     )]
-    pub user_token_a: Box<Account<'info, TokenAccount>>,
+    pub user_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
         mut,
         token::mint = token_b_mint,
+        token::authority = user,
+        token::token_program = token_b_program
+    )]
+    pub user_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = lp_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub user_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// Token program owning the LP mint
+    pub token_program: Interface<'info, TokenInterface>,
+    pub token_a_program: Interface<'info, TokenInterface>,
+    pub token_b_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AddLiquiditySingleSided<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            token_a_mint.key().as_ref(),
+            token_b_mint.key().as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+    
+    #[account(
+        mint::token_program = token_a_program,
+        constraint = token_a_mint.key() < token_b_mint.key() @ CustomError::InvalidMintOrder
+    )]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(mut, address = pool.lp_token_mint)]
+    pub lp_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserLiquidityPosition::SIZE,
+        seeds = [b"position", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_position: Box<Account<'info, UserLiquidityPosition>>,
+    
+    #[account(mut, address = pool.token_a_vault @ CustomError::InvalidVault)]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(mut, address = pool.token_b_vault @ CustomError::InvalidVault)]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// User's account for the deposited token (token A or B per is_token_a)
+    #[account(
+        mut,
         token::authority = user
     )]
-    pub user_token_b: Box<Account<'info, TokenAccount>>,
+    pub user_token_in: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = lp_token_mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program
     )]
-    pub user_lp_token_account: Box<Account<'info, TokenAccount>>,
+    pub user_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(mut)]
     pub user: Signer<'info>,
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    /// Token program owning the LP mint
+    pub token_program: Interface<'info, TokenInterface>,
+    pub token_a_program: Interface<'info, TokenInterface>,
+    pub token_b_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
 pub struct RemoveLiquidity<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = lp_token_mint,
+        has_one = token_a_vault @ CustomError::InvalidVault,
+        has_one = token_b_vault @ CustomError::InvalidVault
+    )]
     pub pool: Box<Account<'info, LiquidityPool>>,
     
     #[account(mut)]
-    pub lp_token_mint: Box<Account<'info, Mint>>,
+    pub lp_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        address = pool.token_a_mint,
+        mint::token_program = token_a_program
+    )]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        address = pool.token_b_mint,
+        mint::token_program = token_b_program
+    )]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
     
     #[account(
         mut,
         seeds = [b"position", pool.key().as_ref(), user.key().as_ref()],
-        bump
+        bump = user_position.bump
     )]
     pub user_position: Box<Account<'info, UserLiquidityPosition>>,
     
     #[account(mut)]
-    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(mut)]
-    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
         mut,
         token::mint = lp_token_mint,
         token::authority = user
     )]
-    pub user_lp_token_account: Box<Account<'info, TokenAccount>>,
+    pub user_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        token::mint = token_a_mint,
+        token::authority = user,
+        token::token_program = token_a_program
+    )]
+    pub user_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        token::mint = token_b_mint,
+        token::authority = user,
+        token::token_program = token_b_program
+    )]
+    pub user_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// Token program owning the LP mint
+    pub token_program: Interface<'info, TokenInterface>,
+    pub token_a_program: Interface<'info, TokenInterface>,
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveLiquiditySingleSided<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = token_a_mint,
+        has_one = token_b_mint,
+        has_one = lp_token_mint,
+        has_one = token_a_vault @ CustomError::InvalidVault,
+        has_one = token_b_vault @ CustomError::InvalidVault
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+    
+    #[account(mut)]
+    pub lp_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), user.key().as_ref()],
+        bump = user_position.bump
+    )]
+    pub user_position: Box<Account<'info, UserLiquidityPosition>>,
+    
+    #[account(mut)]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
         mut,
-        token::mint = token_a_vault.mint,
+        token::mint = lp_token_mint,
         token::authority = user
     )]
-    pub user_token_a: Box<Account<'info, TokenAccount>>,
+    pub user_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     
+    /// User's account for the withdrawn token (token A or B per is_token_a_out)
     #[account(
         mut,
-        token::mint = token_b_vault.mint,
         token::authority = user
     )]
-    pub user_token_b: Box<Account<'info, TokenAccount>>,
+    pub user_token_out: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(mut)]
     pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    /// Token program owning the LP mint
+    pub token_program: Interface<'info, TokenInterface>,
+    pub token_a_program: Interface<'info, TokenInterface>,
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SyncReserves<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = token_a_vault @ CustomError::InvalidVault,
+        has_one = token_b_vault @ CustomError::InvalidVault
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+    
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SkimReserves<'info> {
+    #[account(
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = token_a_vault @ CustomError::InvalidVault,
+        has_one = token_b_vault @ CustomError::InvalidVault
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+    
+    #[account(
+        address = pool.token_a_mint,
+        mint::token_program = token_a_program
+    )]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        address = pool.token_b_mint,
+        mint::token_program = token_b_program
+    )]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(mut)]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(mut, token::mint = pool.token_a_mint)]
+    pub recipient_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(mut, token::mint = pool.token_b_mint)]
+    pub recipient_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub caller: Signer<'info>,
+    pub token_a_program: Interface<'info, TokenInterface>,
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetPoolStatus<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = authority @ CustomError::InvalidAuthority
+    )]
+    pub pool: Account<'info, LiquidityPool>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetDynamicFee<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = authority @ CustomError::InvalidAuthority
+    )]
+    pub pool: Account<'info, LiquidityPool>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = authority @ CustomError::InvalidAuthority,
+        has_one = token_a_vault @ CustomError::InvalidVault,
+        has_one = token_b_vault @ CustomError::InvalidVault,
+        has_one = lp_token_mint
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+    
+    #[account(
+        address = pool.token_a_mint,
+        mint::token_program = token_a_program
+    )]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        address = pool.token_b_mint,
+        mint::token_program = token_b_program
+    )]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(mut, mint::token_program = token_program)]
+    pub lp_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// CHECK: the pool's LP stake vault PDA; only exists once LP has been staked,
+    /// and is closed by close_pool when it does
+    #[account(mut, seeds = [b"lp_stake_vault", pool.key().as_ref()], bump)]
+    pub lp_stake_vault: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(mut, token::mint = pool.token_a_mint)]
+    pub recipient_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(mut, token::mint = pool.token_b_mint)]
+    pub recipient_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Registry page holding the pool's live entry
+    #[account(
+        mut,
+        seeds = [b"registry_page", registry_page.page_index.to_le_bytes().as_ref()],
+        bump = registry_page.bump
+    )]
+    pub registry_page: Box<Account<'info, PoolRegistryPage>>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// LP mints are Token-2022
+    pub token_program: Program<'info, Token2022>,
+    pub token_a_program: Interface<'info, TokenInterface>,
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct GetPositionEarnings<'info> {
+    #[account(
+        seeds = [b"position", pool.key().as_ref(), position.owner.as_ref()],
+        bump = position.bump,
+        has_one = pool @ CustomError::PoolMismatch
+    )]
+    pub position: Account<'info, UserLiquidityPosition>,
+    #[account(
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, LiquidityPool>,
 }
@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    DexConfig,
+    LiquidityPool,
+    PoolRegistry,
+    PoolRegistryEntry,
+    PoolRegistryPage,
+    POOLS_PER_REGISTRY_PAGE,
+};
+use crate::errors::CustomError;
+use crate::events::PoolRegistered;
+
+// ========================================================================
+// MODULE 5.3: INITIALIZE POOL REGISTRY
+// ========================================================================
+
+/// Initialize the pool registry (factory) header (admin only)
+/// Pages are allocated lazily by initialize_pool as pools are created
+pub fn initialize_pool_registry(
+    ctx: Context<InitializePoolRegistry>,
+) -> Result<()> {
+    let pool_registry = &mut ctx.accounts.pool_registry;
+
+    pool_registry.pool_count = 0;
+    pool_registry.page_count = 0;
+    pool_registry.bump = ctx.bumps.pool_registry;
+
+    msg!(
+        "✓ Pool registry initialized: {} pools per page",
+        POOLS_PER_REGISTRY_PAGE
+    );
+
+    Ok(())
+}
+
+// ========================================================================
+// MODULE 5.3: REGISTER POOL (called from initialize_pool and migrate_account)
+// ========================================================================
+
+/// Append a freshly created (or migrated legacy) pool to the current registry page
+/// Returns the pool's global index in the registry
+pub(crate) fn register_pool<'info>(
+    pool_registry: &mut Account<'info, PoolRegistry>,
+    registry_page: &mut Account<'info, PoolRegistryPage>,
+    registry_page_bump: u8,
+    pool_key: Pubkey,
+    pool: &LiquidityPool,
+) -> Result<u64> {
+    let page_index = pool_registry.current_page_index();
+
+    // First pool on this page: the page was just allocated by init_if_needed
+    if registry_page.entries.is_empty() {
+        registry_page.page_index = page_index;
+        registry_page.bump = registry_page_bump;
+        pool_registry.page_count = pool_registry
+            .page_count
+            .checked_add(1)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+    }
+
+    require!(
+        registry_page.entries.len() < POOLS_PER_REGISTRY_PAGE,
+        CustomError::RegistryPageFull
+    );
+
+    let created_at = Clock::get()?.unix_timestamp;
+    registry_page.entries.push(PoolRegistryEntry {
+        pool: pool_key,
+        token_a_mint: pool.token_a_mint,
+        token_b_mint: pool.token_b_mint,
+        fee_tier_bps: pool.fee_tier_bps,
+        created_at,
+        closed_at: 0,
+    });
+
+    let pool_index = pool_registry.pool_count;
+    pool_registry.pool_count = pool_registry
+        .pool_count
+        .checked_add(1)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
+    emit!(PoolRegistered {
+        pool: pool_key,
+        registry_page: registry_page.key(),
+        pool_index,
+        token_a_mint: pool.token_a_mint,
+        token_b_mint: pool.token_b_mint,
+        fee_tier_bps: pool.fee_tier_bps,
+        created_at,
+    });
+
+    Ok(pool_index)
+}
+
+// ========================================================================
+// MODULE 5.3: MARK POOL CLOSED (called from close_pool)
+// ========================================================================
+
+/// Stamp the pool's live registry entry as closed
+/// The entry stays on its page as history; a pool re-created at the same
+/// address is appended as a new entry
+pub(crate) fn mark_pool_closed(
+    registry_page: &mut Account<'_, PoolRegistryPage>,
+    pool: Pubkey,
+    closed_at: i64,
+) -> Result<()> {
+    let entry = registry_page
+        .entries
+        .iter_mut()
+        .find(|entry| entry.pool == pool && entry.closed_at == 0)
+        .ok_or(error!(CustomError::RegistryEntryNotFound))?;
+    entry.closed_at = closed_at;
+    Ok(())
+}
+
+// ========================================================================
+// ACCOUNT CONTEXTS
+// ========================================================================
+
+#[derive(Accounts)]
+pub struct InitializePoolRegistry<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = PoolRegistry::SIZE,
+        seeds = [b"pool_registry"],
+        bump
+    )]
+    pub pool_registry: Account<'info, PoolRegistry>,
+
+    #[account(
+        seeds = [b"dex_config"],
+        bump = dex_config.bump,
+        has_one = authority @ CustomError::InvalidAuthority
+    )]
+    pub dex_config: Account<'info, DexConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,12 +1,21 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Token, TokenAccount, Mint, MintTo, mint_to},
+    token_interface::{Mint, TokenAccount, TokenInterface, Token2022, MintTo, mint_to},
 };
 
-use crate::state::{RushConfig, UserLiquidityPosition, LiquidityPool};
+use crate::state::{RushConfig, UserLiquidityPosition, LiquidityPool, ACCOUNT_VERSION};
 use crate::errors::CustomError;
 use crate::events::{RushTokenInitialized, RewardsClaimed, RewardsConfigUpdated, RewardsPaused};
+use crate::utils::{
+    initialize_token_metadata,
+    calculate_rush_per_share_increment,
+    calculate_reward_debt,
+    calculate_staking_rewards,
+    advance_rush_reward_index,
+    current_rush_reward_index,
+    update_rush_accumulator,
+};
 
 // ========================================================================
 // MODULE 4.2: INITIALIZE RUSH TOKEN
@@ -23,6 +32,8 @@ pub fn initialize_rush_token(
     const APY_NUMERATOR: u64 = 50;
     const APY_DENOMINATOR: u64 = 100;
     const SECONDS_PER_YEAR: u64 = 31_536_000;
+    const RUSH_NAME: &str = "SolRush";
+    const RUSH_SYMBOL: &str = "RUSH";
     
     // Calculate yearly rewards
     let yearly_rewards = (MAX_RUSH_SUPPLY as u128 * APY_NUMERATOR as u128)
@@ -46,7 +57,8 @@ pub fn initialize_rush_token(
     let now_timestamp = Clock::get()?.unix_timestamp;
     
     rush_config.mint = ctx.accounts.rush_mint.key();
-    rush_config.authority = rush_config.key();
+    // Admin is the initializer; the mint itself is controlled by the rush_config PDA
+    rush_config.authority = ctx.accounts.authority.key();
     rush_config.total_supply = MAX_RUSH_SUPPLY_BASE;
     rush_config.minted_so_far = 0;
     rush_config.rewards_per_second = rewards_per_second_base;
@@ -54,7 +66,25 @@ pub fn initialize_rush_token(
     rush_config.apy_denominator = APY_DENOMINATOR;
     rush_config.start_timestamp = now_timestamp;
     rush_config.is_paused = false;
+    rush_config.reward_index = 0;
+    rush_config.reward_index_updated_at = now_timestamp;
     rush_config.bump = ctx.bumps.rush_config;
+    rush_config.version = ACCOUNT_VERSION;
+    
+    // RUSH metadata lives on the mint itself via the Token-2022 metadata extension
+    let bump_seed = rush_config.bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"rush_config", &[bump_seed]]];
+    initialize_token_metadata(
+        &ctx.accounts.token_program,
+        &ctx.accounts.system_program,
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.rush_mint.to_account_info(),
+        rush_config.to_account_info(),
+        signer_seeds,
+        RUSH_NAME.to_string(),
+        RUSH_SYMBOL.to_string(),
+        String::new(),
+    )?;
     
     emit!(RushTokenInitialized {
         rush_mint: ctx.accounts.rush_mint.key(),
@@ -84,6 +114,7 @@ pub fn initialize_rush_token(
 // ========================================================================
 
 /// Calculate pending RUSH rewards for a liquidity provider
+/// Only staked LP accrues (Module 4.7); settled but unclaimed rewards are included
 pub fn calculate_pending_rewards(
     ctx: Context<CalculateRewards>,
 ) -> Result<u64> {
@@ -92,41 +123,34 @@ pub fn calculate_pending_rewards(
     let rush_config = &ctx.accounts.rush_config;
     let current_time = Clock::get()?.unix_timestamp;
     
-    require!(position.lp_tokens > 0, CustomError::InvalidAmount);
-    require!(pool.total_lp_supply > 0, CustomError::InsufficientLiquidity);
-    
-    // Calculate time elapsed
-    let time_elapsed = current_time
-        .checked_sub(position.last_claim_timestamp)
-        .ok_or(error!(CustomError::CalculationOverflow))? as u64;
-    
-    if time_elapsed == 0 {
-        return Ok(0);
-    }
+    require!(
+        position.staked_lp > 0 || position.unclaimed_rewards > 0,
+        CustomError::InvalidAmount
+    );
     
-    // User share (fixed-point with 10^12 scaling)
-    let user_share_fixed = (position.lp_tokens as u128)
-        .checked_mul(1_000_000_000_000u128)
-        .ok_or(error!(CustomError::CalculationOverflow))?
-        .checked_div(pool.total_lp_supply as u128)
+    // Project the pool accumulator to now without writing it
+    let reward_index = current_rush_reward_index(rush_config, current_time)?;
+    let pending_increment = calculate_rush_per_share_increment(
+        reward_index
+            .checked_sub(pool.rush_index_snapshot)
+            .ok_or(error!(CustomError::CalculationOverflow))?,
+        pool.total_staked_lp,
+    )?;
+    let acc_rush_per_share = pool
+        .acc_rush_per_share
+        .checked_add(pending_increment)
         .ok_or(error!(CustomError::CalculationOverflow))?;
     
-    // Period rewards
-    let period_rewards_fixed = (rush_config.rewards_per_second as u128)
-        .checked_mul(time_elapsed as u128)
-        .ok_or(error!(CustomError::CalculationOverflow))?;
+    let accrued = calculate_staking_rewards(
+        position.staked_lp,
+        acc_rush_per_share,
+        position.reward_debt,
+    )?;
     
-    // User rewards
-    let user_rewards_fixed = period_rewards_fixed
-        .checked_mul(user_share_fixed)
-        .ok_or(error!(CustomError::CalculationOverflow))?
-        .checked_div(1_000_000_000_000u128)
+    let user_rewards = position.unclaimed_rewards
+        .checked_add(accrued)
         .ok_or(error!(CustomError::CalculationOverflow))?;
     
-    let user_rewards = user_rewards_fixed
-        .try_into()
-        .map_err(|_| error!(CustomError::CalculationOverflow))?;
-    
     // Validate against max supply
     let new_minted_total = rush_config.minted_so_far
         .checked_add(user_rewards)
@@ -155,42 +179,32 @@ pub fn claim_rush_rewards(
     ctx: Context<ClaimRewards>,
 ) -> Result<()> {
     let position = &mut ctx.accounts.position;
-    let pool = &ctx.accounts.pool;
+    let pool = &mut ctx.accounts.pool;
     let rush_config = &mut ctx.accounts.rush_config;
     let current_time = Clock::get()?.unix_timestamp;
     
     // Check if rewards are paused
     require!(!rush_config.is_paused, CustomError::InvalidAmount);
     
-    // Validation
-    require!(position.lp_tokens > 0, CustomError::InvalidAmount);
-    require!(pool.total_lp_supply > 0, CustomError::InsufficientLiquidity);
+    // Validation: only staked LP earns
+    require!(
+        position.staked_lp > 0 || position.unclaimed_rewards > 0,
+        CustomError::InvalidAmount
+    );
     
     // Calculate rewards
-    let time_elapsed = current_time
-        .checked_sub(position.last_claim_timestamp)
-        .ok_or(error!(CustomError::CalculationOverflow))? as u64;
-    
-    let user_share_fixed = (position.lp_tokens as u128)
-        .checked_mul(1_000_000_000_000u128)
-        .ok_or(error!(CustomError::CalculationOverflow))?
-        .checked_div(pool.total_lp_supply as u128)
-        .ok_or(error!(CustomError::CalculationOverflow))?;
-    
-    let period_rewards_fixed = (rush_config.rewards_per_second as u128)
-        .checked_mul(time_elapsed as u128)
-        .ok_or(error!(CustomError::CalculationOverflow))?;
+    let reward_index = current_rush_reward_index(rush_config, current_time)?;
+    update_rush_accumulator(pool, reward_index, current_time)?;
+    let accrued = calculate_staking_rewards(
+        position.staked_lp,
+        pool.acc_rush_per_share,
+        position.reward_debt,
+    )?;
     
-    let user_rewards_fixed = period_rewards_fixed
-        .checked_mul(user_share_fixed)
-        .ok_or(error!(CustomError::CalculationOverflow))?
-        .checked_div(1_000_000_000_000u128)
+    let user_rewards = position.unclaimed_rewards
+        .checked_add(accrued)
         .ok_or(error!(CustomError::CalculationOverflow))?;
     
-    let user_rewards = user_rewards_fixed
-        .try_into()
-        .map_err(|_| error!(CustomError::CalculationOverflow))?;
-    
     require!(user_rewards > 0, CustomError::InvalidAmount);
     
     // Validate max supply
@@ -222,6 +236,8 @@ pub fn claim_rush_rewards(
     
     // Update state
     position.last_claim_timestamp = current_time;
+    position.unclaimed_rewards = 0;
+    position.reward_debt = calculate_reward_debt(position.staked_lp, pool.acc_rush_per_share)?;
     position.total_rush_claimed = position.total_rush_claimed
         .checked_add(user_rewards)
         .ok_or(error!(CustomError::CalculationOverflow))?;
@@ -235,8 +251,8 @@ pub fn claim_rush_rewards(
         pool: pool.key(),
         rewards_amount: user_rewards,
         rewards_display: user_rewards as f64 / 1_000_000.0,
-        time_elapsed: time_elapsed as i64,
-        user_lp_share: user_share_fixed as f64 / 1_000_000_000_000.0,
+        staked_lp: position.staked_lp,
+        total_staked_lp: pool.total_staked_lp,
         claimed_at: current_time,
         total_claimed_lifetime: position.total_rush_claimed,
     });
@@ -262,13 +278,6 @@ pub fn update_rush_apy(
     let rush_config = &mut ctx.accounts.rush_config;
     let current_time = Clock::get()?.unix_timestamp;
     
-    // Verify authority
-    require_eq!(
-        ctx.accounts.authority.key(),
-        rush_config.authority,
-        CustomError::InvalidAuthority
-    );
-    
     // Validate APY (0-500%)
     require!(new_apy > 0 && new_apy <= 500, CustomError::InvalidAmount);
     
@@ -286,6 +295,9 @@ pub fn update_rush_apy(
         .try_into()
         .map_err(|_| error!(CustomError::CalculationOverflow))?;
     
+    // Emissions up to now are priced at the old rate
+    advance_rush_reward_index(rush_config, current_time)?;
+    
     let previous_apy = rush_config.apy_numerator;
     rush_config.apy_numerator = new_apy;
     rush_config.rewards_per_second = new_rewards_per_second;
@@ -310,12 +322,8 @@ pub fn pause_rush_rewards(
     let rush_config = &mut ctx.accounts.rush_config;
     let current_time = Clock::get()?.unix_timestamp;
     
-    // Verify authority
-    require_eq!(
-        ctx.accounts.authority.key(),
-        rush_config.authority,
-        CustomError::InvalidAuthority
-    );
+    // No RUSH is emitted while paused, so close out the index first
+    advance_rush_reward_index(rush_config, current_time)?;
     
     // Toggle pause state
     let was_paused = rush_config.is_paused;
@@ -359,62 +367,115 @@ pub struct InitializeRushToken<'info> {
         payer = authority,
         mint::decimals = 6,
         mint::authority = rush_config,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = rush_config,
+        extensions::metadata_pointer::metadata_address = rush_mint,
     )]
-    pub rush_mint: Account<'info, Mint>,
+    pub rush_mint: InterfaceAccount<'info, Mint>,
     
     #[account(mut)]
     pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    /// RUSH is a Token-2022 mint so it can carry the metadata extension
+    pub token_program: Program<'info, Token2022>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
 pub struct CalculateRewards<'info> {
+    #[account(
+        seeds = [b"position", pool.key().as_ref(), position.owner.as_ref()],
+        bump = position.bump,
+        has_one = pool @ CustomError::PoolMismatch
+    )]
     pub position: Account<'info, UserLiquidityPosition>,
+    
+    #[account(
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump
+    )]
     pub pool: Account<'info, LiquidityPool>,
+    
+    #[account(seeds = [b"rush_config"], bump = rush_config.bump)]
     pub rush_config: Account<'info, RushConfig>,
 }
 
 #[derive(Accounts)]
 pub struct ClaimRewards<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), user.key().as_ref()],
+        bump = position.bump,
+        has_one = pool @ CustomError::PoolMismatch
+    )]
     pub position: Account<'info, UserLiquidityPosition>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump
+    )]
     pub pool: Account<'info, LiquidityPool>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"rush_config"],
+        bump = rush_config.bump
+    )]
     pub rush_config: Account<'info, RushConfig>,
     
-    #[account(mut)]
-    pub rush_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        address = rush_config.mint @ CustomError::InvalidRushMint
+    )]
+    pub rush_mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = rush_mint,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_rush_account: Account<'info, TokenAccount>,
+    pub user_rush_account: InterfaceAccount<'info, TokenAccount>,
     
     #[account(mut)]
     pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct UpdateRushAPY<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"rush_config"],
+        bump = rush_config.bump,
+        has_one = authority @ CustomError::InvalidAuthority
+    )]
     pub rush_config: Account<'info, RushConfig>,
     pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
 pub struct PauseRewards<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"rush_config"],
+        bump = rush_config.bump,
+        has_one = authority @ CustomError::InvalidAuthority
+    )]
     pub rush_config: Account<'info, RushConfig>,
     pub authority: Signer<'info>,
 }
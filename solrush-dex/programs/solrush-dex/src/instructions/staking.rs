@@ -0,0 +1,304 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint,
+    TokenAccount,
+    TokenInterface,
+    TransferChecked,
+    transfer_checked,
+};
+
+use crate::state::{LiquidityPool, RushConfig, UserLiquidityPosition, ACCOUNT_VERSION};
+use crate::errors::CustomError;
+use crate::events::{LpStaked, LpUnstaked};
+use crate::utils::{
+    calculate_reward_debt,
+    calculate_staking_rewards,
+    current_rush_reward_index,
+    update_rush_accumulator,
+};
+
+// ========================================================================
+// MODULE 4.7: LP STAKING
+// ========================================================================
+//
+// RUSH rewards accrue only on LP escrowed in the pool's stake vault, so a
+// position's reward weight always matches tokens the program actually holds.
+// RushConfig keeps `reward_index`, the RUSH each pool has emitted since launch,
+// advanced whenever the rate changes or rewards are paused. Each pool turns its
+// share of the index growth since `rush_index_snapshot` into `acc_rush_per_share`,
+// the RUSH emitted per staked LP, and every stake, unstake and claim brings it
+// up to date first.
+// A position owes `staked_lp * acc_rush_per_share - reward_debt`; that amount
+// is settled into `unclaimed_rewards` before every balance change and paid out
+// by claim_rush_rewards.
+
+/// Escrow LP tokens in the pool's stake vault to start earning RUSH
+pub fn stake_lp(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+    require!(amount > 0, CustomError::InvalidAmount);
+    require!(
+        ctx.accounts.user_lp_token_account.amount >= amount,
+        CustomError::InsufficientLPBalance
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let pool = &mut ctx.accounts.pool;
+    let user_position = &mut ctx.accounts.user_position;
+
+    // LP received by transfer can be staked without ever calling add_liquidity
+    if user_position.owner == Pubkey::default() {
+        user_position.owner = ctx.accounts.user.key();
+        user_position.pool = pool.key();
+        user_position.deposit_timestamp = now;
+        user_position.last_claim_timestamp = now;
+        user_position.bump = ctx.bumps.user_position;
+        user_position.version = ACCOUNT_VERSION;
+    }
+
+    let reward_index = current_rush_reward_index(&ctx.accounts.rush_config, now)?;
+    update_rush_accumulator(pool, reward_index, now)?;
+    let rewards_settled = settle_staking_rewards(user_position, pool.acc_rush_per_share, now)?;
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_lp_token_account.to_account_info(),
+                mint: ctx.accounts.lp_token_mint.to_account_info(),
+                to: ctx.accounts.lp_stake_vault.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.lp_token_mint.decimals,
+    )?;
+
+    user_position.staked_lp = user_position
+        .staked_lp
+        .checked_add(amount)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    user_position.reward_debt =
+        calculate_reward_debt(user_position.staked_lp, pool.acc_rush_per_share)?;
+    pool.total_staked_lp = pool
+        .total_staked_lp
+        .checked_add(amount)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
+    emit!(LpStaked {
+        user: ctx.accounts.user.key(),
+        pool: pool.key(),
+        position: user_position.key(),
+        amount,
+        staked_balance: user_position.staked_lp,
+        total_staked: pool.total_staked_lp,
+        rewards_settled,
+        timestamp: now,
+    });
+
+    msg!(
+        "✓ Staked {} LP | Position: {} | Pool total: {}",
+        amount,
+        user_position.staked_lp,
+        pool.total_staked_lp
+    );
+
+    Ok(())
+}
+
+/// Return staked LP tokens from the stake vault to the owner
+/// Rewards earned so far stay claimable via claim_rush_rewards
+pub fn unstake_lp(ctx: Context<UnstakeLp>, amount: u64) -> Result<()> {
+    require!(amount > 0, CustomError::InvalidAmount);
+    require!(
+        ctx.accounts.user_position.staked_lp >= amount,
+        CustomError::InsufficientStakedBalance
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let pool = &mut ctx.accounts.pool;
+    let user_position = &mut ctx.accounts.user_position;
+
+    let reward_index = current_rush_reward_index(&ctx.accounts.rush_config, now)?;
+    update_rush_accumulator(pool, reward_index, now)?;
+    let rewards_settled = settle_staking_rewards(user_position, pool.acc_rush_per_share, now)?;
+
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.lp_stake_vault.to_account_info(),
+                mint: ctx.accounts.lp_token_mint.to_account_info(),
+                to: ctx.accounts.user_lp_token_account.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        ctx.accounts.lp_token_mint.decimals,
+    )?;
+
+    user_position.staked_lp -= amount;
+    user_position.reward_debt =
+        calculate_reward_debt(user_position.staked_lp, pool.acc_rush_per_share)?;
+    pool.total_staked_lp = pool
+        .total_staked_lp
+        .checked_sub(amount)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
+    emit!(LpUnstaked {
+        user: ctx.accounts.user.key(),
+        pool: pool.key(),
+        position: user_position.key(),
+        amount,
+        staked_balance: user_position.staked_lp,
+        total_staked: pool.total_staked_lp,
+        rewards_settled,
+        timestamp: now,
+    });
+
+    msg!(
+        "✓ Unstaked {} LP | Position: {} | Pool total: {}",
+        amount,
+        user_position.staked_lp,
+        pool.total_staked_lp
+    );
+
+    Ok(())
+}
+
+/// Move rewards accrued at the current staked balance into unclaimed_rewards
+/// and restart the claim clock. The caller resets reward_debt once staked_lp
+/// has its new value. Returns the amount settled.
+fn settle_staking_rewards(
+    position: &mut UserLiquidityPosition,
+    acc_rush_per_share: u128,
+    now: i64,
+) -> Result<u64> {
+    let accrued = calculate_staking_rewards(
+        position.staked_lp,
+        acc_rush_per_share,
+        position.reward_debt,
+    )?;
+
+    position.unclaimed_rewards = position
+        .unclaimed_rewards
+        .checked_add(accrued)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    position.last_claim_timestamp = now;
+
+    Ok(accrued)
+}
+
+// ============================================================================
+// CONTEXT STRUCTURES
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct StakeLp<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = lp_token_mint
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    #[account(seeds = [b"rush_config"], bump = rush_config.bump)]
+    pub rush_config: Box<Account<'info, RushConfig>>,
+
+    #[account(mint::token_program = token_program)]
+    pub lp_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserLiquidityPosition::SIZE,
+        seeds = [b"position", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_position: Box<Account<'info, UserLiquidityPosition>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"lp_stake_vault", pool.key().as_ref()],
+        bump,
+        token::mint = lp_token_mint,
+        token::authority = pool,
+        token::token_program = token_program
+    )]
+    pub lp_stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = lp_token_mint,
+        token::authority = user,
+        token::token_program = token_program
+    )]
+    pub user_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// Token program owning the LP mint
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeLp<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = lp_token_mint
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    #[account(seeds = [b"rush_config"], bump = rush_config.bump)]
+    pub rush_config: Box<Account<'info, RushConfig>>,
+
+    #[account(mint::token_program = token_program)]
+    pub lp_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), user.key().as_ref()],
+        bump = user_position.bump,
+        has_one = pool
+    )]
+    pub user_position: Box<Account<'info, UserLiquidityPosition>>,
+
+    #[account(
+        mut,
+        seeds = [b"lp_stake_vault", pool.key().as_ref()],
+        bump,
+        token::mint = lp_token_mint,
+        token::authority = pool,
+        token::token_program = token_program
+    )]
+    pub lp_stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = lp_token_mint,
+        token::authority = user,
+        token::token_program = token_program
+    )]
+    pub user_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub user: Signer<'info>,
+    /// Token program owning the LP mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -1,10 +1,26 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+use anchor_spl::token_interface::{
+    Mint,
+    TokenAccount,
+    TokenInterface,
+    TransferChecked,
+    transfer_checked,
+};
 
 use crate::state::LiquidityPool;
 use crate::errors::CustomError;
 use crate::events::SwapExecuted;
-use crate::utils::calculate_output_amount;
+use crate::utils::{
+    calculate_swap_output,
+    calculate_spot_price,
+    calculate_effective_fee_numerator,
+    update_volatility_accumulator,
+    accrue_lp_fee_growth,
+    calculate_protocol_fee,
+    calculate_amount_after_transfer_fee,
+    assert_vault_invariants,
+    check_deadline,
+};
 
 // ========================================================================
 // MODULE 3.1: SWAP
@@ -17,11 +33,16 @@ pub fn swap(
     amount_in: u64,
     minimum_amount_out: u64,
     is_a_to_b: bool,
+    deadline: Option<i64>,
 ) -> Result<()> {
+    check_deadline(deadline)?;
+
     // Validation: Input amount must be greater than 0
     require!(amount_in > 0, CustomError::InvalidAmount);
 
     let pool = &mut ctx.accounts.pool;
+    require!(pool.status.allows_swaps(), CustomError::PoolSwapsDisabled);
+    require!(!pool.locked, CustomError::PoolLocked);
 
     // Determine input/output reserves based on direction
     let (input_reserve, output_reserve) = if is_a_to_b {
@@ -42,18 +63,22 @@ pub fn swap(
         CustomError::InsufficientBalance
     );
 
-    // Calculate output using constant product formula with fee
-    let amount_out = calculate_output_amount(
-        amount_in,
-        input_reserve,
-        output_reserve,
-        pool.fee_numerator,
-        pool.fee_denominator,
-    )?;
+    // Reserves are credited with what the vault receives after any transfer fee
+    let amount_in_received = calculate_amount_after_transfer_fee(&ctx.accounts.token_in_mint, amount_in)?;
+    require!(amount_in_received > 0, CustomError::InvalidAmount);
+
+    // Dynamic fee mode prices the swap on recent volatility
+    let now = Clock::get()?.unix_timestamp;
+    let fee_numerator = calculate_effective_fee_numerator(pool, now);
+    let price_before = calculate_spot_price(pool)?;
 
-    // Slippage protection: verify output >= minimum_amount_out
+    // Calculate output on the pool's curve (constant product, StableSwap or weighted) with fee
+    let amount_out = calculate_swap_output(pool, amount_in_received, is_a_to_b, fee_numerator)?;
+
+    // Slippage protection is checked against what the user receives after transfer fees
+    let amount_out_received = calculate_amount_after_transfer_fee(&ctx.accounts.token_out_mint, amount_out)?;
     require!(
-        amount_out >= minimum_amount_out,
+        amount_out_received >= minimum_amount_out,
         CustomError::SlippageTooHigh
     );
 
@@ -64,30 +89,42 @@ pub fn swap(
     );
 
     // Calculate fee for tracking
-    let fee_amount = (amount_in as u128)
-        .checked_mul(pool.fee_numerator as u128)
+    let fee_amount = (amount_in_received as u128)
+        .checked_mul(fee_numerator as u128)
         .ok_or(error!(CustomError::CalculationOverflow))?
         .checked_div(pool.fee_denominator as u128)
         .ok_or(error!(CustomError::CalculationOverflow))? as u64;
 
+    // Protocol share of the fee stays in the vault but outside the curve reserves
+    let protocol_fee = calculate_protocol_fee(fee_amount, pool.protocol_fee_share_bps)?;
+    let amount_in_to_reserve = amount_in_received
+        .checked_sub(protocol_fee)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
     // Transfer input tokens from user to pool vault
-    transfer(
+    transfer_checked(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_in_program.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.user_token_in.to_account_info(),
+                mint: ctx.accounts.token_in_mint.to_account_info(),
                 to: ctx.accounts.pool_vault_in.to_account_info(),
                 authority: ctx.accounts.user.to_account_info(),
             },
         ),
         amount_in,
+        ctx.accounts.token_in_mint.decimals,
     )?;
 
     // Update pool reserves (input increases, output decreases)
     if is_a_to_b {
         pool.reserve_a = pool
             .reserve_a
-            .checked_add(amount_in)
+            .checked_add(amount_in_to_reserve)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+        pool.protocol_fees_a = pool
+            .protocol_fees_a
+            .checked_add(protocol_fee)
             .ok_or(error!(CustomError::CalculationOverflow))?;
         pool.reserve_b = pool
             .reserve_b
@@ -96,46 +133,57 @@ pub fn swap(
     } else {
         pool.reserve_b = pool
             .reserve_b
-            .checked_add(amount_in)
+            .checked_add(amount_in_to_reserve)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+        pool.protocol_fees_b = pool
+            .protocol_fees_b
+            .checked_add(protocol_fee)
             .ok_or(error!(CustomError::CalculationOverflow))?;
         pool.reserve_a = pool
             .reserve_a
             .checked_sub(amount_out)
             .ok_or(error!(CustomError::InsufficientPoolReserves))?;
     }
+    // LPs earn the fee net of the protocol share
+    accrue_lp_fee_growth(pool, fee_amount - protocol_fee, is_a_to_b);
+    update_volatility_accumulator(pool, price_before, now)?;
 
     // Transfer output tokens from pool vault to user
     let pool_key = pool.key();
-    let token_a_mint = pool.token_a_mint;
-    let token_b_mint = pool.token_b_mint;
-    let bump_seed = pool.bump;
-    let signer_seeds: &[&[&[u8]]] = &[&[
-        b"pool",
-        token_a_mint.as_ref(),
-        token_b_mint.as_ref(),
-        &[bump_seed],
-    ]];
-
-    transfer(
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    transfer_checked(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_out_program.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.pool_vault_out.to_account_info(),
+                mint: ctx.accounts.token_out_mint.to_account_info(),
                 to: ctx.accounts.user_token_out.to_account_info(),
                 authority: pool.to_account_info(),
             },
             signer_seeds,
         ),
         amount_out,
+        ctx.accounts.token_out_mint.decimals,
     )?;
 
+    let (vault_a, vault_b) = if is_a_to_b {
+        (&mut ctx.accounts.pool_vault_in, &mut ctx.accounts.pool_vault_out)
+    } else {
+        (&mut ctx.accounts.pool_vault_out, &mut ctx.accounts.pool_vault_in)
+    };
+    assert_vault_invariants(pool, vault_a, vault_b)?;
+
     // Emit swap event
     emit!(SwapExecuted {
         user: ctx.accounts.user.key(),
         pool: pool_key,
-        amount_in,
+        amount_in: amount_in_received,
         amount_out,
         fee_amount,
+        fee_bps: fee_numerator as u16,
+        protocol_fee_amount: protocol_fee,
         is_a_to_b,
         new_reserve_a: pool.reserve_a,
         new_reserve_b: pool.reserve_b,
@@ -170,11 +218,16 @@ pub fn market_buy(
     ctx: Context<MarketBuy>,
     usdc_amount: u64,
     min_sol_received: u64,
+    deadline: Option<i64>,
 ) -> Result<()> {
+    check_deadline(deadline)?;
+
     // Validation: USDC amount must be greater than 0
     require!(usdc_amount > 0, CustomError::InvalidAmount);
 
     let pool = &mut ctx.accounts.pool;
+    require!(pool.status.allows_swaps(), CustomError::PoolSwapsDisabled);
+    require!(!pool.locked, CustomError::PoolLocked);
 
     // Determine input/output reserves for USDC→SOL swap (is_a_to_b=false, so B→A)
     let (input_reserve, output_reserve) = (pool.reserve_b, pool.reserve_a);
@@ -191,18 +244,22 @@ pub fn market_buy(
         CustomError::InsufficientBalance
     );
 
-    // Calculate SOL output using constant product formula with 0.3% fee
-    let sol_amount_out = calculate_output_amount(
-        usdc_amount,
-        input_reserve,
-        output_reserve,
-        pool.fee_numerator,
-        pool.fee_denominator,
-    )?;
+    // Reserves are credited with what the vault receives after any transfer fee
+    let usdc_amount_received = calculate_amount_after_transfer_fee(&ctx.accounts.token_in_mint, usdc_amount)?;
+    require!(usdc_amount_received > 0, CustomError::InvalidAmount);
+
+    // Dynamic fee mode prices the swap on recent volatility
+    let now = Clock::get()?.unix_timestamp;
+    let fee_numerator = calculate_effective_fee_numerator(pool, now);
+    let price_before = calculate_spot_price(pool)?;
+
+    // Calculate SOL output on the pool curve with the pool fee
+    let sol_amount_out = calculate_swap_output(pool, usdc_amount_received, false, fee_numerator)?;
 
-    // Slippage protection: verify output >= minimum_amount_out
+    // Slippage protection is checked against what the user receives after transfer fees
+    let sol_amount_out_received = calculate_amount_after_transfer_fee(&ctx.accounts.token_out_mint, sol_amount_out)?;
     require!(
-        sol_amount_out >= min_sol_received,
+        sol_amount_out_received >= min_sol_received,
         CustomError::SlippageTooHigh
     );
 
@@ -213,67 +270,85 @@ pub fn market_buy(
     );
 
     // Calculate fee for tracking
-    let fee_amount = (usdc_amount as u128)
-        .checked_mul(pool.fee_numerator as u128)
+    let fee_amount = (usdc_amount_received as u128)
+        .checked_mul(fee_numerator as u128)
         .ok_or(error!(CustomError::CalculationOverflow))?
         .checked_div(pool.fee_denominator as u128)
         .ok_or(error!(CustomError::CalculationOverflow))? as u64;
 
+    // Protocol share of the fee stays in the vault but outside the curve reserves
+    let protocol_fee = calculate_protocol_fee(fee_amount, pool.protocol_fee_share_bps)?;
+    let usdc_to_reserve = usdc_amount_received
+        .checked_sub(protocol_fee)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
     // Transfer USDC from user to pool vault
-    transfer(
+    transfer_checked(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_in_program.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.user_token_in.to_account_info(),
+                mint: ctx.accounts.token_in_mint.to_account_info(),
                 to: ctx.accounts.pool_vault_in.to_account_info(),
                 authority: ctx.accounts.user.to_account_info(),
             },
         ),
         usdc_amount,
+        ctx.accounts.token_in_mint.decimals,
     )?;
 
     // Update pool reserves: USDC increases (reserve_b), SOL decreases (reserve_a)
     pool.reserve_b = pool
         .reserve_b
-        .checked_add(usdc_amount)
+        .checked_add(usdc_to_reserve)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    pool.protocol_fees_b = pool
+        .protocol_fees_b
+        .checked_add(protocol_fee)
         .ok_or(error!(CustomError::CalculationOverflow))?;
     pool.reserve_a = pool
         .reserve_a
         .checked_sub(sol_amount_out)
         .ok_or(error!(CustomError::InsufficientPoolReserves))?;
+    // LPs earn the fee net of the protocol share
+    accrue_lp_fee_growth(pool, fee_amount - protocol_fee, false);
+    update_volatility_accumulator(pool, price_before, now)?;
 
     // Transfer SOL from pool vault to user
     let pool_key = pool.key();
-    let token_a_mint = pool.token_a_mint;
-    let token_b_mint = pool.token_b_mint;
-    let bump_seed = pool.bump;
-    let signer_seeds: &[&[&[u8]]] = &[&[
-        b"pool",
-        token_a_mint.as_ref(),
-        token_b_mint.as_ref(),
-        &[bump_seed],
-    ]];
-
-    transfer(
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    transfer_checked(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_out_program.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.pool_vault_out.to_account_info(),
+                mint: ctx.accounts.token_out_mint.to_account_info(),
                 to: ctx.accounts.user_token_out.to_account_info(),
                 authority: pool.to_account_info(),
             },
             signer_seeds,
         ),
         sol_amount_out,
+        ctx.accounts.token_out_mint.decimals,
+    )?;
+
+    assert_vault_invariants(
+        pool,
+        &mut ctx.accounts.pool_vault_out,
+        &mut ctx.accounts.pool_vault_in,
     )?;
 
     // Emit swap event (is_a_to_b=false for USDC→SOL)
     emit!(SwapExecuted {
         user: ctx.accounts.user.key(),
         pool: pool_key,
-        amount_in: usdc_amount,
+        amount_in: usdc_amount_received,
         amount_out: sol_amount_out,
         fee_amount,
+        fee_bps: fee_numerator as u16,
+        protocol_fee_amount: protocol_fee,
         is_a_to_b: false,
         new_reserve_a: pool.reserve_a,
         new_reserve_b: pool.reserve_b,
@@ -305,11 +380,16 @@ pub fn market_sell(
     ctx: Context<MarketSell>,
     sol_amount: u64,
     min_usdc_received: u64,
+    deadline: Option<i64>,
 ) -> Result<()> {
+    check_deadline(deadline)?;
+
     // Validation: SOL amount must be greater than 0
     require!(sol_amount > 0, CustomError::InvalidAmount);
 
     let pool = &mut ctx.accounts.pool;
+    require!(pool.status.allows_swaps(), CustomError::PoolSwapsDisabled);
+    require!(!pool.locked, CustomError::PoolLocked);
 
     // Determine input/output reserves for SOL→USDC swap (is_a_to_b=true, so A→B)
     let (input_reserve, output_reserve) = (pool.reserve_a, pool.reserve_b);
@@ -326,18 +406,22 @@ pub fn market_sell(
         CustomError::InsufficientBalance
     );
 
-    // Calculate USDC output using constant product formula with 0.3% fee
-    let usdc_amount_out = calculate_output_amount(
-        sol_amount,
-        input_reserve,
-        output_reserve,
-        pool.fee_numerator,
-        pool.fee_denominator,
-    )?;
+    // Reserves are credited with what the vault receives after any transfer fee
+    let sol_amount_received = calculate_amount_after_transfer_fee(&ctx.accounts.token_in_mint, sol_amount)?;
+    require!(sol_amount_received > 0, CustomError::InvalidAmount);
+
+    // Dynamic fee mode prices the swap on recent volatility
+    let now = Clock::get()?.unix_timestamp;
+    let fee_numerator = calculate_effective_fee_numerator(pool, now);
+    let price_before = calculate_spot_price(pool)?;
 
-    // Slippage protection: verify output >= minimum_amount_out
+    // Calculate USDC output on the pool curve with the pool fee
+    let usdc_amount_out = calculate_swap_output(pool, sol_amount_received, true, fee_numerator)?;
+
+    // Slippage protection is checked against what the user receives after transfer fees
+    let usdc_amount_out_received = calculate_amount_after_transfer_fee(&ctx.accounts.token_out_mint, usdc_amount_out)?;
     require!(
-        usdc_amount_out >= min_usdc_received,
+        usdc_amount_out_received >= min_usdc_received,
         CustomError::SlippageTooHigh
     );
 
@@ -348,67 +432,85 @@ pub fn market_sell(
     );
 
     // Calculate fee for tracking
-    let fee_amount = (sol_amount as u128)
-        .checked_mul(pool.fee_numerator as u128)
+    let fee_amount = (sol_amount_received as u128)
+        .checked_mul(fee_numerator as u128)
         .ok_or(error!(CustomError::CalculationOverflow))?
         .checked_div(pool.fee_denominator as u128)
         .ok_or(error!(CustomError::CalculationOverflow))? as u64;
 
+    // Protocol share of the fee stays in the vault but outside the curve reserves
+    let protocol_fee = calculate_protocol_fee(fee_amount, pool.protocol_fee_share_bps)?;
+    let sol_to_reserve = sol_amount_received
+        .checked_sub(protocol_fee)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
     // Transfer SOL from user to pool vault
-    transfer(
+    transfer_checked(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_in_program.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.user_token_in.to_account_info(),
+                mint: ctx.accounts.token_in_mint.to_account_info(),
                 to: ctx.accounts.pool_vault_in.to_account_info(),
                 authority: ctx.accounts.user.to_account_info(),
             },
         ),
         sol_amount,
+        ctx.accounts.token_in_mint.decimals,
     )?;
 
     // Update pool reserves: SOL increases (reserve_a), USDC decreases (reserve_b)
     pool.reserve_a = pool
         .reserve_a
-        .checked_add(sol_amount)
+        .checked_add(sol_to_reserve)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    pool.protocol_fees_a = pool
+        .protocol_fees_a
+        .checked_add(protocol_fee)
         .ok_or(error!(CustomError::CalculationOverflow))?;
     pool.reserve_b = pool
         .reserve_b
         .checked_sub(usdc_amount_out)
         .ok_or(error!(CustomError::InsufficientPoolReserves))?;
+    // LPs earn the fee net of the protocol share
+    accrue_lp_fee_growth(pool, fee_amount - protocol_fee, true);
+    update_volatility_accumulator(pool, price_before, now)?;
 
     // Transfer USDC from pool vault to user
     let pool_key = pool.key();
-    let token_a_mint = pool.token_a_mint;
-    let token_b_mint = pool.token_b_mint;
-    let bump_seed = pool.bump;
-    let signer_seeds: &[&[&[u8]]] = &[&[
-        b"pool",
-        token_a_mint.as_ref(),
-        token_b_mint.as_ref(),
-        &[bump_seed],
-    ]];
-
-    transfer(
+    let pool_seeds = pool.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&pool_seeds.as_seeds()];
+
+    transfer_checked(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_out_program.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.pool_vault_out.to_account_info(),
+                mint: ctx.accounts.token_out_mint.to_account_info(),
                 to: ctx.accounts.user_token_out.to_account_info(),
                 authority: pool.to_account_info(),
             },
             signer_seeds,
         ),
         usdc_amount_out,
+        ctx.accounts.token_out_mint.decimals,
+    )?;
+
+    assert_vault_invariants(
+        pool,
+        &mut ctx.accounts.pool_vault_in,
+        &mut ctx.accounts.pool_vault_out,
     )?;
 
     // Emit swap event (is_a_to_b=true for SOL→USDC)
     emit!(SwapExecuted {
         user: ctx.accounts.user.key(),
         pool: pool_key,
-        amount_in: sol_amount,
+        amount_in: sol_amount_received,
         amount_out: usdc_amount_out,
         fee_amount,
+        fee_bps: fee_numerator as u16,
+        protocol_fee_amount: protocol_fee,
         is_a_to_b: true,
         new_reserve_a: pool.reserve_a,
         new_reserve_b: pool.reserve_b,
@@ -429,79 +531,176 @@ pub fn market_sell(
 // ========================================================================
 
 #[derive(Accounts)]
+#[instruction(amount_in: u64, minimum_amount_out: u64, is_a_to_b: bool)]
 pub struct Swap<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump
+    )]
     pub pool: Account<'info, LiquidityPool>,
     
     /// User's input token account (from token based on is_a_to_b)
-    #[account(mut)]
-    pub user_token_in: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = token_in_mint,
+        token::authority = user
+    )]
+    pub user_token_in: InterfaceAccount<'info, TokenAccount>,
     
     /// User's output token account (to token based on is_a_to_b)
-    #[account(mut)]
-    pub user_token_out: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = token_out_mint)]
+    pub user_token_out: InterfaceAccount<'info, TokenAccount>,
     
     /// Pool's input token vault (receives input tokens)
-    #[account(mut)]
-    pub pool_vault_in: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        address = if is_a_to_b { pool.token_a_vault } else { pool.token_b_vault } @ CustomError::InvalidVault
+    )]
+    pub pool_vault_in: InterfaceAccount<'info, TokenAccount>,
     
     /// Pool's output token vault (sends output tokens)
-    #[account(mut)]
-    pub pool_vault_out: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        address = if is_a_to_b { pool.token_b_vault } else { pool.token_a_vault } @ CustomError::InvalidVault
+    )]
+    pub pool_vault_out: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        address = if is_a_to_b { pool.token_a_mint } else { pool.token_b_mint } @ CustomError::InvalidTokenMint,
+        mint::token_program = token_in_program
+    )]
+    pub token_in_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        address = if is_a_to_b { pool.token_b_mint } else { pool.token_a_mint } @ CustomError::InvalidTokenMint,
+        mint::token_program = token_out_program
+    )]
+    pub token_out_mint: InterfaceAccount<'info, Mint>,
     
     #[account(mut)]
     pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_in_program: Interface<'info, TokenInterface>,
+    pub token_out_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 pub struct MarketBuy<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump
+    )]
     pub pool: Account<'info, LiquidityPool>,
     
     /// User's USDC token account (input)
-    #[account(mut)]
-    pub user_token_in: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = token_in_mint,
+        token::authority = user
+    )]
+    pub user_token_in: InterfaceAccount<'info, TokenAccount>,
     
     /// User's SOL token account (output)
-    #[account(mut)]
-    pub user_token_out: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = token_out_mint)]
+    pub user_token_out: InterfaceAccount<'info, TokenAccount>,
     
     /// Pool's USDC vault (receives USDC)
-    #[account(mut)]
-    pub pool_vault_in: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        address = pool.token_b_vault @ CustomError::InvalidVault
+    )]
+    pub pool_vault_in: InterfaceAccount<'info, TokenAccount>,
     
     /// Pool's SOL vault (sends SOL)
-    #[account(mut)]
-    pub pool_vault_out: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        address = pool.token_a_vault @ CustomError::InvalidVault
+    )]
+    pub pool_vault_out: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        address = pool.token_b_mint @ CustomError::InvalidTokenMint,
+        mint::token_program = token_in_program
+    )]
+    pub token_in_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        address = pool.token_a_mint @ CustomError::InvalidTokenMint,
+        mint::token_program = token_out_program
+    )]
+    pub token_out_mint: InterfaceAccount<'info, Mint>,
     
     #[account(mut)]
     pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_in_program: Interface<'info, TokenInterface>,
+    pub token_out_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 pub struct MarketSell<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.fee_tier_seed().as_ref()
+        ],
+        bump = pool.bump
+    )]
     pub pool: Account<'info, LiquidityPool>,
     
     /// User's SOL token account (input)
-    #[account(mut)]
-    pub user_token_in: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = token_in_mint,
+        token::authority = user
+    )]
+    pub user_token_in: InterfaceAccount<'info, TokenAccount>,
     
     /// User's USDC token account (output)
-    #[account(mut)]
-    pub user_token_out: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = token_out_mint)]
+    pub user_token_out: InterfaceAccount<'info, TokenAccount>,
     
     /// Pool's SOL vault (receives SOL)
-    #[account(mut)]
-    pub pool_vault_in: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        address = pool.token_a_vault @ CustomError::InvalidVault
+    )]
+    pub pool_vault_in: InterfaceAccount<'info, TokenAccount>,
     
     /// Pool's USDC vault (sends USDC)
-    #[account(mut)]
-    pub pool_vault_out: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        address = pool.token_b_vault @ CustomError::InvalidVault
+    )]
+    pub pool_vault_out: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        address = pool.token_a_mint @ CustomError::InvalidTokenMint,
+        mint::token_program = token_in_program
+    )]
+    pub token_in_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        address = pool.token_b_mint @ CustomError::InvalidTokenMint,
+        mint::token_program = token_out_program
+    )]
+    pub token_out_mint: InterfaceAccount<'info, Mint>,
     
     #[account(mut)]
     pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_in_program: Interface<'info, TokenInterface>,
+    pub token_out_program: Interface<'info, TokenInterface>,
 }
@@ -27,23 +27,66 @@ pub mod solrush_dex {
     // MODULE 2: LIQUIDITY POOL MANAGEMENT
     // ========================================================================
 
-    /// Initialize a new liquidity pool
+    /// Initialize a new constant product, StableSwap or weighted pool at an allowlisted fee tier
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize_pool(
         ctx: Context<InitializePool>,
         initial_deposit_a: u64,
         initial_deposit_b: u64,
+        fee_tier_bps: u16,
+        token_a_symbol: String,
+        token_b_symbol: String,
+        pool_type: PoolType,
+        amp_coefficient: u64,
+        weight_a_bps: u16,
     ) -> Result<()> {
-        instructions::pool::initialize_pool(ctx, initial_deposit_a, initial_deposit_b)
+        instructions::pool::initialize_pool(
+            ctx,
+            initial_deposit_a,
+            initial_deposit_b,
+            fee_tier_bps,
+            token_a_symbol,
+            token_b_symbol,
+            pool_type,
+            amp_coefficient,
+            weight_a_bps,
+        )
     }
 
-    /// Add liquidity to an existing pool
+    /// Add liquidity to an existing pool at the optimal ratio
     pub fn add_liquidity(
         ctx: Context<AddLiquidity>,
-        amount_a: u64,
-        amount_b: u64,
+        amount_a_desired: u64,
+        amount_b_desired: u64,
+        amount_a_min: u64,
+        amount_b_min: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::pool::add_liquidity(
+            ctx,
+            amount_a_desired,
+            amount_b_desired,
+            amount_a_min,
+            amount_b_min,
+            deadline,
+        )
+    }
+
+    /// Add liquidity using a single token (zap)
+    pub fn add_liquidity_single_sided(
+        ctx: Context<AddLiquiditySingleSided>,
+        amount_in: u64,
+        is_token_a: bool,
         min_lp_tokens: u64,
+        deadline: Option<i64>,
     ) -> Result<()> {
-        instructions::pool::add_liquidity(ctx, amount_a, amount_b, min_lp_tokens)
+        instructions::pool::add_liquidity_single_sided(
+            ctx,
+            amount_in,
+            is_token_a,
+            min_lp_tokens,
+            deadline,
+        )
     }
 
     /// Remove liquidity from a pool
@@ -52,8 +95,153 @@ pub mod solrush_dex {
         lp_tokens_to_burn: u64,
         min_amount_a: u64,
         min_amount_b: u64,
+        deadline: Option<i64>,
     ) -> Result<()> {
-        instructions::pool::remove_liquidity(ctx, lp_tokens_to_burn, min_amount_a, min_amount_b)
+        instructions::pool::remove_liquidity(
+            ctx,
+            lp_tokens_to_burn,
+            min_amount_a,
+            min_amount_b,
+            deadline,
+        )
+    }
+
+    /// Remove liquidity for exact amounts of each token, burning at most max_lp_tokens_to_burn
+    pub fn remove_liquidity_exact_out(
+        ctx: Context<RemoveLiquidity>,
+        amount_a_out: u64,
+        amount_b_out: u64,
+        max_lp_tokens_to_burn: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::pool::remove_liquidity_exact_out(
+            ctx,
+            amount_a_out,
+            amount_b_out,
+            max_lp_tokens_to_burn,
+            deadline,
+        )
+    }
+
+    /// Remove liquidity into a single token, swapping the other side back into the pool
+    pub fn remove_liquidity_single_sided(
+        ctx: Context<RemoveLiquiditySingleSided>,
+        lp_tokens_to_burn: u64,
+        is_token_a_out: bool,
+        min_amount_out: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::pool::remove_liquidity_single_sided(
+            ctx,
+            lp_tokens_to_burn,
+            is_token_a_out,
+            min_amount_out,
+            deadline,
+        )
+    }
+
+    /// Reset pool reserves to the vault balances (permissionless)
+    pub fn sync(ctx: Context<SyncReserves>) -> Result<()> {
+        instructions::pool::sync(ctx)
+    }
+
+    /// Send vault balances above reserves to recipient accounts (permissionless)
+    pub fn skim(ctx: Context<SkimReserves>) -> Result<()> {
+        instructions::pool::skim(ctx)
+    }
+
+    /// Set a pool's emergency status (pool authority only)
+    pub fn set_pool_status(ctx: Context<SetPoolStatus>, status: PoolStatus) -> Result<()> {
+        instructions::pool::set_pool_status(ctx, status)
+    }
+
+    /// Close an empty pool and reclaim its rent (pool authority only)
+    pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+        instructions::pool::close_pool(ctx)
+    }
+
+    /// Enable or disable volatility-driven dynamic fees (pool authority only)
+    pub fn set_dynamic_fee(
+        ctx: Context<SetDynamicFee>,
+        enabled: bool,
+        min_fee_bps: u16,
+        max_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::pool::set_dynamic_fee(ctx, enabled, min_fee_bps, max_fee_bps)
+    }
+
+    /// Report a position's fee earnings and impermanent loss (view)
+    pub fn get_position_earnings(ctx: Context<GetPositionEarnings>) -> Result<PositionEarnings> {
+        instructions::pool::get_position_earnings(ctx)
+    }
+
+    /// Initialize an empty concentrated liquidity pool at an allowlisted fee tier
+    pub fn initialize_concentrated_pool(
+        ctx: Context<InitializeConcentratedPool>,
+        fee_tier_bps: u16,
+        tick_spacing: u16,
+        initial_sqrt_price_x64: u128,
+    ) -> Result<()> {
+        instructions::concentrated::initialize_concentrated_pool(
+            ctx,
+            fee_tier_bps,
+            tick_spacing,
+            initial_sqrt_price_x64,
+        )
+    }
+
+    /// Allocate a tick array for a concentrated pool
+    pub fn initialize_tick_array(ctx: Context<InitializeTickArray>, start_tick_index: i32) -> Result<()> {
+        instructions::concentrated::initialize_tick_array(ctx, start_tick_index)
+    }
+
+    /// Open an empty concentrated liquidity position over a tick range
+    pub fn open_concentrated_position(
+        ctx: Context<OpenConcentratedPosition>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+    ) -> Result<()> {
+        instructions::concentrated::open_concentrated_position(ctx, tick_lower_index, tick_upper_index)
+    }
+
+    /// Add liquidity to a concentrated position
+    pub fn increase_concentrated_liquidity(
+        ctx: Context<ModifyConcentratedLiquidity>,
+        liquidity_amount: u128,
+        max_amount_a: u64,
+        max_amount_b: u64,
+    ) -> Result<()> {
+        instructions::concentrated::increase_concentrated_liquidity(
+            ctx,
+            liquidity_amount,
+            max_amount_a,
+            max_amount_b,
+        )
+    }
+
+    /// Withdraw liquidity from a concentrated position
+    pub fn decrease_concentrated_liquidity(
+        ctx: Context<ModifyConcentratedLiquidity>,
+        liquidity_amount: u128,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    ) -> Result<()> {
+        instructions::concentrated::decrease_concentrated_liquidity(
+            ctx,
+            liquidity_amount,
+            min_amount_a,
+            min_amount_b,
+        )
+    }
+
+    /// Collect the swap fees earned by a concentrated position
+    pub fn collect_concentrated_fees(ctx: Context<ModifyConcentratedLiquidity>) -> Result<()> {
+        instructions::concentrated::collect_concentrated_fees(ctx)
+    }
+
+    /// Close an empty concentrated position and reclaim its rent
+    pub fn close_concentrated_position(ctx: Context<CloseConcentratedPosition>) -> Result<()> {
+        instructions::concentrated::close_concentrated_position(ctx)
     }
 
     // ========================================================================
@@ -66,8 +254,19 @@ pub mod solrush_dex {
         amount_in: u64,
         minimum_amount_out: u64,
         is_a_to_b: bool,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::swap::swap(ctx, amount_in, minimum_amount_out, is_a_to_b, deadline)
+    }
+
+    /// Exact-input swap against a concentrated pool; tick arrays go in remaining accounts
+    pub fn swap_concentrated<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapConcentrated<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        is_a_to_b: bool,
     ) -> Result<()> {
-        instructions::swap::swap(ctx, amount_in, minimum_amount_out, is_a_to_b)
+        instructions::concentrated::swap_concentrated(ctx, amount_in, minimum_amount_out, is_a_to_b)
     }
 
     /// Market buy: Buy token A with token B
@@ -75,8 +274,9 @@ pub mod solrush_dex {
         ctx: Context<MarketBuy>,
         usdc_amount: u64,
         min_sol_received: u64,
+        deadline: Option<i64>,
     ) -> Result<()> {
-        instructions::swap::market_buy(ctx, usdc_amount, min_sol_received)
+        instructions::swap::market_buy(ctx, usdc_amount, min_sol_received, deadline)
     }
 
     /// Market sell: Sell token A for token B
@@ -84,8 +284,9 @@ pub mod solrush_dex {
         ctx: Context<MarketSell>,
         sol_amount: u64,
         min_usdc_received: u64,
+        deadline: Option<i64>,
     ) -> Result<()> {
-        instructions::swap::market_sell(ctx, sol_amount, min_usdc_received)
+        instructions::swap::market_sell(ctx, sol_amount, min_usdc_received, deadline)
     }
 
     // ========================================================================
@@ -119,6 +320,25 @@ pub mod solrush_dex {
         instructions::limit_orders::cancel_limit_order(ctx)
     }
 
+    // ========================================================================
+    // MODULE 3.5: FLASH LOANS
+    // ========================================================================
+
+    /// Borrow from a pool vault and repay with a fee within one receiver CPI
+    pub fn flash_loan<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FlashLoan<'info>>,
+        amount: u64,
+        is_token_a: bool,
+        receiver_data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::flash_loan::flash_loan(ctx, amount, is_token_a, receiver_data)
+    }
+
+    /// Set the pool's flash loan fee in bps; 0 disables flash loans
+    pub fn set_flash_loan_fee(ctx: Context<SetFlashLoanFee>, fee_bps: u16) -> Result<()> {
+        instructions::flash_loan::set_flash_loan_fee(ctx, fee_bps)
+    }
+
     // ========================================================================
     // MODULE 4: RUSH TOKEN REWARDS
     // ========================================================================
@@ -147,4 +367,92 @@ pub mod solrush_dex {
     pub fn pause_rush_rewards(ctx: Context<PauseRewards>) -> Result<()> {
         instructions::rewards::pause_rush_rewards(ctx)
     }
+
+    /// Stake LP tokens to earn RUSH rewards
+    pub fn stake_lp(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+        instructions::staking::stake_lp(ctx, amount)
+    }
+
+    /// Unstake LP tokens; accrued rewards remain claimable
+    pub fn unstake_lp(ctx: Context<UnstakeLp>, amount: u64) -> Result<()> {
+        instructions::staking::unstake_lp(ctx, amount)
+    }
+
+    // ========================================================================
+    // MODULE 5: PROTOCOL CONFIGURATION
+    // ========================================================================
+
+    /// Initialize the global protocol config
+    pub fn initialize_dex_config(ctx: Context<InitializeDexConfig>, treasury: Pubkey) -> Result<()> {
+        instructions::config::initialize_dex_config(ctx, treasury)
+    }
+
+    /// Add a pool fee tier to the allowlist (admin only)
+    pub fn add_fee_tier(ctx: Context<UpdateFeeTiers>, fee_tier_bps: u16) -> Result<()> {
+        instructions::config::add_fee_tier(ctx, fee_tier_bps)
+    }
+
+    /// Remove a pool fee tier from the allowlist (admin only)
+    pub fn remove_fee_tier(ctx: Context<UpdateFeeTiers>, fee_tier_bps: u16) -> Result<()> {
+        instructions::config::remove_fee_tier(ctx, fee_tier_bps)
+    }
+
+    /// Set a pool's protocol fee share (admin only)
+    pub fn set_protocol_fee_share(
+        ctx: Context<SetProtocolFeeShare>,
+        protocol_fee_share_bps: u16,
+    ) -> Result<()> {
+        instructions::config::set_protocol_fee_share(ctx, protocol_fee_share_bps)
+    }
+
+    /// Collect accrued protocol fees into the treasury (admin only)
+    pub fn collect_protocol_fees(ctx: Context<CollectProtocolFees>) -> Result<()> {
+        instructions::config::collect_protocol_fees(ctx)
+    }
+
+    /// Initialize the on-chain pool registry (admin only)
+    pub fn initialize_pool_registry(ctx: Context<InitializePoolRegistry>) -> Result<()> {
+        instructions::registry::initialize_pool_registry(ctx)
+    }
+
+    /// Upgrade a pre-versioning account to the current layout (admin only)
+    pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migration::migrate_account(ctx)
+    }
+
+    /// Propose a new pool authority (pool authority only)
+    pub fn propose_pool_authority(
+        ctx: Context<ManagePoolAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::authority::propose_pool_authority(ctx, new_authority)
+    }
+
+    /// Accept a proposed pool authority transfer (pending authority only)
+    pub fn accept_pool_authority(ctx: Context<AcceptPoolAuthority>) -> Result<()> {
+        instructions::authority::accept_pool_authority(ctx)
+    }
+
+    /// Cancel a proposed pool authority transfer (pool authority only)
+    pub fn cancel_pool_authority_transfer(ctx: Context<ManagePoolAuthority>) -> Result<()> {
+        instructions::authority::cancel_pool_authority_transfer(ctx)
+    }
+
+    /// Propose a new RUSH rewards authority (rewards authority only)
+    pub fn propose_rush_authority(
+        ctx: Context<ManageRushAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::authority::propose_rush_authority(ctx, new_authority)
+    }
+
+    /// Accept a proposed RUSH rewards authority transfer (pending authority only)
+    pub fn accept_rush_authority(ctx: Context<AcceptRushAuthority>) -> Result<()> {
+        instructions::authority::accept_rush_authority(ctx)
+    }
+
+    /// Cancel a proposed RUSH rewards authority transfer (rewards authority only)
+    pub fn cancel_rush_authority_transfer(ctx: Context<ManageRushAuthority>) -> Result<()> {
+        instructions::authority::cancel_rush_authority_transfer(ctx)
+    }
 }
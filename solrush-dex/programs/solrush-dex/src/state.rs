@@ -1,9 +1,30 @@
 use anchor_lang::prelude::*;
 
+/// LP tokens permanently locked when a pool is created
+/// Counted in total_lp_supply but never minted, so reserves can never be drained
+/// to zero and the LP share price cannot be inflated by a first depositor
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+// ============================================================================
+// ACCOUNT VERSIONING (Module 5.4)
+// ============================================================================
+//
+// Versioned accounts end with `version` followed by zeroed `reserved` padding.
+// New fields are carved out of the padding (declared after `version`, with
+// `reserved` shrunk to match) so SIZE and existing offsets never change.
+// Accounts created before versioning (LEGACY_SIZE, no version byte) are
+// upgraded in place by the admin `migrate_account` instruction. Pre-versioning
+// pools also predate fee tiers, so their fields after fee_denominator are
+// rewritten rather than zero-extended; they keep the old [b"pool", mint_a, mint_b]
+// PDA, which signs for their vaults, and are flagged `legacy_pda`.
+
+/// Current layout version written into every versioned account
+pub const ACCOUNT_VERSION: u8 = 1;
+
 /// LiquidityPool Account Structure
 /// Represents a single trading pair pool (SOL/USDC or SOL/USDT)
 ///
-/// Space: 8 (discriminator) + 32*6 + 8*5 + 1 = 249 bytes
+/// Space: 8 (discriminator) + 32*6 + 8*7 + 2*2 + 1 + 1 + 1 + 32 + 8 + 1 + 8 + 2*2 + 2 + 4 + 16*4 + 1 + 2*2 + 8*2 + 111 = 518 bytes
 #[account]
 pub struct LiquidityPool {
     // Authority and Token Configuration (192 bytes)
@@ -21,20 +42,250 @@ pub struct LiquidityPool {
     
     // Fee Configuration (16 bytes)
     pub fee_numerator: u64,          // Fee numerator = 3 for 0.3% (8 bytes)
-    pub fee_denominator: u64,        // Fee denominator = 1000 (8 bytes)
+    pub fee_denominator: u64,        // Fee denominator = 10000 (8 bytes)
+    pub fee_tier_bps: u16,           // Fee tier in basis points, part of PDA seeds (2 bytes)
+    
+    // Protocol Fees (20 bytes)
+    pub protocol_fee_share_bps: u16, // Share of each swap fee kept by the protocol, in bps of the fee (2 bytes)
+    pub protocol_fees_a: u64,        // Uncollected protocol fees held in token_a_vault (8 bytes)
+    pub protocol_fees_b: u64,        // Uncollected protocol fees held in token_b_vault (8 bytes)
     
     // PDA Verification (1 byte)
     pub bump: u8,                    // PDA bump seed (1 byte)
+    
+    // Versioning (257 bytes)
+    pub version: u8,                 // Account layout version (1 byte)
+    pub status: PoolStatus,          // Emergency mode set by the pool authority (1 byte) - Module 2.9
+    pub pending_authority: Pubkey,   // Proposed new authority, default when none (32 bytes) - Module 5.5
+    pub total_staked_lp: u64,        // LP tokens escrowed in the stake vault (8 bytes) - Module 4.7
+    pub pool_type: PoolType,         // Pricing curve, fixed at creation (1 byte) - Module 2.11
+    pub amp_coefficient: u64,        // StableSwap amplification A, 0 for other curves (8 bytes)
+    pub weight_a_bps: u16,           // Weighted pool share of token A, 0 for other curves (2 bytes) - Module 2.12
+    pub weight_b_bps: u16,           // Weighted pool share of token B, 0 for other curves (2 bytes)
+    pub tick_spacing: u16,           // Concentrated pools: distance between usable ticks (2 bytes) - Module 2.13
+    pub tick_current_index: i32,     // Tick containing the current sqrt price (4 bytes)
+    pub sqrt_price_x64: u128,        // sqrt(price of A in B) as Q64.64 (16 bytes)
+    pub active_liquidity: u128,      // Liquidity of positions in range at the current price (16 bytes)
+    pub fee_growth_global_a: u128,   // LP fees in A per unit of liquidity (per LP token for other curves), Q64.64, wrapping (16 bytes)
+    pub fee_growth_global_b: u128,   // LP fees in B per unit of liquidity (per LP token for other curves), Q64.64, wrapping (16 bytes)
+    pub dynamic_fee_enabled: bool,   // Swap fee follows volatility instead of fee_numerator (1 byte) - Module 2.14
+    pub min_fee_bps: u16,            // Dynamic fee in calm markets (2 bytes)
+    pub max_fee_bps: u16,            // Dynamic fee once volatility saturates (2 bytes)
+    pub volatility_accumulator: u64, // Recent price movement in bps, decays between swaps (8 bytes)
+    pub last_swap_timestamp: i64,    // Time of the last swap, for accumulator decay (8 bytes)
+    pub flash_loan_fee_bps: u16,     // Flash loan fee on the principal, 0 disables loans (2 bytes) - Module 3.5
+    pub locked: bool,                // Reentrancy lock held while a flash loan is outstanding (1 byte)
+    pub acc_rush_per_share: u128,    // RUSH emitted per staked LP, scaled by REWARD_SHARE_PRECISION (16 bytes) - Module 4.7
+    pub last_reward_timestamp: i64,  // When acc_rush_per_share was last brought up to date (8 bytes)
+    pub rush_index_snapshot: u128,   // RushConfig reward_index at that update (16 bytes)
+    pub legacy_pda: bool,            // Migrated pre-fee-tier pool at [b"pool", mint_a, mint_b] (1 byte) - Module 5.4
+    pub reserved: [u8; 67],          // Zeroed padding for future fields (67 bytes)
 }
 
 impl LiquidityPool {
-    pub const SIZE: usize = 8 + 32*6 + 8*5 + 1;
+    /// Size of pre-versioning pools (no fee tier, protocol fees, version byte or padding)
+    /// `migrate_account` rewrites these into the current layout at fee tier LEGACY_FEE_TIER_BPS
+    pub const LEGACY_SIZE: usize = 8 + 32*6 + 8*5 + 1;
+    pub const SIZE: usize = 8 + 32*6 + 8*7 + 2*2 + 1 + 1 + 1 + 32 + 8 + 1 + 8 + 2*2 + 2 + 4 + 16*4 + 1 + 2*2 + 8*2 + 2 + 1 + 16 + 8 + 16 + 1 + 67;
+
+    /// Fee tier of migrated pre-fee-tier pools, which all charged a fixed 3/1000
+    pub const LEGACY_FEE_TIER_BPS: u16 = 30;
+
+    /// Fee tier seed of the pool PDA: fee_tier_bps (u16 LE), or nothing for
+    /// migrated legacy pools. An empty seed adds no bytes to the PDA hash, so
+    /// [b"pool", mint_a, mint_b, &[]] derives the pre-fee-tier address
+    pub fn fee_tier_seed(&self) -> Vec<u8> {
+        if self.legacy_pda {
+            Vec::new()
+        } else {
+            self.fee_tier_bps.to_le_bytes().to_vec()
+        }
+    }
+
+    /// Seeds the pool PDA signs vault transfers and LP mints with
+    /// Copied out of the account so handlers can keep mutating the pool while signing
+    pub fn signer_seeds(&self) -> PoolSignerSeeds {
+        PoolSignerSeeds {
+            token_a_mint: self.token_a_mint,
+            token_b_mint: self.token_b_mint,
+            fee_tier: self.fee_tier_bps.to_le_bytes(),
+            legacy_pda: self.legacy_pda,
+            bump: [self.bump],
+        }
+    }
+}
+
+/// Owned pool PDA seeds: [b"pool", mint_a, mint_b, fee_tier_bps (u16 LE), bump]
+/// Legacy pools sign with an empty fee tier seed, i.e. [b"pool", mint_a, mint_b, bump]
+pub struct PoolSignerSeeds {
+    token_a_mint: Pubkey,
+    token_b_mint: Pubkey,
+    fee_tier: [u8; 2],
+    legacy_pda: bool,
+    bump: [u8; 1],
+}
+
+impl PoolSignerSeeds {
+    pub fn as_seeds(&self) -> [&[u8]; 5] {
+        [
+            b"pool",
+            self.token_a_mint.as_ref(),
+            self.token_b_mint.as_ref(),
+            if self.legacy_pda { &[] } else { &self.fee_tier },
+            &self.bump,
+        ]
+    }
+}
+
+/// PoolStatus Enum (Module 2.9)
+/// Emergency modes for a single pool, from least to most restrictive.
+/// Active is variant 0 so zeroed padding (migrated accounts) reads as Active.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PoolStatus {
+    Active = 0,        // Everything enabled
+    SwapsPaused = 1,   // No swaps or limit fills; deposits and withdrawals allowed
+    WithdrawOnly = 2,  // Only remove_liquidity allowed
+    Frozen = 3,        // Nothing touches the pool's reserves
+}
+
+impl PoolStatus {
+    pub fn allows_swaps(&self) -> bool {
+        matches!(self, PoolStatus::Active)
+    }
+
+    pub fn allows_deposits(&self) -> bool {
+        matches!(self, PoolStatus::Active | PoolStatus::SwapsPaused)
+    }
+
+    pub fn allows_withdrawals(&self) -> bool {
+        !matches!(self, PoolStatus::Frozen)
+    }
+
+    /// sync / skim rewrite reserves or move vault tokens, so only Frozen stops them
+    pub fn allows_reconciliation(&self) -> bool {
+        !matches!(self, PoolStatus::Frozen)
+    }
+}
+
+/// PoolType Enum (Module 2.11)
+/// Pricing curve used by swaps and deposits. ConstantProduct is variant 0 so
+/// pools created before pool types existed keep x*y=k.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PoolType {
+    ConstantProduct = 0,  // Uniswap V2 style x*y=k
+    StableSwap = 1,       // Curve StableSwap invariant for pegged pairs
+    Weighted = 2,         // Balancer-style a^wa * b^wb for non-50/50 pools
+    Concentrated = 3,     // Uniswap V3 style liquidity in tick ranges
+}
+
+/// Bounds for the StableSwap amplification coefficient (Module 2.11)
+pub const MIN_AMP_COEFFICIENT: u64 = 1;
+pub const MAX_AMP_COEFFICIENT: u64 = 1_000_000;
+
+/// Weighted pool weights are basis points that sum to TOTAL_WEIGHT_BPS (Module 2.12)
+/// Each side keeps at least 1%, so the swap exponent stays within [1/99, 99]
+pub const TOTAL_WEIGHT_BPS: u16 = 10_000;
+pub const MIN_WEIGHT_BPS: u16 = 100;
+
+/// Dynamic fee bounds and volatility tracking (Module 2.14)
+/// The accumulator adds each swap's price movement in bps and decays linearly
+/// to zero over VOLATILITY_DECAY_PERIOD; the fee reaches max_fee_bps once it
+/// hits VOLATILITY_SATURATION_BPS
+pub const MAX_DYNAMIC_FEE_BPS: u16 = 1_000;
+pub const VOLATILITY_DECAY_PERIOD: i64 = 300;
+pub const VOLATILITY_SATURATION_BPS: u64 = 500;
+pub const MAX_VOLATILITY_ACCUMULATOR: u64 = 10_000;
+
+/// Upper bound on the flash loan fee a pool authority can set (Module 3.5)
+pub const MAX_FLASH_LOAN_FEE_BPS: u16 = 1_000;
+
+// ============================================================================
+// CONCENTRATED LIQUIDITY (Module 2.13)
+// ============================================================================
+//
+// Price is 1.0001^tick (token B per token A, in base units) and the pool
+// stores its square root as Q64.64. Liquidity is provided over
+// [tick_lower, tick_upper) by ConcentratedPosition accounts; per-tick state
+// lives in TickArray accounts covering TICK_ARRAY_SIZE usable ticks each.
+// Concentrated pools have no LP mint and use reserve_a / reserve_b to track
+// everything owed to positions (principal plus uncollected fees).
+
+/// Tick bounds; sqrt(1.0001^tick) * 2^64 stays within u128 across this range
+pub const MIN_TICK: i32 = -443_636;
+pub const MAX_TICK: i32 = 443_636;
+
+/// Largest tick spacing a concentrated pool may use
+pub const MAX_TICK_SPACING: u16 = 1_000;
+
+/// Usable ticks stored per TickArray account
+pub const TICK_ARRAY_SIZE: usize = 16;
+
+/// Per-tick state for concentrated liquidity (Module 2.13)
+/// fee_growth_outside_* is the fee growth on the side of the tick away from
+/// the current price; it is flipped each time a swap crosses the tick.
+///
+/// Space: 1 + 16 * 4 = 65 bytes
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Tick {
+    pub initialized: bool,           // Referenced by at least one position (1 byte)
+    pub liquidity_net: i128,         // Liquidity added when crossed left to right (16 bytes)
+    pub liquidity_gross: u128,       // Total liquidity referencing the tick (16 bytes)
+    pub fee_growth_outside_a: u128,  // Q64.64 (16 bytes)
+    pub fee_growth_outside_b: u128,  // Q64.64 (16 bytes)
+}
+
+impl Tick {
+    pub const SIZE: usize = 1 + 16 * 4;
+}
+
+/// TickArray Account Structure (Module 2.13)
+/// Holds TICK_ARRAY_SIZE consecutive usable ticks starting at start_tick_index
+/// PDA seeds: [b"tick_array", pool, start_tick_index (le bytes)]
+///
+/// Space: 8 (discriminator) + 32 + 4 + 65*16 + 1 + 1 + 32 = 1118 bytes
+#[account]
+pub struct TickArray {
+    pub pool: Pubkey,                      // Concentrated pool (32 bytes)
+    pub start_tick_index: i32,             // Multiple of tick_spacing * TICK_ARRAY_SIZE (4 bytes)
+    pub ticks: [Tick; TICK_ARRAY_SIZE],    // Tick i is start_tick_index + i * tick_spacing (1040 bytes)
+    pub bump: u8,                          // PDA bump seed (1 byte)
+    pub version: u8,                       // Account layout version (1 byte)
+    pub reserved: [u8; 32],                // Zeroed padding for future fields (32 bytes)
+}
+
+impl TickArray {
+    pub const SIZE: usize = 8 + 32 + 4 + Tick::SIZE * TICK_ARRAY_SIZE + 1 + 1 + 32;
+}
+
+/// ConcentratedPosition Account Structure (Module 2.13)
+/// Liquidity over [tick_lower_index, tick_upper_index) with per-position fee tracking
+/// PDA seeds: [b"cl_position", pool, owner, tick_lower_index (le), tick_upper_index (le)]
+///
+/// Space: 8 (discriminator) + 32*2 + 4*2 + 16*3 + 8*2 + 1 + 1 + 64 = 210 bytes
+#[account]
+pub struct ConcentratedPosition {
+    pub owner: Pubkey,                     // Position owner (32 bytes)
+    pub pool: Pubkey,                      // Concentrated pool (32 bytes)
+    pub tick_lower_index: i32,             // Inclusive lower tick (4 bytes)
+    pub tick_upper_index: i32,             // Exclusive upper tick (4 bytes)
+    pub liquidity: u128,                   // Liquidity provided over the range (16 bytes)
+    pub fee_growth_inside_last_a: u128,    // Fee growth inside the range at last update, Q64.64 (16 bytes)
+    pub fee_growth_inside_last_b: u128,    // Q64.64 (16 bytes)
+    pub fees_owed_a: u64,                  // Uncollected fees in token A (8 bytes)
+    pub fees_owed_b: u64,                  // Uncollected fees in token B (8 bytes)
+    pub bump: u8,                          // PDA bump seed (1 byte)
+    pub version: u8,                       // Account layout version (1 byte)
+    pub reserved: [u8; 64],                // Zeroed padding for future fields (64 bytes)
+}
+
+impl ConcentratedPosition {
+    pub const SIZE: usize = 8 + 32*2 + 4*2 + 16*3 + 8*2 + 1 + 1 + 64;
 }
 
 /// UserLiquidityPosition Account Structure
 /// Tracks individual user's LP token position and rewards
 ///
-/// Space: 8 (discriminator) + 32*2 + 8*4 + 1 = 113 bytes
+/// Space: 8 (discriminator) + 32*2 + 8*4 + 1 + 1 + 8*2 + 8*4 + 16*2 + 8*2 + 32 = 234 bytes
 #[account]
 pub struct UserLiquidityPosition {
     pub owner: Pubkey,               // User wallet address (32 bytes)
@@ -44,10 +295,43 @@ pub struct UserLiquidityPosition {
     pub last_claim_timestamp: i64,   // Last RUSH reward claim timestamp (8 bytes)
     pub total_rush_claimed: u64,     // Total RUSH tokens claimed (8 bytes)
     pub bump: u8,                    // PDA bump seed (1 byte)
+    pub version: u8,                 // Account layout version (1 byte)
+    pub staked_lp: u64,              // LP tokens escrowed for RUSH rewards (8 bytes) - Module 4.7
+    pub unclaimed_rewards: u64,      // RUSH settled on stake changes, paid at next claim (8 bytes)
+    pub deposited_a: u64,            // Token A added to reserves by this position (8 bytes) - Module 2.15
+    pub deposited_b: u64,            // Token B added to reserves by this position (8 bytes)
+    pub withdrawn_a: u64,            // Token A taken from reserves by this position (8 bytes)
+    pub withdrawn_b: u64,            // Token B taken from reserves by this position (8 bytes)
+    pub fee_growth_checkpoint_a: u128, // Pool fee_growth_global_a when fees were last settled (16 bytes)
+    pub fee_growth_checkpoint_b: u128, // Pool fee_growth_global_b when fees were last settled (16 bytes)
+    pub fees_earned_a: u64,          // LP fees in A settled so far, still held in reserves (8 bytes)
+    pub fees_earned_b: u64,          // LP fees in B settled so far, still held in reserves (8 bytes)
+    pub reward_debt: u128,           // staked_lp * pool acc_rush_per_share at the last settlement (16 bytes) - Module 4.7
+    pub reserved: [u8; 16],          // Zeroed padding for future fields (16 bytes)
 }
 
 impl UserLiquidityPosition {
-    pub const SIZE: usize = 8 + 32*2 + 8*4 + 1;
+    /// Size of pre-versioning accounts (no version byte or padding)
+    pub const LEGACY_SIZE: usize = 8 + 32*2 + 8*4 + 1;
+    pub const SIZE: usize = Self::LEGACY_SIZE + 1 + 8*2 + 8*4 + 16*2 + 8*2 + 16 + 16;
+}
+
+/// PositionEarnings (Module 2.15)
+/// Returned by get_position_earnings; not stored on chain.
+/// Values in token B use the pool's spot price at the time of the call.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PositionEarnings {
+    pub lp_tokens: u64,              // LP tokens held by the position
+    pub current_amount_a: u64,       // Position's share of reserve A
+    pub current_amount_b: u64,       // Position's share of reserve B
+    pub deposited_a: u64,            // Lifetime token A deposited
+    pub deposited_b: u64,            // Lifetime token B deposited
+    pub withdrawn_a: u64,            // Lifetime token A withdrawn
+    pub withdrawn_b: u64,            // Lifetime token B withdrawn
+    pub fees_earned_a: u64,          // LP fees in A earned, settled and pending
+    pub fees_earned_b: u64,          // LP fees in B earned, settled and pending
+    pub spot_price: u64,             // Price of A in B, 6 decimals
+    pub impermanent_loss_b: i64,     // Loss versus holding the net deposits, before fees; negative is a gain
 }
 
 /// OrderStatus Enum (Module 3.4)
@@ -63,7 +347,7 @@ pub enum OrderStatus {
 /// LimitOrder Account Structure (Module 3.4)
 /// Stores a single limit order with price conditions and escrow
 ///
-/// Space: 8 (discriminator) + 32*4 + 8*5 + 8*2 + 1 + 1 = 181 bytes
+/// Space: 8 (discriminator) + 32*4 + 8*5 + 8*2 + 1 + 1 + 1 + 64 = 259 bytes
 #[account]
 pub struct LimitOrder {
     pub owner: Pubkey,           // Order creator wallet (32 bytes)
@@ -79,10 +363,14 @@ pub struct LimitOrder {
     
     pub status: OrderStatus,     // Current order status (1 byte)
     pub bump: u8,                // PDA bump seed (1 byte)
+    pub version: u8,             // Account layout version (1 byte)
+    pub reserved: [u8; 64],      // Zeroed padding for future fields (64 bytes)
 }
 
 impl LimitOrder {
-    pub const SIZE: usize = 8 + 32*4 + 8*5 + 8*2 + 1 + 1;
+    /// Size of pre-versioning accounts (no version byte or padding)
+    pub const LEGACY_SIZE: usize = 8 + 32*4 + 8*5 + 8*2 + 1 + 1;
+    pub const SIZE: usize = Self::LEGACY_SIZE + 1 + 64;
 }
 
 // ============================================================================
@@ -92,7 +380,7 @@ impl LimitOrder {
 /// RushConfig Account Structure
 /// Manages RUSH token configuration and rewards distribution settings
 ///
-/// Space: 8 (discriminator) + 32*2 + 8*6 + 2 = 122 bytes
+/// Space: 8 (discriminator) + 32*2 + 8*6 + 2 + 1 + 32 + 96 = 251 bytes
 ///
 /// Module 4.1 - RUSH Token Configuration
 /// The RushConfig stores all settings for the RUSH token incentive mechanism.
@@ -107,7 +395,7 @@ impl LimitOrder {
 #[account]
 pub struct RushConfig {
     pub mint: Pubkey,                // RUSH token mint address (32 bytes)
-    pub authority: Pubkey,           // Minting authority (Program PDA) (32 bytes)
+    pub authority: Pubkey,           // Admin for APY/pause; minting is signed by this PDA (32 bytes)
     pub total_supply: u64,           // Max supply: 1,000,000 * 10^6 (8 bytes)
     pub minted_so_far: u64,          // Tokens already distributed (8 bytes)
     pub rewards_per_second: u64,     // Base reward rate (RUSH/second) (8 bytes)
@@ -116,10 +404,17 @@ pub struct RushConfig {
     pub start_timestamp: i64,        // When rewards distribution starts (8 bytes)
     pub is_paused: bool,             // Emergency pause flag (1 byte) - Module 4.6
     pub bump: u8,                    // PDA bump seed (1 byte)
+    pub version: u8,                 // Account layout version (1 byte)
+    pub pending_authority: Pubkey,   // Proposed new authority, default when none (32 bytes) - Module 5.5
+    pub reward_index: u128,          // RUSH emitted per pool since launch: rewards_per_second summed over unpaused seconds (16 bytes) - Module 4.7
+    pub reward_index_updated_at: i64, // When reward_index was last advanced (8 bytes)
+    pub reserved: [u8; 72],          // Zeroed padding for future fields (72 bytes)
 }
 
 impl RushConfig {
-    pub const SIZE: usize = 8 + 32*2 + 8*6 + 2;
+    /// Size of pre-versioning accounts (no version byte or padding)
+    pub const LEGACY_SIZE: usize = 8 + 32*2 + 8*6 + 2;
+    pub const SIZE: usize = Self::LEGACY_SIZE + 1 + 32 + 16 + 8 + 72;
     
     /// Calculate total rewards available per year
     /// Formula: (total_supply * apy_numerator) / apy_denominator
@@ -133,3 +428,109 @@ impl RushConfig {
         self.total_supply.saturating_sub(self.minted_so_far)
     }
 }
+
+// ============================================================================
+// PROTOCOL CONFIGURATION
+// ============================================================================
+
+/// Maximum number of fee tiers the allowlist can hold
+pub const MAX_FEE_TIERS: usize = 8;
+
+/// Basis point denominator used for pool fees (10000 = 100%)
+pub const FEE_DENOMINATOR_BPS: u64 = 10_000;
+
+/// DexConfig Account Structure
+/// Global admin-managed settings shared by every pool
+///
+/// Space: 8 (discriminator) + 32*2 + 2*8 + 1 + 1 = 90 bytes
+#[account]
+pub struct DexConfig {
+    pub authority: Pubkey,                 // Protocol admin (32 bytes)
+    pub treasury: Pubkey,                  // Owner of the token accounts receiving protocol fees (32 bytes)
+    pub fee_tiers: [u16; MAX_FEE_TIERS],   // Allowed pool fee tiers in bps (16 bytes)
+    pub fee_tier_count: u8,                // Number of active entries in fee_tiers (1 byte)
+    pub bump: u8,                          // PDA bump seed (1 byte)
+}
+
+impl DexConfig {
+    pub const SIZE: usize = 8 + 32 * 2 + 2 * MAX_FEE_TIERS + 1 + 1;
+
+    /// Default tiers: 0.01%, 0.05%, 0.3% and 1%
+    pub const DEFAULT_FEE_TIERS: [u16; 4] = [1, 5, 30, 100];
+
+    /// Highest fee tier the admin may allow (10%)
+    pub const MAX_FEE_TIER_BPS: u16 = 1_000;
+
+    /// Highest share of a swap fee the protocol may take (50% of the fee)
+    pub const MAX_PROTOCOL_FEE_SHARE_BPS: u16 = 5_000;
+
+    /// Active fee tiers
+    pub fn active_fee_tiers(&self) -> &[u16] {
+        &self.fee_tiers[..self.fee_tier_count as usize]
+    }
+
+    /// Check whether a fee tier is on the allowlist
+    pub fn is_fee_tier_allowed(&self, fee_tier_bps: u16) -> bool {
+        self.active_fee_tiers().contains(&fee_tier_bps)
+    }
+}
+
+// ============================================================================
+// POOL REGISTRY (Module 5.3)
+// ============================================================================
+
+/// Number of pools recorded per registry page
+pub const POOLS_PER_REGISTRY_PAGE: usize = 32;
+
+/// PoolRegistry Account Structure
+/// Singleton factory header counting every pool the program has created
+///
+/// Space: 8 (discriminator) + 8 + 4 + 1 = 21 bytes
+#[account]
+pub struct PoolRegistry {
+    pub pool_count: u64,             // Total pools registered (8 bytes)
+    pub page_count: u32,             // Registry pages allocated so far (4 bytes)
+    pub bump: u8,                    // PDA bump seed (1 byte)
+}
+
+impl PoolRegistry {
+    pub const SIZE: usize = 8 + 8 + 4 + 1;
+
+    /// Page that the next registered pool is appended to
+    pub fn current_page_index(&self) -> u32 {
+        (self.pool_count / POOLS_PER_REGISTRY_PAGE as u64) as u32
+    }
+}
+
+/// PoolRegistryEntry
+/// Discovery record for a single pool
+///
+/// Space: 32*3 + 2 + 8 + 8 = 114 bytes
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PoolRegistryEntry {
+    pub pool: Pubkey,                // Pool PDA (32 bytes)
+    pub token_a_mint: Pubkey,        // Token A mint (32 bytes)
+    pub token_b_mint: Pubkey,        // Token B mint (32 bytes)
+    pub fee_tier_bps: u16,           // Pool fee tier (2 bytes)
+    pub created_at: i64,             // Pool creation timestamp (8 bytes)
+    pub closed_at: i64,              // Set by close_pool, 0 while the pool is live (8 bytes)
+}
+
+impl PoolRegistryEntry {
+    pub const SIZE: usize = 32*3 + 2 + 8 + 8;
+}
+
+/// PoolRegistryPage Account Structure
+/// Fixed-capacity page of registry entries, seeded by page index
+///
+/// Space: 8 (discriminator) + 4 + 4 (vec len) + 114*32 + 1 = 3665 bytes
+#[account]
+pub struct PoolRegistryPage {
+    pub page_index: u32,                   // Index of this page (4 bytes)
+    pub entries: Vec<PoolRegistryEntry>,   // Up to POOLS_PER_REGISTRY_PAGE entries
+    pub bump: u8,                          // PDA bump seed (1 byte)
+}
+
+impl PoolRegistryPage {
+    pub const SIZE: usize = 8 + 4 + 4 + PoolRegistryEntry::SIZE * POOLS_PER_REGISTRY_PAGE + 1;
+}
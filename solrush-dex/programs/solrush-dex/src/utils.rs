@@ -1,5 +1,47 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{
+    Mint,
+    TokenAccount,
+    Token2022,
+    TokenMetadataInitialize,
+    token_metadata_initialize,
+    spl_token_metadata_interface::state::TokenMetadata,
+};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::TransferFeeConfig,
+        BaseStateWithExtensions,
+        ExtensionType,
+        StateWithExtensions,
+    },
+};
 use crate::errors::CustomError;
+use crate::state::{
+    LiquidityPool,
+    PoolType,
+    RushConfig,
+    Tick,
+    UserLiquidityPosition,
+    FEE_DENOMINATOR_BPS,
+    MAX_TICK,
+    MAX_VOLATILITY_ACCUMULATOR,
+    MIN_TICK,
+    TOTAL_WEIGHT_BPS,
+    VOLATILITY_DECAY_PERIOD,
+    VOLATILITY_SATURATION_BPS,
+};
+
+// Kept out of the anchor prelude scope: the macro expands against core's Result
+#[allow(clippy::manual_div_ceil, clippy::assign_op_pattern)]
+mod wide {
+    uint::construct_uint! {
+        /// 256-bit unsigned integer for intermediate products that overflow u128
+        pub struct U256(4);
+    }
+}
+pub use wide::U256;
 
 // ============================================================================
 // UTILITY FUNCTIONS (Module 2.1, 2.3, 2.4, 2.5, 3.1 Helpers)
@@ -71,34 +113,52 @@ pub fn calculate_remove_liquidity_amounts(
     Ok((amount_a, amount_b))
 }
 
-/// Validate that amounts maintain pool ratio within 1% tolerance (Module 2.3)
-pub fn validate_ratio_imbalance(
-    amount_a: u64,
-    amount_b: u64,
-    reserve_a: u64,
-    reserve_b: u64,
-) -> Result<()> {
-    let expected_ratio = (reserve_b as u128)
-        .checked_mul(10000)
+/// Quote the amount of the other token matching a deposit at the current ratio (Module 2.3)
+/// Formula: amount_b = amount_a * reserve_b / reserve_a
+pub fn quote_liquidity_amount(amount_a: u64, reserve_a: u64, reserve_b: u64) -> Result<u64> {
+    require!(amount_a > 0, CustomError::InvalidAmount);
+    require!(reserve_a > 0 && reserve_b > 0, CustomError::InsufficientLiquidity);
+
+    let amount_b = (amount_a as u128)
+        .checked_mul(reserve_b as u128)
         .ok_or(error!(CustomError::CalculationOverflow))?
         .checked_div(reserve_a as u128)
         .ok_or(error!(CustomError::CalculationOverflow))?;
 
-    let provided_ratio = (amount_b as u128)
-        .checked_mul(10000)
-        .ok_or(error!(CustomError::CalculationOverflow))?
-        .checked_div(amount_a as u128)
-        .ok_or(error!(CustomError::CalculationOverflow))?;
+    u64::try_from(amount_b).map_err(|_| error!(CustomError::CalculationOverflow))
+}
 
-    let tolerance = 100u128;
-    let diff = if expected_ratio > provided_ratio {
-        expected_ratio - provided_ratio
-    } else {
-        provided_ratio - expected_ratio
-    };
+/// Calculate the deposit pair to actually use when adding liquidity (Module 2.3)
+///
+/// Mirrors Uniswap V2's router: keep all of amount_a_desired if the matching
+/// amount of B fits within amount_b_desired, otherwise keep all of amount_b_desired
+/// and scale A down. The unused side is simply never transferred.
+///
+/// Returns: (amount_a, amount_b) to deposit
+pub fn calculate_optimal_liquidity_amounts(
+    amount_a_desired: u64,
+    amount_b_desired: u64,
+    amount_a_min: u64,
+    amount_b_min: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+) -> Result<(u64, u64)> {
+    require!(
+        amount_a_desired > 0 && amount_b_desired > 0,
+        CustomError::InvalidAmount
+    );
 
-    require!(diff <= tolerance, CustomError::RatioImbalance);
-    Ok(())
+    let amount_b_optimal = quote_liquidity_amount(amount_a_desired, reserve_a, reserve_b)?;
+    if amount_b_optimal <= amount_b_desired {
+        require!(amount_b_optimal >= amount_b_min, CustomError::SlippageTooHigh);
+        return Ok((amount_a_desired, amount_b_optimal));
+    }
+
+    let amount_a_optimal = quote_liquidity_amount(amount_b_desired, reserve_b, reserve_a)?;
+    require!(amount_a_optimal <= amount_a_desired, CustomError::CalculationOverflow);
+    require!(amount_a_optimal >= amount_a_min, CustomError::SlippageTooHigh);
+
+    Ok((amount_a_optimal, amount_b_desired))
 }
 
 /// Module 2.5 & 3.1: Calculate output amount for swaps with fee
@@ -163,6 +223,424 @@ pub fn calculate_output_amount(
     Ok(output_amount as u64)
 }
 
+/// Calculate how much of a single-sided deposit to swap before adding liquidity (Module 2.6)
+///
+/// Solves for the swap amount s that leaves the remaining input and the swap output
+/// in the post-swap reserve ratio, with d = fee_denominator, n = fee_numerator, r = d - n:
+/// s = (sqrt(R * (R * (2d - n)^2 + 4 * r * d * A)) - R * (2d - n)) / (2r)
+///
+/// Parameters:
+/// - amount_in: Total single-sided deposit (A)
+/// - reserve_in: Pool reserve of the deposited token (R)
+/// - fee_numerator / fee_denominator: Pool swap fee
+///
+/// Returns: Portion of amount_in to swap for the other token
+pub fn calculate_single_sided_swap_amount(
+    amount_in: u64,
+    reserve_in: u64,
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> Result<u64> {
+    require!(amount_in > 0, CustomError::InvalidAmount);
+    require!(reserve_in > 0, CustomError::InsufficientLiquidity);
+    require!(
+        fee_denominator > 0 && fee_numerator < fee_denominator,
+        CustomError::InvalidFeeParameters
+    );
+
+    let reserve = U256::from(reserve_in);
+    let two_d_minus_n = U256::from(2 * fee_denominator - fee_numerator);
+    let r = U256::from(fee_denominator - fee_numerator);
+    let d = U256::from(fee_denominator);
+
+    let inner = reserve
+        .checked_mul(two_d_minus_n * two_d_minus_n)
+        .and_then(|v| v.checked_add(U256::from(4u8) * r * d * U256::from(amount_in)))
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
+    let root = reserve
+        .checked_mul(inner)
+        .ok_or(error!(CustomError::CalculationOverflow))?
+        .integer_sqrt();
+
+    let swap_amount = root
+        .checked_sub(reserve * two_d_minus_n)
+        .ok_or(error!(CustomError::CalculationOverflow))?
+        / (U256::from(2u8) * r);
+
+    let swap_amount = u64::try_from(swap_amount)
+        .map_err(|_| error!(CustomError::CalculationOverflow))?;
+
+    // Never swap the whole deposit; both sides must be non-zero to mint LP
+    require!(
+        swap_amount > 0 && swap_amount < amount_in,
+        CustomError::InvalidAmount
+    );
+
+    Ok(swap_amount)
+}
+
+/// Calculate the protocol's cut of a swap fee (Module 5.2)
+/// Formula: protocol_fee = fee_amount * protocol_fee_share_bps / 10000
+/// The remainder of the fee stays in the reserves for LPs
+pub fn calculate_protocol_fee(fee_amount: u64, protocol_fee_share_bps: u16) -> Result<u64> {
+    let protocol_fee = (fee_amount as u128)
+        .checked_mul(protocol_fee_share_bps as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))?
+        .checked_div(FEE_DENOMINATOR_BPS as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
+    Ok(protocol_fee as u64)
+}
+
+/// Calculate the fee on a flash loan (Module 3.5)
+/// Formula: fee = ceil(amount * flash_loan_fee_bps / 10000)
+/// Rounds up so small loans are never free
+pub fn calculate_flash_loan_fee(amount: u64, flash_loan_fee_bps: u16) -> Result<u64> {
+    let fee = (amount as u128)
+        .checked_mul(flash_loan_fee_bps as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))?
+        .div_ceil(FEE_DENOMINATOR_BPS as u128);
+
+    u64::try_from(fee).map_err(|_| error!(CustomError::CalculationOverflow))
+}
+
+/// Fixed-point scale for acc_rush_per_share and a staker's share of the pool (Module 4.7)
+pub const REWARD_SHARE_PRECISION: u128 = 1_000_000_000_000;
+
+/// RushConfig reward_index brought forward to `now` without writing it (Module 4.7)
+/// The index only grows while rewards are unpaused, at the rate currently set
+pub fn current_rush_reward_index(rush_config: &RushConfig, now: i64) -> Result<u128> {
+    // Configs created before the index existed count from launch
+    let updated_at = if rush_config.reward_index_updated_at > 0 {
+        rush_config.reward_index_updated_at
+    } else {
+        rush_config.start_timestamp
+    };
+    if rush_config.is_paused || now <= updated_at {
+        return Ok(rush_config.reward_index);
+    }
+
+    (rush_config.rewards_per_second as u128)
+        .checked_mul((now - updated_at) as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))?
+        .checked_add(rush_config.reward_index)
+        .ok_or(error!(CustomError::CalculationOverflow))
+}
+
+/// Fold the seconds since the last update into reward_index at the old rate
+/// Must run before rewards_per_second or is_paused changes, so emissions already
+/// earned are never repriced
+pub fn advance_rush_reward_index(rush_config: &mut RushConfig, now: i64) -> Result<()> {
+    rush_config.reward_index = current_rush_reward_index(rush_config, now)?;
+    rush_config.reward_index_updated_at = now;
+    Ok(())
+}
+
+/// Growth of a pool's acc_rush_per_share for a reward_index increase (Module 4.3 / 4.7)
+/// Formula: increment = index_delta * PRECISION / total_staked_lp
+/// Nothing is emitted while no LP is staked
+pub fn calculate_rush_per_share_increment(
+    index_delta: u128,
+    total_staked_lp: u64,
+) -> Result<u128> {
+    if total_staked_lp == 0 || index_delta == 0 {
+        return Ok(0);
+    }
+
+    index_delta
+        .checked_mul(REWARD_SHARE_PRECISION)
+        .ok_or(error!(CustomError::CalculationOverflow))?
+        .checked_div(total_staked_lp as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))
+}
+
+/// Bring a pool's RUSH accumulator up to the given reward_index
+/// Must run before total_staked_lp or any position's staked_lp changes, so each
+/// interval is split among exactly the LP that was staked during it
+pub fn update_rush_accumulator(
+    pool: &mut LiquidityPool,
+    reward_index: u128,
+    now: i64,
+) -> Result<()> {
+    let index_delta = reward_index
+        .checked_sub(pool.rush_index_snapshot)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    let increment = calculate_rush_per_share_increment(index_delta, pool.total_staked_lp)?;
+    pool.acc_rush_per_share = pool
+        .acc_rush_per_share
+        .checked_add(increment)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    pool.rush_index_snapshot = reward_index;
+    pool.last_reward_timestamp = now;
+    Ok(())
+}
+
+/// RUSH owed to `staked_lp` at the accumulator value, before subtracting reward debt
+/// Formula: staked_lp * acc_rush_per_share / PRECISION
+pub fn calculate_reward_debt(staked_lp: u64, acc_rush_per_share: u128) -> Result<u128> {
+    (staked_lp as u128)
+        .checked_mul(acc_rush_per_share)
+        .ok_or(error!(CustomError::CalculationOverflow))?
+        .checked_div(REWARD_SHARE_PRECISION)
+        .ok_or(error!(CustomError::CalculationOverflow))
+}
+
+/// RUSH accrued by a staked LP position since its last settlement (Module 4.3 / 4.7)
+/// Formula: staked_lp * acc_rush_per_share / PRECISION - reward_debt
+/// Only LP escrowed via stake_lp earns; wallet-held LP does not
+pub fn calculate_staking_rewards(
+    staked_lp: u64,
+    acc_rush_per_share: u128,
+    reward_debt: u128,
+) -> Result<u64> {
+    calculate_reward_debt(staked_lp, acc_rush_per_share)?
+        .checked_sub(reward_debt)
+        .ok_or(error!(CustomError::CalculationOverflow))?
+        .try_into()
+        .map_err(|_| error!(CustomError::CalculationOverflow))
+}
+
+/// Reject a transaction that lands after the caller's deadline (unix timestamp)
+/// `None` means no deadline; a deadline equal to the current time is still accepted
+pub fn check_deadline(deadline: Option<i64>) -> Result<()> {
+    if let Some(deadline) = deadline {
+        require!(
+            Clock::get()?.unix_timestamp <= deadline,
+            CustomError::DeadlineExceeded
+        );
+    }
+    Ok(())
+}
+
+// ============================================================================
+// RESERVE RECONCILIATION (Module 2.7)
+// ============================================================================
+
+/// Vault balance above what the pool has accounted for (reserve + protocol fees)
+/// Returns (surplus_a, surplus_b); errors if a vault holds less than its bookkeeping
+pub fn calculate_vault_surplus(
+    pool: &LiquidityPool,
+    vault_a_amount: u64,
+    vault_b_amount: u64,
+) -> Result<(u64, u64)> {
+    let accounted_a = pool
+        .reserve_a
+        .checked_add(pool.protocol_fees_a)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    let accounted_b = pool
+        .reserve_b
+        .checked_add(pool.protocol_fees_b)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
+    let surplus_a = vault_a_amount
+        .checked_sub(accounted_a)
+        .ok_or(error!(CustomError::VaultInvariantViolated))?;
+    let surplus_b = vault_b_amount
+        .checked_sub(accounted_b)
+        .ok_or(error!(CustomError::VaultInvariantViolated))?;
+
+    Ok((surplus_a, surplus_b))
+}
+
+/// Assert that both vaults cover the pool's reserves plus uncollected protocol fees
+///
+/// Call at the end of any instruction that moves pool tokens. The vaults are reloaded
+/// so balances reflect CPIs made earlier in the instruction. Compiled to a no-op in
+/// release builds unless the `invariant-checks` feature is enabled.
+pub fn assert_vault_invariants<'info>(
+    pool: &LiquidityPool,
+    token_a_vault: &mut InterfaceAccount<'info, TokenAccount>,
+    token_b_vault: &mut InterfaceAccount<'info, TokenAccount>,
+) -> Result<()> {
+    if cfg!(any(debug_assertions, feature = "invariant-checks")) {
+        token_a_vault.reload()?;
+        token_b_vault.reload()?;
+        calculate_vault_surplus(pool, token_a_vault.amount, token_b_vault.amount)?;
+    }
+    Ok(())
+}
+
+// ============================================================================
+// TOKEN-2022 SUPPORT (Module 2.8)
+// ============================================================================
+
+/// Token-2022 mint extensions that pools accept
+/// Anything that can block, redirect or hook a transfer is rejected at pool creation
+const SUPPORTED_MINT_EXTENSIONS: [ExtensionType; 4] = [
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+];
+
+/// Reject Token-2022 mints carrying extensions the pool cannot account for
+/// Legacy SPL Token mints are always supported
+pub fn validate_supported_mint(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == anchor_spl::token::ID {
+        return Ok(());
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    for extension in mint_state.get_extension_types()? {
+        require!(
+            SUPPORTED_MINT_EXTENSIONS.contains(&extension),
+            CustomError::UnsupportedMintExtension
+        );
+    }
+
+    Ok(())
+}
+
+/// Fee withheld by the mint's TransferFeeConfig when `amount` is transferred this epoch
+/// Returns 0 for legacy SPL Token mints and Token-2022 mints without a transfer fee
+pub fn calculate_transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == anchor_spl::token::ID {
+        return Ok(0);
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(error!(CustomError::CalculationOverflow)),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Whether a Token-2022 mint carries a MintCloseAuthority and can be closed at zero supply
+/// LP mints created before close_pool reclaimed them have none
+pub fn mint_has_close_authority(mint: &InterfaceAccount<Mint>) -> Result<bool> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == anchor_spl::token::ID {
+        return Ok(false);
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    Ok(mint_state
+        .get_extension_types()?
+        .contains(&ExtensionType::MintCloseAuthority))
+}
+
+/// Amount that actually lands in the destination account after the transfer fee
+/// Reserves are credited with this value rather than the amount sent
+pub fn calculate_amount_after_transfer_fee(
+    mint: &InterfaceAccount<Mint>,
+    amount: u64,
+) -> Result<u64> {
+    let transfer_fee = calculate_transfer_fee(mint, amount)?;
+    amount
+        .checked_sub(transfer_fee)
+        .ok_or(error!(CustomError::CalculationOverflow))
+}
+
+/// Amount to send so that `amount` lands in the destination after the transfer fee
+/// Used where the pool is owed an exact amount, such as concentrated liquidity deposits
+pub fn calculate_amount_before_transfer_fee(
+    mint: &InterfaceAccount<Mint>,
+    amount: u64,
+) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == anchor_spl::token::ID || amount == 0 {
+        return Ok(amount);
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => {
+            let transfer_fee = transfer_fee_config
+                .calculate_inverse_epoch_fee(Clock::get()?.epoch, amount)
+                .ok_or(error!(CustomError::CalculationOverflow))?;
+            amount
+                .checked_add(transfer_fee)
+                .ok_or(error!(CustomError::CalculationOverflow))
+        }
+        Err(_) => Ok(amount),
+    }
+}
+
+/// Maximum length of a pair symbol used to label LP tokens
+pub const MAX_PAIR_SYMBOL_LEN: usize = 10;
+
+/// Validate a token symbol supplied for LP metadata (1-10 ASCII alphanumerics)
+pub fn validate_pair_symbol(symbol: &str) -> Result<()> {
+    require!(
+        !symbol.is_empty()
+            && symbol.len() <= MAX_PAIR_SYMBOL_LEN
+            && symbol.chars().all(|c| c.is_ascii_alphanumeric()),
+        CustomError::InvalidTokenSymbol
+    );
+    Ok(())
+}
+
+/// Write name/symbol/uri into a Token-2022 mint whose metadata pointer targets itself
+///
+/// The metadata extension is appended by the token program, so the mint is first
+/// topped up to stay rent exempt at its new size. `authority` is both the mint
+/// authority and the metadata update authority.
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_token_metadata<'info>(
+    token_program: &Program<'info, Token2022>,
+    system_program: &Program<'info, System>,
+    payer: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    let metadata = TokenMetadata {
+        name: name.clone(),
+        symbol: symbol.clone(),
+        uri: uri.clone(),
+        ..Default::default()
+    };
+    let metadata_space = metadata.tlv_size_of()?;
+    let required_lamports = Rent::get()?.minimum_balance(
+        mint.data_len()
+            .checked_add(metadata_space)
+            .ok_or(error!(CustomError::CalculationOverflow))?,
+    );
+    let additional_lamports = required_lamports.saturating_sub(mint.lamports());
+
+    if additional_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer,
+                    to: mint.clone(),
+                },
+            ),
+            additional_lamports,
+        )?;
+    }
+
+    token_metadata_initialize(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TokenMetadataInitialize {
+                program_id: token_program.to_account_info(),
+                metadata: mint.clone(),
+                update_authority: authority.clone(),
+                mint_authority: authority,
+                mint,
+            },
+            signer_seeds,
+        ),
+        name,
+        symbol,
+        uri,
+    )
+}
+
 /// Integer square root using Newton's method
 pub fn isqrt(n: u128) -> u128 {
     if n < 2 {
@@ -181,68 +659,2548 @@ pub fn isqrt(n: u128) -> u128 {
 }
 
 // ============================================================================
-// PYTH ORACLE PRICE FUNCTIONS (Module 3.5)
+// STABLESWAP CURVE (Module 2.11)
 // ============================================================================
+//
+// Curve StableSwap invariant for two coins (n = 2), with Ann = A * n:
+//   Ann * (x + y) + D = Ann * D + D^3 / (4 * x * y)
+// Both mints share decimals (enforced at pool creation), so raw amounts are
+// already in the same precision. Intermediates use U256.
 
-/// Get price from Pyth Oracle with 6 decimal precision
-/// 
-/// Note: This is a placeholder for Pyth integration.
-/// Currently returns 0 - will be fully implemented with Pyth SDK in future updates.
-/// 
-/// For now, use calculate_pool_price() for local AMM price calculations.
-/// 
-/// Parameters:
-/// - price_account: Pyth price account info
-/// 
-/// Returns: Price with 6 decimal precision
-#[allow(unused)]
-pub fn get_pyth_price(price_account: &AccountInfo) -> Result<u64> {
-    // TODO: Implement Pyth Oracle integration
-    // For now, return 0 as placeholder
-    // This will be replaced with actual Pyth price fetching in future updates
-    Ok(0)
+/// Number of coins in a StableSwap pool
+const STABLE_N_COINS: u64 = 2;
+
+/// Newton iteration cap for get_d / get_y
+const STABLE_MAX_ITERATIONS: usize = 255;
+
+/// Solve the StableSwap invariant D for the given balances (Newton's method)
+/// D equals x + y for a balanced pool and falls towards 2 * sqrt(x * y) as A -> 0
+pub fn get_d(reserve_a: u64, reserve_b: u64, amp: u64) -> Result<u128> {
+    let sum = U256::from(reserve_a) + U256::from(reserve_b);
+    if sum.is_zero() {
+        return Ok(0);
+    }
+    require!(
+        reserve_a > 0 && reserve_b > 0,
+        CustomError::InsufficientLiquidity
+    );
+    require!(amp > 0, CustomError::InvalidAmpCoefficient);
+
+    let n = U256::from(STABLE_N_COINS);
+    let ann = U256::from(amp) * n;
+    let x_times_n = U256::from(reserve_a) * n;
+    let y_times_n = U256::from(reserve_b) * n;
+
+    let mut d = sum;
+    for _ in 0..STABLE_MAX_ITERATIONS {
+        // d_p = D^3 / (n^n * x * y)
+        let d_p = d * d / x_times_n * d / y_times_n;
+        let d_prev = d;
+
+        // D = (Ann * S + n * d_p) * D / ((Ann - 1) * D + (n + 1) * d_p)
+        let numerator = (ann * sum + d_p * n) * d;
+        let denominator = (ann - U256::one()) * d + (n + U256::one()) * d_p;
+        d = numerator / denominator;
+
+        if abs_diff(d, d_prev) <= U256::one() {
+            return u128::try_from(d).map_err(|_| error!(CustomError::CalculationOverflow));
+        }
+    }
+
+    err!(CustomError::StableSwapNotConverged)
 }
 
-/// Calculate local pool price with 6 decimal precision
-/// 
-/// Formula: price = (reserve_b * 1_000_000) / reserve_a
-/// Returns price of token_a in terms of token_b
-pub fn calculate_pool_price(reserve_a: u64, reserve_b: u64) -> Result<u64> {
-    require!(reserve_a > 0, CustomError::InsufficientLiquidity);
-    
-    let price = (reserve_b as u128)
-        .checked_mul(1_000_000)
+/// Solve for the other balance y that keeps D fixed when one balance is x (Newton's method)
+/// y^2 + (x + D / Ann - D) * y = D^3 / (n^n * x * Ann)
+pub fn get_y(new_reserve_in: u64, d: u128, amp: u64) -> Result<u64> {
+    require!(new_reserve_in > 0, CustomError::InsufficientLiquidity);
+    require!(amp > 0, CustomError::InvalidAmpCoefficient);
+
+    let n = U256::from(STABLE_N_COINS);
+    let ann = U256::from(amp) * n;
+    let d = U256::from(d);
+    let x = U256::from(new_reserve_in);
+
+    let c = d * d / (x * n) * d / (ann * n);
+    let b = x + d / ann;
+
+    let mut y = d;
+    for _ in 0..STABLE_MAX_ITERATIONS {
+        let y_prev = y;
+        let denominator = (y * U256::from(2u8) + b)
+            .checked_sub(d)
+            .ok_or(error!(CustomError::CalculationOverflow))?;
+        y = (y * y + c) / denominator;
+
+        if abs_diff(y, y_prev) <= U256::one() {
+            return u64::try_from(y).map_err(|_| error!(CustomError::CalculationOverflow));
+        }
+    }
+
+    err!(CustomError::StableSwapNotConverged)
+}
+
+fn abs_diff(a: U256, b: U256) -> U256 {
+    if a > b { a - b } else { b - a }
+}
+
+/// Calculate StableSwap output with the pool fee taken from the input (Module 2.11)
+/// Mirrors calculate_output_amount; D is rounded up and the output down by one
+/// unit so Newton rounding can never pay out more than the invariant allows
+pub fn calculate_stable_swap_output(
+    input_amount: u64,
+    input_reserve: u64,
+    output_reserve: u64,
+    amp: u64,
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> Result<u64> {
+    require!(input_amount > 0, CustomError::InvalidAmount);
+    require!(
+        input_reserve > 0 && output_reserve > 0,
+        CustomError::InsufficientLiquidity
+    );
+
+    let fee_amount = (input_amount as u128)
+        .checked_mul(fee_numerator as u128)
         .ok_or(error!(CustomError::CalculationOverflow))?
-        .checked_div(reserve_a as u128)
+        .checked_div(fee_denominator as u128)
         .ok_or(error!(CustomError::CalculationOverflow))? as u64;
-    
-    Ok(price)
+    let amount_with_fee = input_amount
+        .checked_sub(fee_amount)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
+    let d = get_d(input_reserve, output_reserve, amp)?;
+    let new_input_reserve = input_reserve
+        .checked_add(amount_with_fee)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    // get_d rounds down, which would understate y; solving against D + 1 keeps
+    // the rounding on the pool's side
+    let new_output_reserve = get_y(new_input_reserve, d + 1, amp)?;
+
+    let output_amount = output_reserve
+        .saturating_sub(new_output_reserve)
+        .saturating_sub(1);
+
+    require!(output_amount > 0, CustomError::InsufficientLiquidity);
+
+    Ok(output_amount)
 }
 
-/// Compare pool price against target price for limit order execution
-/// 
-/// For sell orders (selling token_a for token_b):
-/// - Condition: pool_price >= target_price (want more token_b per token_a)
-/// 
-/// Parameters:
-/// - pool_price: Current pool price (reserve_b / reserve_a) with 6 decimals
-/// - target_price: Target price with 6 decimals
-/// - is_sell: true if selling token_a, false if selling token_b
-/// 
-/// Returns: true if price condition is met
-pub fn check_price_condition(
-    pool_price: u64,
-    target_price: u64,
-    is_sell: bool,
-) -> bool {
-    if is_sell {
-        // For sell: we want pool_price >= target_price
-        // (want to receive more per unit)
-        pool_price >= target_price
-    } else {
-        // For buy: we want pool_price <= target_price
-        // (want to pay less per unit)
-        pool_price <= target_price
+// ============================================================================
+// WEIGHTED POOL MATH (Module 2.12)
+// ============================================================================
+//
+// Balancer-style invariant V = a^wa * b^wb with wa + wb = 1. Powers are
+// computed in 18-decimal fixed point as exp(y * ln(x)); ln and exp are
+// accurate to a few dozen units in the last place, and every result that
+// reaches a token amount is widened by MAX_POW_RELATIVE_ERROR in the pool's
+// favour before rounding.
+
+/// 1.0 in 18-decimal fixed point
+pub const FIXED_ONE: u128 = 1_000_000_000_000_000_000;
+const FIXED_ONE_SIGNED: i128 = FIXED_ONE as i128;
+
+/// ln(2) in 18-decimal fixed point
+const LN_2: i128 = 693_147_180_559_945_309;
+
+/// exp_fixed bounds: e^47 is the largest power of e that still fits in u128
+/// at 18 decimals, and anything below e^-42 rounds to zero
+const MAX_NATURAL_EXPONENT: i128 = 47 * FIXED_ONE_SIGNED;
+const MIN_NATURAL_EXPONENT: i128 = -42 * FIXED_ONE_SIGNED;
+
+/// Relative error bound of pow_fixed (1e-14), applied when rounding powers
+pub const MAX_POW_RELATIVE_ERROR: u128 = 10_000;
+
+/// A weighted swap may add at most 30% of the input reserve, which keeps the
+/// power's base in the range where pow_fixed is accurate
+pub const WEIGHTED_MAX_IN_RATIO_BPS: u64 = 3_000;
+
+/// Natural logarithm of an 18-decimal fixed-point value
+/// Writes x = m * 2^k with m in [1, 2) and sums ln(m) = 2 * atanh((m - 1) / (m + 1))
+pub fn ln_fixed(x: u128) -> Result<i128> {
+    require!(x > 0, CustomError::InvalidAmount);
+
+    // FIXED_ONE is a 60-bit number; shifting x to the same bit length puts m in [0.5, 2)
+    let mut k = (128 - x.leading_zeros()) as i32 - 60;
+    let mut m = if k >= 0 { x >> k } else { x << -k };
+    if m < FIXED_ONE {
+        m <<= 1;
+        k -= 1;
     }
-}
 
+    // z < 1/3, so each term shrinks by at least 9x
+    let z = ((m - FIXED_ONE) * FIXED_ONE / (m + FIXED_ONE)) as i128;
+    let z_squared = z * z / FIXED_ONE_SIGNED;
+    let mut term = z;
+    let mut series = z;
+    let mut n: i128 = 1;
+    while term != 0 {
+        term = term * z_squared / FIXED_ONE_SIGNED;
+        n += 2;
+        series += term / n;
+    }
+
+    Ok(k as i128 * LN_2 + 2 * series)
+}
+
+/// e^x for an 18-decimal fixed-point exponent
+/// Reduces x = k * ln(2) + r with |r| <= ln(2) / 2 and sums the Taylor series of e^r
+pub fn exp_fixed(x: i128) -> Result<u128> {
+    require!(x <= MAX_NATURAL_EXPONENT, CustomError::CalculationOverflow);
+    if x < MIN_NATURAL_EXPONENT {
+        return Ok(0);
+    }
+
+    let half_ln_2 = if x >= 0 { LN_2 / 2 } else { -LN_2 / 2 };
+    let k = (x + half_ln_2) / LN_2;
+    let r = x - k * LN_2;
+
+    let mut term = FIXED_ONE_SIGNED;
+    let mut series = FIXED_ONE_SIGNED;
+    let mut n: i128 = 1;
+    while term != 0 {
+        term = term * r / (FIXED_ONE_SIGNED * n);
+        series += term;
+        n += 1;
+    }
+
+    // e^r >= e^-0.35, so the series is positive
+    let series = series as u128;
+    if k >= 0 {
+        series
+            .checked_mul(1u128 << k)
+            .ok_or(error!(CustomError::CalculationOverflow))
+    } else {
+        Ok(series >> -k)
+    }
+}
+
+/// x^y for 18-decimal fixed-point base and exponent, computed as exp(y * ln(x))
+pub fn pow_fixed(x: u128, y: u128) -> Result<u128> {
+    if y == 0 {
+        return Ok(FIXED_ONE);
+    }
+    if x == 0 {
+        return Ok(0);
+    }
+
+    let ln_x = ln_fixed(x)?;
+    // Split y so ln_x * y cannot overflow i128 for exponents up to 99
+    let whole = ln_x
+        .checked_mul((y / FIXED_ONE) as i128)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    let fraction = ln_x * (y % FIXED_ONE) as i128 / FIXED_ONE_SIGNED;
+
+    exp_fixed(whole + fraction)
+}
+
+/// pow_fixed rounded up by its error bound
+pub fn pow_fixed_up(x: u128, y: u128) -> Result<u128> {
+    let raw = pow_fixed(x, y)?;
+    let max_error = raw / (FIXED_ONE / MAX_POW_RELATIVE_ERROR) + 1;
+    raw.checked_add(max_error)
+        .ok_or(error!(CustomError::CalculationOverflow))
+}
+
+/// Weighted invariant V = a^wa * b^wb in 18-decimal fixed point, before rounding
+fn weighted_invariant_fixed(
+    amount_a: u64,
+    amount_b: u64,
+    weight_a_bps: u16,
+    weight_b_bps: u16,
+) -> Result<u128> {
+    require!(amount_a > 0 && amount_b > 0, CustomError::InvalidAmount);
+
+    let ln_a = ln_fixed(amount_a as u128 * FIXED_ONE)?;
+    let ln_b = ln_fixed(amount_b as u128 * FIXED_ONE)?;
+    let exponent = (ln_a * weight_a_bps as i128 + ln_b * weight_b_bps as i128)
+        / TOTAL_WEIGHT_BPS as i128;
+
+    exp_fixed(exponent)
+}
+
+/// Weighted invariant V = a^wa * b^wb with weights in basis points, rounded down
+/// Used as the first deposit's LP supply; equals sqrt(a * b) for a 50/50 pool
+pub fn calculate_weighted_invariant(
+    amount_a: u64,
+    amount_b: u64,
+    weight_a_bps: u16,
+    weight_b_bps: u16,
+) -> Result<u64> {
+    let invariant = weighted_invariant_fixed(amount_a, amount_b, weight_a_bps, weight_b_bps)?;
+    let max_error = invariant / (FIXED_ONE / MAX_POW_RELATIVE_ERROR) + 1;
+
+    u64::try_from(invariant.saturating_sub(max_error) / FIXED_ONE)
+        .map_err(|_| error!(CustomError::CalculationOverflow))
+}
+
+/// calculate_weighted_invariant rounded up by its error bound
+pub fn calculate_weighted_invariant_up(
+    amount_a: u64,
+    amount_b: u64,
+    weight_a_bps: u16,
+    weight_b_bps: u16,
+) -> Result<u64> {
+    let invariant = weighted_invariant_fixed(amount_a, amount_b, weight_a_bps, weight_b_bps)?;
+    let max_error = invariant / (FIXED_ONE / MAX_POW_RELATIVE_ERROR) + 1;
+
+    u64::try_from((invariant + max_error).div_ceil(FIXED_ONE))
+        .map_err(|_| error!(CustomError::CalculationOverflow))
+}
+
+/// Calculate weighted pool output with the pool fee taken from the input (Module 2.12)
+/// Formula: out = reserve_out * (1 - (reserve_in / (reserve_in + amount_in))^(w_in / w_out))
+/// The base and power round up, so the output rounds down
+#[allow(clippy::too_many_arguments)]
+pub fn calculate_weighted_swap_output(
+    input_amount: u64,
+    input_reserve: u64,
+    output_reserve: u64,
+    input_weight_bps: u16,
+    output_weight_bps: u16,
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> Result<u64> {
+    require!(input_amount > 0, CustomError::InvalidAmount);
+    require!(
+        input_reserve > 0 && output_reserve > 0,
+        CustomError::InsufficientLiquidity
+    );
+    require!(
+        input_weight_bps > 0 && output_weight_bps > 0,
+        CustomError::InvalidPoolWeights
+    );
+
+    let fee_amount = (input_amount as u128)
+        .checked_mul(fee_numerator as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))?
+        .checked_div(fee_denominator as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))? as u64;
+    let amount_with_fee = input_amount
+        .checked_sub(fee_amount)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
+    require!(
+        amount_with_fee as u128 * FEE_DENOMINATOR_BPS as u128
+            <= input_reserve as u128 * WEIGHTED_MAX_IN_RATIO_BPS as u128,
+        CustomError::WeightedSwapTooLarge
+    );
+
+    let new_input_reserve = input_reserve as u128 + amount_with_fee as u128;
+    let base = (input_reserve as u128 * FIXED_ONE).div_ceil(new_input_reserve);
+    let exponent = input_weight_bps as u128 * FIXED_ONE / output_weight_bps as u128;
+    let power = pow_fixed_up(base, exponent)?;
+
+    let output_amount = (output_reserve as u128 * FIXED_ONE.saturating_sub(power) / FIXED_ONE) as u64;
+
+    require!(output_amount > 0, CustomError::InsufficientLiquidity);
+
+    Ok(output_amount)
+}
+
+/// Weighted spot price of token_a in token_b with 6 decimal precision (Module 2.12)
+/// Formula: price = (reserve_b / weight_b) / (reserve_a / weight_a)
+pub fn calculate_weighted_pool_price(
+    reserve_a: u64,
+    reserve_b: u64,
+    weight_a_bps: u16,
+    weight_b_bps: u16,
+) -> Result<u64> {
+    require!(reserve_a > 0, CustomError::InsufficientLiquidity);
+    require!(
+        weight_a_bps > 0 && weight_b_bps > 0,
+        CustomError::InvalidPoolWeights
+    );
+
+    let price = (reserve_b as u128)
+        .checked_mul(weight_a_bps as u128 * 1_000_000)
+        .ok_or(error!(CustomError::CalculationOverflow))?
+        .checked_div(reserve_a as u128 * weight_b_bps as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+
+    u64::try_from(price).map_err(|_| error!(CustomError::CalculationOverflow))
+}
+
+// ============================================================================
+// CONCENTRATED LIQUIDITY MATH (Module 2.13)
+// ============================================================================
+//
+// Uniswap V3 style math on Q64.64 sqrt prices. Amounts owed to the pool round
+// up and amounts paid out round down, so rounding never drains a vault.
+
+/// sqrt(1.0001^MIN_TICK) and sqrt(1.0001^MAX_TICK) as Q64.64
+pub const MIN_SQRT_PRICE_X64: u128 = 4_295_048_017;
+pub const MAX_SQRT_PRICE_X64: u128 = 79_226_673_515_401_279_992_447_579_062;
+
+/// ln(1.0001) in 18-decimal fixed point
+const LN_1_0001: i128 = 99_995_000_333_308;
+
+/// floor(2^128 / sqrt(1.0001)^(2^i)) for each bit i of |tick|
+const TICK_RATIO_FACTORS: [u128; 19] = [
+    0xfffcb933bd6fad37aa2d162d1a594001,
+    0xfff97272373d413259a46990580e2139,
+    0xfff2e50f5f656932ef12357cf3c7fdcb,
+    0xffe5caca7e10e4e61c3624eaa0941ccf,
+    0xffcb9843d60f6159c9db58835c926643,
+    0xff973b41fa98c081472e6896dfb254bf,
+    0xff2ea16466c96a3843ec78b326b52860,
+    0xfe5dee046a99a2a811c461f1969c3052,
+    0xfcbe86c7900a88aedcffc83b479aa3a3,
+    0xf987a7253ac413176f2b074cf7815e53,
+    0xf3392b0822b70005940c7a398e4b70f2,
+    0xe7159475a2c29b7443b29c7fa6e889d8,
+    0xd097f3bdfd2022b8845ad8f792aa5825,
+    0xa9f746462d870fdf8a65dc1f90e061e4,
+    0x70d869a156d2a1b890bb3df62baf32f6,
+    0x31be135f97d08fd981231505542fcfa5,
+    0x09aa508b5b7a84e1c677de54f3e99bc8,
+    0x005d6af8dedb81196699c329225ee604,
+    0x00002216e584f5fa1ea926041bedfe97,
+];
+
+/// sqrt(1.0001^tick) as Q64.64, rounded up
+/// Multiplies the Q128 factors for each set bit of |tick| and inverts for positive ticks
+pub fn get_sqrt_price_at_tick(tick: i32) -> Result<u128> {
+    require!(
+        (MIN_TICK..=MAX_TICK).contains(&tick),
+        CustomError::InvalidTickIndex
+    );
+
+    let abs_tick = tick.unsigned_abs();
+    let mut ratio = U256::one() << 128;
+    for (bit, factor) in TICK_RATIO_FACTORS.iter().enumerate() {
+        if abs_tick & (1 << bit) != 0 {
+            ratio = (ratio * U256::from(*factor)) >> 128;
+        }
+    }
+    if tick > 0 {
+        ratio = U256::MAX / ratio;
+    }
+
+    // Q128 -> Q64, rounding up
+    let round_up = !(ratio & U256::from(u64::MAX)).is_zero();
+    let sqrt_price = (ratio >> 64) + if round_up { U256::one() } else { U256::zero() };
+    u128::try_from(sqrt_price).map_err(|_| error!(CustomError::CalculationOverflow))
+}
+
+/// Greatest tick whose sqrt price is <= sqrt_price_x64
+/// Estimates floor(2 * ln(sqrt_price) / ln(1.0001)) with ln_fixed, then settles
+/// the estimate against get_sqrt_price_at_tick so the result is exact
+pub fn get_tick_at_sqrt_price(sqrt_price_x64: u128) -> Result<i32> {
+    require!(
+        (MIN_SQRT_PRICE_X64..MAX_SQRT_PRICE_X64).contains(&sqrt_price_x64),
+        CustomError::InvalidSqrtPrice
+    );
+
+    let sqrt_price_fixed = u128::try_from((U256::from(sqrt_price_x64) * U256::from(FIXED_ONE)) >> 64)
+        .map_err(|_| error!(CustomError::CalculationOverflow))?;
+    let estimate = (2 * ln_fixed(sqrt_price_fixed)?).div_euclid(LN_1_0001);
+    let mut tick = estimate.clamp(MIN_TICK as i128, MAX_TICK as i128) as i32;
+
+    while tick > MIN_TICK && get_sqrt_price_at_tick(tick)? > sqrt_price_x64 {
+        tick -= 1;
+    }
+    while tick < MAX_TICK && get_sqrt_price_at_tick(tick + 1)? <= sqrt_price_x64 {
+        tick += 1;
+    }
+
+    Ok(tick)
+}
+
+fn sort_sqrt_prices(sqrt_price_0: u128, sqrt_price_1: u128) -> (u128, u128) {
+    if sqrt_price_0 > sqrt_price_1 {
+        (sqrt_price_1, sqrt_price_0)
+    } else {
+        (sqrt_price_0, sqrt_price_1)
+    }
+}
+
+/// Token A backing `liquidity` between two sqrt prices
+/// Formula: L * (sqrt_upper - sqrt_lower) / (sqrt_upper * sqrt_lower)
+pub fn get_amount_a_delta(
+    sqrt_price_0: u128,
+    sqrt_price_1: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<u128> {
+    let (sqrt_lower, sqrt_upper) = sort_sqrt_prices(sqrt_price_0, sqrt_price_1);
+    require!(sqrt_lower > 0, CustomError::InvalidSqrtPrice);
+
+    let product = U256::from(liquidity) * U256::from(sqrt_upper - sqrt_lower);
+    require!(product.bits() <= 192, CustomError::CalculationOverflow);
+    let numerator = product << 64;
+    let denominator = U256::from(sqrt_upper) * U256::from(sqrt_lower);
+
+    let mut amount = numerator / denominator;
+    if round_up && !(numerator % denominator).is_zero() {
+        amount += U256::one();
+    }
+    u128::try_from(amount).map_err(|_| error!(CustomError::CalculationOverflow))
+}
+
+/// Token B backing `liquidity` between two sqrt prices
+/// Formula: L * (sqrt_upper - sqrt_lower)
+pub fn get_amount_b_delta(
+    sqrt_price_0: u128,
+    sqrt_price_1: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<u128> {
+    let (sqrt_lower, sqrt_upper) = sort_sqrt_prices(sqrt_price_0, sqrt_price_1);
+
+    let product = U256::from(liquidity) * U256::from(sqrt_upper - sqrt_lower);
+    let mut amount = product >> 64;
+    if round_up && !(product & U256::from(u64::MAX)).is_zero() {
+        amount += U256::one();
+    }
+    u128::try_from(amount).map_err(|_| error!(CustomError::CalculationOverflow))
+}
+
+/// Sqrt price after adding `amount` of token A (price moves down), rounded up
+/// Formula: L * sqrt / (L + amount * sqrt)
+fn get_next_sqrt_price_from_amount_a(sqrt_price: u128, liquidity: u128, amount: u64) -> Result<u128> {
+    if amount == 0 {
+        return Ok(sqrt_price);
+    }
+
+    let product = U256::from(liquidity) * U256::from(sqrt_price);
+    require!(product.bits() <= 192, CustomError::CalculationOverflow);
+    let numerator = product << 64;
+    let denominator = (U256::from(liquidity) << 64) + U256::from(amount) * U256::from(sqrt_price);
+
+    let mut next = numerator / denominator;
+    if !(numerator % denominator).is_zero() {
+        next += U256::one();
+    }
+    u128::try_from(next).map_err(|_| error!(CustomError::CalculationOverflow))
+}
+
+/// Sqrt price after adding `amount` of token B (price moves up), rounded down
+/// Formula: sqrt + amount / L
+fn get_next_sqrt_price_from_amount_b(sqrt_price: u128, liquidity: u128, amount: u64) -> Result<u128> {
+    let delta = ((amount as u128) << 64) / liquidity;
+    sqrt_price
+        .checked_add(delta)
+        .ok_or(error!(CustomError::CalculationOverflow))
+}
+
+/// Result of one swap step within a single tick range
+pub struct SwapStep {
+    pub sqrt_price_next: u128,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+/// Swap as much of `amount_remaining` as fits before the price reaches
+/// `sqrt_price_target`, with the pool fee taken from the input (exact input)
+pub fn compute_swap_step(
+    sqrt_price_current: u128,
+    sqrt_price_target: u128,
+    liquidity: u128,
+    amount_remaining: u64,
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> Result<SwapStep> {
+    let a_to_b = sqrt_price_current >= sqrt_price_target;
+
+    let amount_remaining_less_fee = (amount_remaining as u128)
+        .checked_mul((fee_denominator - fee_numerator) as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))?
+        / fee_denominator as u128;
+
+    let amount_in_to_target = if a_to_b {
+        get_amount_a_delta(sqrt_price_target, sqrt_price_current, liquidity, true)?
+    } else {
+        get_amount_b_delta(sqrt_price_current, sqrt_price_target, liquidity, true)?
+    };
+
+    let reaches_target = amount_remaining_less_fee >= amount_in_to_target;
+    let sqrt_price_next = if reaches_target {
+        sqrt_price_target
+    } else if a_to_b {
+        get_next_sqrt_price_from_amount_a(sqrt_price_current, liquidity, amount_remaining_less_fee as u64)?
+    } else {
+        get_next_sqrt_price_from_amount_b(sqrt_price_current, liquidity, amount_remaining_less_fee as u64)?
+    };
+
+    let amount_in = if reaches_target {
+        amount_in_to_target
+    } else if a_to_b {
+        get_amount_a_delta(sqrt_price_next, sqrt_price_current, liquidity, true)?
+    } else {
+        get_amount_b_delta(sqrt_price_current, sqrt_price_next, liquidity, true)?
+    };
+    let amount_out = if a_to_b {
+        get_amount_b_delta(sqrt_price_next, sqrt_price_current, liquidity, false)?
+    } else {
+        get_amount_a_delta(sqrt_price_current, sqrt_price_next, liquidity, false)?
+    };
+
+    // amount_in <= amount_remaining_less_fee here, so both fit in u64
+    let amount_in = amount_in as u64;
+    let fee_amount = if reaches_target {
+        (amount_in as u128 * fee_numerator as u128).div_ceil((fee_denominator - fee_numerator) as u128) as u64
+    } else {
+        // The target was not reached, so the rest of the input is fee
+        amount_remaining - amount_in
+    };
+
+    Ok(SwapStep {
+        sqrt_price_next,
+        amount_in,
+        amount_out: u64::try_from(amount_out).map_err(|_| error!(CustomError::CalculationOverflow))?,
+        fee_amount,
+    })
+}
+
+/// Token amounts backing `liquidity` over [tick_lower, tick_upper) at the current price
+/// Only token A below the range, only token B above it, both inside it
+pub fn get_amounts_for_liquidity(
+    sqrt_price_current: u128,
+    tick_current: i32,
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<(u64, u64)> {
+    let sqrt_price_lower = get_sqrt_price_at_tick(tick_lower)?;
+    let sqrt_price_upper = get_sqrt_price_at_tick(tick_upper)?;
+
+    let (amount_a, amount_b) = if tick_current < tick_lower {
+        (get_amount_a_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up)?, 0)
+    } else if tick_current < tick_upper {
+        (
+            get_amount_a_delta(sqrt_price_current, sqrt_price_upper, liquidity, round_up)?,
+            get_amount_b_delta(sqrt_price_lower, sqrt_price_current, liquidity, round_up)?,
+        )
+    } else {
+        (0, get_amount_b_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up)?)
+    };
+
+    Ok((
+        u64::try_from(amount_a).map_err(|_| error!(CustomError::CalculationOverflow))?,
+        u64::try_from(amount_b).map_err(|_| error!(CustomError::CalculationOverflow))?,
+    ))
+}
+
+/// Fee growth per unit of liquidity inside [tick_lower, tick_upper), per token
+/// Global growth minus the growth below the lower tick and above the upper tick;
+/// all values wrap, only differences between snapshots are meaningful
+#[allow(clippy::too_many_arguments)]
+pub fn get_fee_growth_inside(
+    tick_lower_index: i32,
+    tick_lower: &Tick,
+    tick_upper_index: i32,
+    tick_upper: &Tick,
+    tick_current: i32,
+    fee_growth_global_a: u128,
+    fee_growth_global_b: u128,
+) -> (u128, u128) {
+    let (below_a, below_b) = if tick_current >= tick_lower_index {
+        (tick_lower.fee_growth_outside_a, tick_lower.fee_growth_outside_b)
+    } else {
+        (
+            fee_growth_global_a.wrapping_sub(tick_lower.fee_growth_outside_a),
+            fee_growth_global_b.wrapping_sub(tick_lower.fee_growth_outside_b),
+        )
+    };
+    let (above_a, above_b) = if tick_current < tick_upper_index {
+        (tick_upper.fee_growth_outside_a, tick_upper.fee_growth_outside_b)
+    } else {
+        (
+            fee_growth_global_a.wrapping_sub(tick_upper.fee_growth_outside_a),
+            fee_growth_global_b.wrapping_sub(tick_upper.fee_growth_outside_b),
+        )
+    };
+
+    (
+        fee_growth_global_a.wrapping_sub(below_a).wrapping_sub(above_a),
+        fee_growth_global_b.wrapping_sub(below_b).wrapping_sub(above_b),
+    )
+}
+
+/// Fees earned by `liquidity` since the last fee growth snapshot
+/// Formula: liquidity * (fee_growth_inside - fee_growth_inside_last) / 2^64
+pub fn calculate_fees_earned(
+    liquidity: u128,
+    fee_growth_inside: u128,
+    fee_growth_inside_last: u128,
+) -> Result<u64> {
+    let growth = fee_growth_inside.wrapping_sub(fee_growth_inside_last);
+    let fees = (U256::from(liquidity) * U256::from(growth)) >> 64;
+    u64::try_from(fees).map_err(|_| error!(CustomError::CalculationOverflow))
+}
+
+/// Fee growth per unit of liquidity for `fee_amount` of LP fees, Q64.64
+pub fn calculate_fee_growth(fee_amount: u64, liquidity: u128) -> u128 {
+    if liquidity == 0 {
+        return 0;
+    }
+    ((fee_amount as u128) << 64) / liquidity
+}
+
+/// Apply a signed liquidity change
+pub fn add_liquidity_delta(liquidity: u128, delta: i128) -> Result<u128> {
+    if delta >= 0 {
+        liquidity.checked_add(delta as u128)
+    } else {
+        liquidity.checked_sub(delta.unsigned_abs())
+    }
+    .ok_or(error!(CustomError::CalculationOverflow))
+}
+
+/// Spot price of token_a in token_b from a Q64.64 sqrt price, 6 decimals
+/// Formula: price = sqrt_price^2 / 2^128
+pub fn calculate_concentrated_pool_price(sqrt_price_x64: u128) -> Result<u64> {
+    let sqrt_price = U256::from(sqrt_price_x64);
+    let price = (sqrt_price * sqrt_price * U256::from(1_000_000u64)) >> 128;
+    u64::try_from(price).map_err(|_| error!(CustomError::CalculationOverflow))
+}
+
+// ============================================================================
+// DYNAMIC FEES (Module 2.14)
+// ============================================================================
+//
+// Pools in dynamic fee mode charge between min_fee_bps and max_fee_bps
+// depending on recent volatility: each swap adds its price movement (in bps of
+// the pre-swap spot price) to an accumulator that decays linearly to zero over
+// VOLATILITY_DECAY_PERIOD. A swap is charged on the accumulator as it stood
+// before the swap, so its own impact only raises the fee for later swaps.
+
+/// Accumulator after linear decay since the last swap
+pub fn decay_volatility_accumulator(accumulator: u64, last_swap_timestamp: i64, now: i64) -> u64 {
+    let elapsed = now.saturating_sub(last_swap_timestamp).max(0);
+    if elapsed >= VOLATILITY_DECAY_PERIOD {
+        return 0;
+    }
+    ((accumulator as u128 * (VOLATILITY_DECAY_PERIOD - elapsed) as u128)
+        / VOLATILITY_DECAY_PERIOD as u128) as u64
+}
+
+/// Dynamic fee in bps for a volatility accumulator
+/// Formula: min_fee + (max_fee - min_fee) * min(accumulator, saturation) / saturation
+pub fn calculate_dynamic_fee_bps(min_fee_bps: u16, max_fee_bps: u16, accumulator: u64) -> u16 {
+    let volatility = accumulator.min(VOLATILITY_SATURATION_BPS);
+    let fee_range = max_fee_bps.saturating_sub(min_fee_bps) as u64;
+    min_fee_bps + (fee_range * volatility / VOLATILITY_SATURATION_BPS) as u16
+}
+
+/// Swap fee numerator in effect for the pool at `now`, over FEE_DENOMINATOR_BPS
+/// The pool's fee tier unless dynamic fee mode is enabled
+pub fn calculate_effective_fee_numerator(pool: &LiquidityPool, now: i64) -> u64 {
+    if !pool.dynamic_fee_enabled {
+        return pool.fee_numerator;
+    }
+    let accumulator = decay_volatility_accumulator(
+        pool.volatility_accumulator,
+        pool.last_swap_timestamp,
+        now,
+    );
+    calculate_dynamic_fee_bps(pool.min_fee_bps, pool.max_fee_bps, accumulator) as u64
+}
+
+/// Price movement between two spot prices in bps of the earlier one
+/// Prices too small to register at 6 decimals count as no movement
+pub fn calculate_price_movement_bps(price_before: u64, price_after: u64) -> u64 {
+    if price_before == 0 {
+        return 0;
+    }
+    let movement = price_before.abs_diff(price_after) as u128 * FEE_DENOMINATOR_BPS as u128
+        / price_before as u128;
+    movement.min(MAX_VOLATILITY_ACCUMULATOR as u128) as u64
+}
+
+/// Fold a completed swap's price movement into the pool's volatility accumulator
+/// `price_before` is calculate_spot_price from before the reserves changed;
+/// pools outside dynamic fee mode are left untouched
+pub fn update_volatility_accumulator(pool: &mut LiquidityPool, price_before: u64, now: i64) -> Result<()> {
+    if !pool.dynamic_fee_enabled {
+        return Ok(());
+    }
+    let price_after = calculate_spot_price(pool)?;
+    let decayed = decay_volatility_accumulator(
+        pool.volatility_accumulator,
+        pool.last_swap_timestamp,
+        now,
+    );
+    pool.volatility_accumulator = decayed
+        .saturating_add(calculate_price_movement_bps(price_before, price_after))
+        .min(MAX_VOLATILITY_ACCUMULATOR);
+    pool.last_swap_timestamp = now;
+    Ok(())
+}
+
+// ============================================================================
+// POOL CURVE DISPATCH (Module 2.11, 2.12, 2.13)
+// ============================================================================
+
+/// Swap output for the pool's curve (Module 3.1 / 2.11 / 2.12)
+/// Reserves (and weights) are taken from the pool in the swap's direction;
+/// `fee_numerator` comes from calculate_effective_fee_numerator (Module 2.14)
+pub fn calculate_swap_output(
+    pool: &LiquidityPool,
+    input_amount: u64,
+    is_a_to_b: bool,
+    fee_numerator: u64,
+) -> Result<u64> {
+    let (input_reserve, output_reserve) = if is_a_to_b {
+        (pool.reserve_a, pool.reserve_b)
+    } else {
+        (pool.reserve_b, pool.reserve_a)
+    };
+
+    match pool.pool_type {
+        PoolType::ConstantProduct => calculate_output_amount(
+            input_amount,
+            input_reserve,
+            output_reserve,
+            fee_numerator,
+            pool.fee_denominator,
+        ),
+        PoolType::StableSwap => calculate_stable_swap_output(
+            input_amount,
+            input_reserve,
+            output_reserve,
+            pool.amp_coefficient,
+            fee_numerator,
+            pool.fee_denominator,
+        ),
+        PoolType::Weighted => {
+            let (input_weight_bps, output_weight_bps) = if is_a_to_b {
+                (pool.weight_a_bps, pool.weight_b_bps)
+            } else {
+                (pool.weight_b_bps, pool.weight_a_bps)
+            };
+            calculate_weighted_swap_output(
+                input_amount,
+                input_reserve,
+                output_reserve,
+                input_weight_bps,
+                output_weight_bps,
+                fee_numerator,
+                pool.fee_denominator,
+            )
+        }
+        // Concentrated pools swap across ticks in swap_concentrated
+        PoolType::Concentrated => err!(CustomError::UnsupportedPoolType),
+    }
+}
+
+/// Spot price of token_a in token_b for the pool's curve, 6 decimals (Module 3.5 / 2.12 / 2.13)
+/// Weighted pools scale the reserve ratio by the weights and concentrated pools
+/// quote their sqrt price; the other curves quote the plain reserve ratio
+pub fn calculate_spot_price(pool: &LiquidityPool) -> Result<u64> {
+    match pool.pool_type {
+        PoolType::ConstantProduct | PoolType::StableSwap => {
+            calculate_pool_price(pool.reserve_a, pool.reserve_b)
+        }
+        PoolType::Weighted => calculate_weighted_pool_price(
+            pool.reserve_a,
+            pool.reserve_b,
+            pool.weight_a_bps,
+            pool.weight_b_bps,
+        ),
+        PoolType::Concentrated => calculate_concentrated_pool_price(pool.sqrt_price_x64),
+    }
+}
+
+/// LP tokens for a pool's first deposit (Module 2.2 / 2.11 / 2.12)
+/// Constant product: sqrt(a * b); StableSwap: D(a, b), as in Curve;
+/// Weighted: a^wa * b^wb, as in Balancer
+pub fn calculate_initial_lp_tokens(
+    pool: &LiquidityPool,
+    amount_a: u64,
+    amount_b: u64,
+) -> Result<u64> {
+    match pool.pool_type {
+        PoolType::ConstantProduct => calculate_lp_tokens(amount_a, amount_b),
+        PoolType::StableSwap => u64::try_from(get_d(amount_a, amount_b, pool.amp_coefficient)?)
+            .map_err(|_| error!(CustomError::CalculationOverflow)),
+        PoolType::Weighted => calculate_weighted_invariant(
+            amount_a,
+            amount_b,
+            pool.weight_a_bps,
+            pool.weight_b_bps,
+        ),
+        // Concentrated pools track liquidity per position instead of minting LP tokens
+        PoolType::Concentrated => err!(CustomError::UnsupportedPoolType),
+    }
+}
+
+/// LP tokens for a deposit into an existing pool (Module 2.3 / 2.11 / 2.12)
+/// Constant product and weighted pools use the lesser of the two deposit
+/// ratios, since a proportional deposit scales the weighted invariant by the
+/// same ratio; StableSwap mints in proportion to the growth of D:
+/// supply * (D1 - D0) / D0
+pub fn calculate_deposit_lp_tokens(
+    pool: &LiquidityPool,
+    amount_a: u64,
+    amount_b: u64,
+) -> Result<u64> {
+    match pool.pool_type {
+        PoolType::ConstantProduct | PoolType::Weighted => calculate_lp_tokens_for_add_liquidity(
+            amount_a,
+            amount_b,
+            pool.reserve_a,
+            pool.reserve_b,
+            pool.total_lp_supply,
+        ),
+        PoolType::StableSwap => {
+            let d0 = get_d(pool.reserve_a, pool.reserve_b, pool.amp_coefficient)?;
+            require!(d0 > 0, CustomError::InsufficientLiquidity);
+            let new_reserve_a = pool
+                .reserve_a
+                .checked_add(amount_a)
+                .ok_or(error!(CustomError::CalculationOverflow))?;
+            let new_reserve_b = pool
+                .reserve_b
+                .checked_add(amount_b)
+                .ok_or(error!(CustomError::CalculationOverflow))?;
+            let d1 = get_d(new_reserve_a, new_reserve_b, pool.amp_coefficient)?;
+
+            let lp_tokens = (U256::from(pool.total_lp_supply) * U256::from(d1.saturating_sub(d0)))
+                / U256::from(d0);
+            u64::try_from(lp_tokens).map_err(|_| error!(CustomError::CalculationOverflow))
+        }
+        PoolType::Concentrated => err!(CustomError::UnsupportedPoolType),
+    }
+}
+
+/// Pool invariant for the given balances, rounded down or up (Module 2.4)
+/// Every curve's invariant is homogeneous of degree one in the balances, so LP
+/// supply scales with it: sqrt(a * b), StableSwap D, or weighted a^wa * b^wb
+pub fn calculate_pool_invariant(
+    pool: &LiquidityPool,
+    reserve_a: u64,
+    reserve_b: u64,
+    round_up: bool,
+) -> Result<u128> {
+    match pool.pool_type {
+        PoolType::ConstantProduct => {
+            let product = reserve_a as u128 * reserve_b as u128;
+            let root = isqrt(product);
+            Ok(if round_up && root * root < product { root + 1 } else { root })
+        }
+        // get_d stops once successive iterates are within 1 of each other
+        PoolType::StableSwap => {
+            let d = get_d(reserve_a, reserve_b, pool.amp_coefficient)?;
+            Ok(if round_up { d + 1 } else { d.saturating_sub(1) })
+        }
+        PoolType::Weighted => {
+            let invariant = if round_up {
+                calculate_weighted_invariant_up(
+                    reserve_a,
+                    reserve_b,
+                    pool.weight_a_bps,
+                    pool.weight_b_bps,
+                )?
+            } else {
+                calculate_weighted_invariant(
+                    reserve_a,
+                    reserve_b,
+                    pool.weight_a_bps,
+                    pool.weight_b_bps,
+                )?
+            };
+            Ok(invariant as u128)
+        }
+        PoolType::Concentrated => err!(CustomError::UnsupportedPoolType),
+    }
+}
+
+/// Fee on one side of an imbalanced withdrawal, rounded up
+/// Charged on the distance between the side's new balance and where a proportional
+/// withdrawal of the same invariant would have left it. Both sides deviate by
+/// roughly the amount an equivalent swap would move, so each is charged half the
+/// swap fee, as in Curve's remove_liquidity_imbalance
+fn calculate_imbalance_fee(
+    old_reserve: u64,
+    new_reserve: u64,
+    d0: u128,
+    d1: u128,
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> Result<u64> {
+    let ideal_reserve = U256::from(d1) * U256::from(old_reserve) / U256::from(d0);
+    let new_reserve = U256::from(new_reserve);
+    let difference = if ideal_reserve > new_reserve {
+        ideal_reserve - new_reserve
+    } else {
+        new_reserve - ideal_reserve
+    };
+
+    let denominator = U256::from(fee_denominator) * U256::from(2u64);
+    let fee = (difference * U256::from(fee_numerator) + denominator - U256::one()) / denominator;
+    u64::try_from(fee).map_err(|_| error!(CustomError::CalculationOverflow))
+}
+
+/// LP tokens to burn for withdrawing exact amounts of both tokens (Module 2.4)
+/// Returns (lp_tokens_to_burn, fee_a, fee_b). The fee on each side is charged on
+/// the imbalanced portion of the withdrawal and stays in the pool; the burn is
+/// supply * (D0 - D2) / D0 where D2 is the invariant after amounts and fees leave.
+/// D0 rounds up, D2 rounds down and the burn rounds up, all against the withdrawer
+pub fn calculate_exact_out_lp_tokens(
+    pool: &LiquidityPool,
+    amount_a: u64,
+    amount_b: u64,
+    fee_numerator: u64,
+) -> Result<(u64, u64, u64)> {
+    require!(amount_a > 0 || amount_b > 0, CustomError::InvalidAmount);
+    require!(pool.total_lp_supply > 0, CustomError::InsufficientLiquidity);
+    // Every curve needs both balances to stay positive
+    require!(
+        amount_a < pool.reserve_a && amount_b < pool.reserve_b,
+        CustomError::InsufficientPoolReserves
+    );
+
+    let new_reserve_a = pool.reserve_a - amount_a;
+    let new_reserve_b = pool.reserve_b - amount_b;
+
+    let d0 = calculate_pool_invariant(pool, pool.reserve_a, pool.reserve_b, true)?;
+    let d1 = calculate_pool_invariant(pool, new_reserve_a, new_reserve_b, false)?;
+
+    let fee_a = calculate_imbalance_fee(
+        pool.reserve_a,
+        new_reserve_a,
+        d0,
+        d1,
+        fee_numerator,
+        pool.fee_denominator,
+    )?;
+    let fee_b = calculate_imbalance_fee(
+        pool.reserve_b,
+        new_reserve_b,
+        d0,
+        d1,
+        fee_numerator,
+        pool.fee_denominator,
+    )?;
+
+    let d2 = calculate_pool_invariant(
+        pool,
+        new_reserve_a
+            .checked_sub(fee_a)
+            .ok_or(error!(CustomError::InsufficientPoolReserves))?,
+        new_reserve_b
+            .checked_sub(fee_b)
+            .ok_or(error!(CustomError::InsufficientPoolReserves))?,
+        false,
+    )?;
+
+    let burned_invariant = U256::from(pool.total_lp_supply) * U256::from(d0.saturating_sub(d2));
+    let lp_tokens = (burned_invariant + U256::from(d0) - U256::one()) / U256::from(d0);
+    let lp_tokens = u64::try_from(lp_tokens).map_err(|_| error!(CustomError::CalculationOverflow))?;
+
+    Ok((lp_tokens, fee_a, fee_b))
+}
+
+// ============================================================================
+// POSITION EARNINGS (Module 2.15)
+// ============================================================================
+//
+// Outside concentrated pools, fee_growth_global_a/b count LP fees per LP token
+// (Q64.64). A position checkpoints the growth whenever its LP balance changes and
+// settles what its tokens earned since into fees_earned_a/b. The fees never leave
+// the reserves; the counters only attribute part of the position's share to fees.
+
+/// Credit the LP share of a swap fee to every LP token in the pool
+/// Call with the LP supply that owns the fee: before minting, after burning
+pub fn accrue_lp_fee_growth(pool: &mut LiquidityPool, lp_fee: u64, is_token_a: bool) {
+    let growth = calculate_fee_growth(lp_fee, pool.total_lp_supply as u128);
+    if is_token_a {
+        pool.fee_growth_global_a = pool.fee_growth_global_a.wrapping_add(growth);
+    } else {
+        pool.fee_growth_global_b = pool.fee_growth_global_b.wrapping_add(growth);
+    }
+}
+
+/// LP fees earned by a position since its last checkpoint: (fees_a, fees_b)
+pub fn calculate_pending_position_fees(
+    position: &UserLiquidityPosition,
+    pool: &LiquidityPool,
+) -> Result<(u64, u64)> {
+    let fees_a = calculate_fees_earned(
+        position.lp_tokens as u128,
+        pool.fee_growth_global_a,
+        position.fee_growth_checkpoint_a,
+    )?;
+    let fees_b = calculate_fees_earned(
+        position.lp_tokens as u128,
+        pool.fee_growth_global_b,
+        position.fee_growth_checkpoint_b,
+    )?;
+    Ok((fees_a, fees_b))
+}
+
+/// Move pending fees into the position's totals and checkpoint the pool's fee growth
+/// Call before the position's LP balance changes
+pub fn settle_position_fees(
+    position: &mut UserLiquidityPosition,
+    pool: &LiquidityPool,
+) -> Result<()> {
+    let (fees_a, fees_b) = calculate_pending_position_fees(position, pool)?;
+    position.fees_earned_a = position
+        .fees_earned_a
+        .checked_add(fees_a)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    position.fees_earned_b = position
+        .fees_earned_b
+        .checked_add(fees_b)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    position.fee_growth_checkpoint_a = pool.fee_growth_global_a;
+    position.fee_growth_checkpoint_b = pool.fee_growth_global_b;
+    Ok(())
+}
+
+/// Record a deposit on a position: LP fees earned so far are settled at the old
+/// LP balance first, so the new tokens only earn fees accrued from now on
+pub fn record_position_deposit(
+    position: &mut UserLiquidityPosition,
+    pool: &LiquidityPool,
+    lp_minted: u64,
+    amount_a: u64,
+    amount_b: u64,
+) -> Result<()> {
+    settle_position_fees(position, pool)?;
+    position.deposited_a = position
+        .deposited_a
+        .checked_add(amount_a)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    position.deposited_b = position
+        .deposited_b
+        .checked_add(amount_b)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    position.lp_tokens = position
+        .lp_tokens
+        .checked_add(lp_minted)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    Ok(())
+}
+
+/// Record a withdrawal on a position, settling LP fees at the old LP balance first
+/// LP tokens are transferable, so the wallet balance is the authority on what can
+/// be burned; the position counter only tracks this owner's share and saturates
+pub fn record_position_withdrawal(
+    position: &mut UserLiquidityPosition,
+    pool: &LiquidityPool,
+    lp_burned: u64,
+    amount_a: u64,
+    amount_b: u64,
+) -> Result<()> {
+    settle_position_fees(position, pool)?;
+    position.lp_tokens = position.lp_tokens.saturating_sub(lp_burned);
+    position.withdrawn_a = position
+        .withdrawn_a
+        .checked_add(amount_a)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    position.withdrawn_b = position
+        .withdrawn_b
+        .checked_add(amount_b)
+        .ok_or(error!(CustomError::CalculationOverflow))?;
+    Ok(())
+}
+
+/// Value of token amounts in token B at a 6-decimal spot price of A in B
+fn value_in_token_b(amount_a: i128, amount_b: i128, price: u64) -> i128 {
+    amount_a * price as i128 / 1_000_000 + amount_b
+}
+
+/// Impermanent loss of a position in token B at the current spot price
+/// Formula: hold_value - (position_value - fees_value), where hold_value is what the
+/// net deposits (deposited - withdrawn) would be worth today. Positive means the
+/// position, before fees, is worth less than holding the tokens would have been.
+pub fn calculate_impermanent_loss(
+    net_deposit_a: i128,
+    net_deposit_b: i128,
+    current_amount_a: u64,
+    current_amount_b: u64,
+    fees_a: u64,
+    fees_b: u64,
+    price: u64,
+) -> Result<i64> {
+    let hold_value = value_in_token_b(net_deposit_a, net_deposit_b, price);
+    let position_value = value_in_token_b(current_amount_a as i128, current_amount_b as i128, price);
+    let fees_value = value_in_token_b(fees_a as i128, fees_b as i128, price);
+
+    i64::try_from(hold_value - (position_value - fees_value))
+        .map_err(|_| error!(CustomError::CalculationOverflow))
+}
+
+// ============================================================================
+// PYTH ORACLE PRICE FUNCTIONS (Module 3.5)
+// ============================================================================
+
+/// Get price from Pyth Oracle with 6 decimal precision
+/// 
+/// Note: This is a placeholder for Pyth integration.
+/// Currently returns 0 - will be fully implemented with Pyth SDK in future updates.
+/// 
+/// For now, use calculate_pool_price() for local AMM price calculations.
+/// 
+/// Parameters:
+/// - price_account: Pyth price account info
+/// 
+/// Returns: Price with 6 decimal precision
+#[allow(unused)]
+pub fn get_pyth_price(price_account: &AccountInfo) -> Result<u64> {
+    // TODO: Implement Pyth Oracle integration
+    // For now, return 0 as placeholder
+    // This will be replaced with actual Pyth price fetching in future updates
+    Ok(0)
+}
+
+/// Calculate local pool price with 6 decimal precision
+/// 
+/// Formula: price = (reserve_b * 1_000_000) / reserve_a
+/// Returns price of token_a in terms of token_b
+pub fn calculate_pool_price(reserve_a: u64, reserve_b: u64) -> Result<u64> {
+    require!(reserve_a > 0, CustomError::InsufficientLiquidity);
+    
+    let price = (reserve_b as u128)
+        .checked_mul(1_000_000)
+        .ok_or(error!(CustomError::CalculationOverflow))?
+        .checked_div(reserve_a as u128)
+        .ok_or(error!(CustomError::CalculationOverflow))? as u64;
+    
+    Ok(price)
+}
+
+/// Compare pool price against target price for limit order execution
+/// 
+/// For sell orders (selling token_a for token_b):
+/// - Condition: pool_price >= target_price (want more token_b per token_a)
+/// 
+/// Parameters:
+/// - pool_price: Current pool price (reserve_b / reserve_a) with 6 decimals
+/// - target_price: Target price with 6 decimals
+/// - is_sell: true if selling token_a, false if selling token_b
+/// 
+/// Returns: true if price condition is met
+pub fn check_price_condition(
+    pool_price: u64,
+    target_price: u64,
+    is_sell: bool,
+) -> bool {
+    if is_sell {
+        // For sell: we want pool_price >= target_price
+        // (want to receive more per unit)
+        pool_price >= target_price
+    } else {
+        // For buy: we want pool_price <= target_price
+        // (want to pay less per unit)
+        pool_price <= target_price
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{MAX_AMP_COEFFICIENT, MAX_FLASH_LOAN_FEE_BPS, MIN_WEIGHT_BPS};
+
+    /// Pool account with every field zeroed, as `init` leaves it
+    fn zeroed_pool() -> LiquidityPool {
+        LiquidityPool::deserialize(&mut &[0u8; LiquidityPool::SIZE][..]).unwrap()
+    }
+
+    // ------------------------------------------------------------------------
+    // Module 4.7: LP staking accumulator
+    // ------------------------------------------------------------------------
+
+    const RPS: u64 = 1_585;
+
+    /// RUSH config emitting RPS per pool from t = 0
+    fn rush_config() -> RushConfig {
+        let mut config = RushConfig::deserialize(&mut &[0u8; RushConfig::SIZE][..]).unwrap();
+        config.rewards_per_second = RPS;
+        config
+    }
+
+    #[derive(Default)]
+    struct Staker {
+        staked_lp: u64,
+        reward_debt: u128,
+        claimed: u64,
+    }
+
+    /// Same bookkeeping as stake_lp / unstake_lp / claim_rush_rewards
+    fn settle_and_restake(
+        config: &RushConfig,
+        pool: &mut LiquidityPool,
+        staker: &mut Staker,
+        delta: i64,
+        now: i64,
+    ) {
+        update_rush_accumulator(pool, current_rush_reward_index(config, now).unwrap(), now)
+            .unwrap();
+        staker.claimed += calculate_staking_rewards(
+            staker.staked_lp,
+            pool.acc_rush_per_share,
+            staker.reward_debt,
+        )
+        .unwrap();
+        staker.staked_lp = (staker.staked_lp as i64 + delta) as u64;
+        pool.total_staked_lp = (pool.total_staked_lp as i64 + delta) as u64;
+        staker.reward_debt =
+            calculate_reward_debt(staker.staked_lp, pool.acc_rush_per_share).unwrap();
+    }
+
+    #[test]
+    fn staking_rewards_follow_stake_changes() {
+        let config = rush_config();
+        let mut pool = zeroed_pool();
+        let (mut alice, mut bob) = (Staker::default(), Staker::default());
+
+        settle_and_restake(&config, &mut pool, &mut alice, 1_000, 100);
+        settle_and_restake(&config, &mut pool, &mut bob, 1_000, 200); // alice alone for 100s
+        settle_and_restake(&config, &mut pool, &mut alice, -1_000, 300); // then 50/50 for 100s
+        settle_and_restake(&config, &mut pool, &mut alice, 0, 10_100);
+        settle_and_restake(&config, &mut pool, &mut bob, 0, 10_100); // bob alone for 9_800s
+
+        assert_eq!(alice.claimed, RPS * 150);
+        assert_eq!(bob.claimed, RPS * 9_850);
+        // Leaving does not let the remaining staker claim the leaver's share twice
+        assert_eq!(alice.claimed + bob.claimed, RPS * 10_000);
+    }
+
+    #[test]
+    fn late_staker_does_not_dilute_past_accrual() {
+        let config = rush_config();
+        let mut pool = zeroed_pool();
+        let (mut early, mut late) = (Staker::default(), Staker::default());
+
+        settle_and_restake(&config, &mut pool, &mut early, 1_000, 1);
+        settle_and_restake(&config, &mut pool, &mut late, 9_000, 1_001);
+        assert_eq!(
+            calculate_staking_rewards(late.staked_lp, pool.acc_rush_per_share, late.reward_debt)
+                .unwrap(),
+            0,
+            "A new stake starts with no claim on earlier emissions"
+        );
+
+        settle_and_restake(&config, &mut pool, &mut early, 0, 2_001);
+        settle_and_restake(&config, &mut pool, &mut late, 0, 2_001);
+        assert_eq!(early.claimed, RPS * 1_000 + RPS * 100);
+        assert_eq!(late.claimed, RPS * 900);
+    }
+
+    #[test]
+    fn nothing_accrues_while_unstaked() {
+        assert_eq!(
+            calculate_rush_per_share_increment(RPS as u128 * 86_400, 0).unwrap(),
+            0
+        );
+
+        // The first stake only records the index
+        let config = rush_config();
+        let mut pool = zeroed_pool();
+        update_rush_accumulator(
+            &mut pool,
+            current_rush_reward_index(&config, 1_000).unwrap(),
+            1_000,
+        )
+        .unwrap();
+        assert_eq!(pool.acc_rush_per_share, 0);
+        assert_eq!(pool.rush_index_snapshot, RPS as u128 * 1_000);
+        assert_eq!(pool.last_reward_timestamp, 1_000);
+
+        // Time with an empty pool is not paid out to the next staker
+        update_rush_accumulator(
+            &mut pool,
+            current_rush_reward_index(&config, 5_000).unwrap(),
+            5_000,
+        )
+        .unwrap();
+        assert_eq!(pool.acc_rush_per_share, 0);
+    }
+
+    #[test]
+    fn rate_change_does_not_reprice_earned_rewards() {
+        let mut config = rush_config();
+        let mut pool = zeroed_pool();
+        let mut staker = Staker::default();
+        settle_and_restake(&config, &mut pool, &mut staker, 1_000, 0);
+
+        // Pending rewards for [0, T] at the old rate
+        let pending = |config: &RushConfig, pool: &LiquidityPool, staker: &Staker, now: i64| {
+            let mut pool = pool.clone();
+            update_rush_accumulator(
+                &mut pool,
+                current_rush_reward_index(config, now).unwrap(),
+                now,
+            )
+            .unwrap();
+            calculate_staking_rewards(
+                staker.staked_lp,
+                pool.acc_rush_per_share,
+                staker.reward_debt,
+            )
+            .unwrap()
+        };
+        const T: i64 = 1_000;
+        let earned_before = pending(&config, &pool, &staker, T);
+        assert_eq!(earned_before, RPS * T as u64);
+
+        // No pool is touched when the rate changes
+        advance_rush_reward_index(&mut config, T).unwrap();
+        config.rewards_per_second = RPS * 10;
+        assert_eq!(pending(&config, &pool, &staker, T), earned_before);
+
+        settle_and_restake(&config, &mut pool, &mut staker, 0, 2 * T);
+        assert_eq!(staker.claimed, earned_before + RPS * 10 * T as u64);
+    }
+
+    #[test]
+    fn paused_seconds_emit_nothing() {
+        let mut config = rush_config();
+        let mut pool = zeroed_pool();
+        let mut staker = Staker::default();
+        settle_and_restake(&config, &mut pool, &mut staker, 1_000, 0);
+
+        advance_rush_reward_index(&mut config, 100).unwrap();
+        config.is_paused = true;
+        assert_eq!(
+            current_rush_reward_index(&config, 500).unwrap(),
+            RPS as u128 * 100
+        );
+
+        advance_rush_reward_index(&mut config, 500).unwrap();
+        config.is_paused = false;
+        settle_and_restake(&config, &mut pool, &mut staker, 0, 600);
+        assert_eq!(staker.claimed, RPS * 200);
+    }
+
+    #[test]
+    fn index_counts_from_launch_when_never_advanced() {
+        let mut config = rush_config();
+        config.start_timestamp = 1_000;
+
+        assert_eq!(current_rush_reward_index(&config, 500).unwrap(), 0);
+        assert_eq!(
+            current_rush_reward_index(&config, 1_500).unwrap(),
+            RPS as u128 * 500
+        );
+    }
+
+    #[test]
+    fn staking_rewards_round_down() {
+        let mut pool = zeroed_pool();
+        let config = rush_config();
+        let mut stakers: Vec<Staker> = (0..3).map(|_| Staker::default()).collect();
+        for staker in stakers.iter_mut() {
+            settle_and_restake(&config, &mut pool, staker, 1, 1);
+        }
+        for staker in stakers.iter_mut() {
+            settle_and_restake(&config, &mut pool, staker, 0, 2);
+        }
+
+        let paid: u64 = stakers.iter().map(|s| s.claimed).sum();
+        assert!(paid <= RPS && RPS - paid < stakers.len() as u64);
+    }
+
+    // ------------------------------------------------------------------------
+    // Module 2.11: StableSwap
+    // ------------------------------------------------------------------------
+
+    const STABLE_AMPS: [u64; 4] = [1, 85, 2_000, MAX_AMP_COEFFICIENT];
+
+    #[test]
+    fn get_d_of_balanced_pool_is_the_sum() {
+        for amp in STABLE_AMPS {
+            assert_eq!(
+                get_d(1_000_000_000_000, 1_000_000_000_000, amp).unwrap(),
+                2_000_000_000_000
+            );
+        }
+    }
+
+    #[test]
+    fn get_d_matches_reference_values() {
+        // floor(D) from solving the invariant at 80 significant digits
+        let cases: [(u64, u64, u64, u128); 5] = [
+            (1_000_000_000_000, 3_000_000_000_000, 100, 3_993_431_643_088),
+            (1_000_000, 1_000_000_000_000, 1, 19_933_340_269),
+            (
+                1_000_000_000_000,
+                1_000_000_000_001,
+                MAX_AMP_COEFFICIENT,
+                2_000_000_000_000,
+            ),
+            (
+                9_000_000_000_000_000_000,
+                1_000_000_000_000_000_000,
+                MAX_AMP_COEFFICIENT,
+                9_999_991_111_143_703_551,
+            ),
+            (123_456_789, 987_654_321, 85, 1_101_473_045),
+        ];
+        for (reserve_a, reserve_b, amp, expected) in cases {
+            let d = get_d(reserve_a, reserve_b, amp).unwrap();
+            assert!(
+                d.abs_diff(expected) <= 1,
+                "get_d({reserve_a}, {reserve_b}, {amp}) = {d}, expected {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn get_d_moves_from_constant_product_to_constant_sum_as_amp_grows() {
+        let (reserve_a, reserve_b) = (1_000_000u64, 1_000_000_000_000u64);
+        let mut previous = 2 * isqrt(reserve_a as u128 * reserve_b as u128);
+        for amp in STABLE_AMPS {
+            let d = get_d(reserve_a, reserve_b, amp).unwrap();
+            assert!(d >= previous && d <= (reserve_a + reserve_b) as u128);
+            previous = d;
+        }
+    }
+
+    #[test]
+    fn get_y_recovers_the_balance_d_was_solved_from() {
+        for amp in STABLE_AMPS {
+            for (reserve_a, reserve_b) in [
+                (1_000_000_000_000u64, 1_000_000_000_000u64),
+                (1_000_000_000_000, 3_000_000_000_000),
+                (5_000_000_000_000, 20_000_000_000),
+            ] {
+                let d = get_d(reserve_a, reserve_b, amp).unwrap();
+                let y = get_y(reserve_a, d, amp).unwrap();
+                assert!(
+                    y.abs_diff(reserve_b) <= 1,
+                    "amp {amp}: get_y({reserve_a}) = {y}, expected {reserve_b}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn stable_swaps_never_decrease_d() {
+        let swaps = [
+            (10_000_000u64, true),
+            (900_000_000_000, true),
+            (1_500_000_000_000, false),
+            (123_456_789, false),
+            (2_000_000_000_000, true),
+        ];
+        for amp in STABLE_AMPS {
+            let (mut reserve_a, mut reserve_b) = (1_000_000_000_000u64, 1_000_000_000_000u64);
+            for (amount_in, is_a_to_b) in swaps {
+                let d_before = get_d(reserve_a, reserve_b, amp).unwrap();
+                let (reserve_in, reserve_out) = if is_a_to_b {
+                    (&mut reserve_a, &mut reserve_b)
+                } else {
+                    (&mut reserve_b, &mut reserve_a)
+                };
+                let amount_out = calculate_stable_swap_output(
+                    amount_in,
+                    *reserve_in,
+                    *reserve_out,
+                    amp,
+                    0,
+                    1_000,
+                )
+                .unwrap();
+                *reserve_in += amount_in;
+                *reserve_out -= amount_out;
+
+                assert!(
+                    get_d(reserve_a, reserve_b, amp).unwrap() >= d_before,
+                    "amp {amp}: swapping {amount_in} lowered D"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn stable_swap_output_rounds_in_the_pools_favour() {
+        for amp in STABLE_AMPS {
+            // Even a flat curve pays out less than was put in
+            let amount_out = calculate_stable_swap_output(
+                1_000_000,
+                1_000_000_000_000,
+                1_000_000_000_000,
+                amp,
+                0,
+                1_000,
+            )
+            .unwrap();
+            assert!(amount_out < 1_000_000);
+
+            // Dust rounds to nothing rather than to a free unit
+            assert_eq!(
+                calculate_stable_swap_output(
+                    1,
+                    1_000_000_000_000,
+                    1_000_000_000_000,
+                    amp,
+                    0,
+                    1_000
+                )
+                .unwrap_err(),
+                error!(CustomError::InsufficientLiquidity)
+            );
+        }
+    }
+
+    #[test]
+    fn pegged_pair_trades_with_less_slippage_than_constant_product() {
+        let reserve = 1_000_000_000_000;
+        for amount_in in [1_000_000_000, 100_000_000_000] {
+            let stable =
+                calculate_stable_swap_output(amount_in, reserve, reserve, 100, 5, 10_000).unwrap();
+            let constant_product =
+                calculate_output_amount(amount_in, reserve, reserve, 5, 10_000).unwrap();
+            assert!(stable > constant_product, "swapping {amount_in}");
+        }
+    }
+
+    #[test]
+    fn stable_lp_supply_follows_d() {
+        let mut pool = zeroed_pool();
+        pool.pool_type = PoolType::StableSwap;
+        pool.amp_coefficient = 100;
+        pool.reserve_a = 10_000_000_000;
+        pool.reserve_b = 10_000_000_000;
+
+        // A balanced first deposit mints D = x + y
+        pool.total_lp_supply =
+            calculate_initial_lp_tokens(&pool, pool.reserve_a, pool.reserve_b).unwrap();
+        assert_eq!(pool.total_lp_supply, 20_000_000_000);
+
+        // A proportional deposit grows D, and so the supply, by its own share
+        let balanced = calculate_deposit_lp_tokens(&pool, 1_000_000_000, 1_000_000_000).unwrap();
+        assert_eq!(balanced, 2_000_000_000);
+
+        // The same amount on one side pushes the pool off its peg and mints less
+        let one_sided = calculate_deposit_lp_tokens(&pool, 2_000_000_000, 0).unwrap();
+        assert!(one_sided < balanced);
+    }
+
+    // ------------------------------------------------------------------------
+    // Module 2.12: Weighted math
+    // ------------------------------------------------------------------------
+
+    /// The error bound pow_fixed_up and the weighted invariant round by
+    fn max_pow_error(value: u128) -> u128 {
+        value / (FIXED_ONE / MAX_POW_RELATIVE_ERROR) + 1
+    }
+
+    #[test]
+    fn ln_fixed_matches_reference_values() {
+        // floor(ln(x)) at 60 significant digits
+        let cases: [(u128, i128); 8] = [
+            (1, -41_446_531_673_892_822_313),
+            (1_000_000_000, -20_723_265_836_946_411_157),
+            (FIXED_ONE / 2, -693_147_180_559_945_310),
+            (FIXED_ONE, 0),
+            (2 * FIXED_ONE, 693_147_180_559_945_309),
+            (3 * FIXED_ONE, 1_098_612_288_668_109_691),
+            (1_000_000_000_000 * FIXED_ONE, 27_631_021_115_928_548_208),
+            (u64::MAX as u128 * FIXED_ONE, 44_361_419_555_836_499_802),
+        ];
+        for (x, expected) in cases {
+            let ln = ln_fixed(x).unwrap();
+            assert!(
+                ln.abs_diff(expected) <= 100,
+                "ln_fixed({x}) = {ln}, expected {expected}"
+            );
+        }
+        assert!(ln_fixed(0).is_err());
+    }
+
+    #[test]
+    fn exp_fixed_matches_reference_values() {
+        // floor(e^x) at 60 significant digits
+        let cases: [(i128, u128); 6] = [
+            (MIN_NATURAL_EXPONENT, 0),
+            (-FIXED_ONE_SIGNED, 367_879_441_171_442_321),
+            (0, FIXED_ONE),
+            (FIXED_ONE_SIGNED, 2_718_281_828_459_045_235),
+            (10 * FIXED_ONE_SIGNED, 22_026_465_794_806_716_516_957),
+            (
+                MAX_NATURAL_EXPONENT,
+                258_131_288_619_006_739_623_285_800_215_273_380_431,
+            ),
+        ];
+        for (x, expected) in cases {
+            let exp = exp_fixed(x).unwrap();
+            assert!(
+                exp.abs_diff(expected) <= max_pow_error(expected),
+                "exp_fixed({x}) = {exp}, expected {expected}"
+            );
+        }
+        assert_eq!(exp_fixed(MIN_NATURAL_EXPONENT - 1).unwrap(), 0);
+        assert!(exp_fixed(MAX_NATURAL_EXPONENT + 1).is_err());
+    }
+
+    /// floor(base^exponent) at 60 significant digits, for bases at the 30% max-in
+    /// ratio, mid-range and next to 1, and exponents at the 1%/99% weight limits
+    const POW_REFERENCE: [(u128, u128, u128); 9] = [
+        (
+            769_230_769_230_769_231,
+            10_101_010_101_010_101,
+            997_353_364_446_250_879,
+        ),
+        (769_230_769_230_769_231, FIXED_ONE, 769_230_769_230_769_231),
+        (769_230_769_230_769_231, 99 * FIXED_ONE, 5_243_341),
+        (
+            900_000_000_000_000_000,
+            10_101_010_101_010_101,
+            998_936_318_477_759_616,
+        ),
+        (900_000_000_000_000_000, FIXED_ONE, 900_000_000_000_000_000),
+        (900_000_000_000_000_000, 99 * FIXED_ONE, 29_512_665_430_652),
+        (
+            999_999_999_999_000_000,
+            10_101_010_101_010_101,
+            999_999_999_999_989_898,
+        ),
+        (999_999_999_999_000_000, FIXED_ONE, 999_999_999_999_000_000),
+        (
+            999_999_999_999_000_000,
+            99 * FIXED_ONE,
+            999_999_999_901_000_000,
+        ),
+    ];
+
+    #[test]
+    fn pow_fixed_is_within_its_error_bound_at_the_weight_limits() {
+        // The reference table's bases and exponents are what a swap produces at the limits
+        let max_in_base = (10_000 * FIXED_ONE).div_ceil(10_000 + WEIGHTED_MAX_IN_RATIO_BPS as u128);
+        let min_exponent =
+            MIN_WEIGHT_BPS as u128 * FIXED_ONE / (TOTAL_WEIGHT_BPS - MIN_WEIGHT_BPS) as u128;
+        assert_eq!(max_in_base, POW_REFERENCE[0].0);
+        assert_eq!(min_exponent, POW_REFERENCE[0].1);
+
+        for (base, exponent, expected) in POW_REFERENCE {
+            let pow = pow_fixed(base, exponent).unwrap();
+            assert!(
+                pow.abs_diff(expected) <= max_pow_error(expected),
+                "pow_fixed({base}, {exponent}) = {pow}, expected {expected}"
+            );
+        }
+        assert_eq!(pow_fixed(0, FIXED_ONE).unwrap(), 0);
+        assert_eq!(pow_fixed(FIXED_ONE / 2, 0).unwrap(), FIXED_ONE);
+    }
+
+    #[test]
+    fn pow_fixed_up_never_rounds_below_the_true_power() {
+        for (base, exponent, expected) in POW_REFERENCE {
+            let pow_up = pow_fixed_up(base, exponent).unwrap();
+            // floor(true value) + 1 is strictly above the true value
+            assert!(
+                pow_up > expected,
+                "pow_fixed_up({base}, {exponent}) = {pow_up}, expected above {expected}"
+            );
+            assert!(pow_up - expected <= 2 * max_pow_error(expected));
+        }
+    }
+
+    #[test]
+    fn weighted_swaps_never_pay_out_more_than_the_exact_curve() {
+        // (amount_in, reserve_in, reserve_out, weight_in, weight_out, fee bps, floor of the
+        // exact output at 80 significant digits)
+        let cases: [(u64, u64, u64, u16, u16, u64, u64); 6] = [
+            (
+                10_000_000_000,
+                8_000_000_000_000,
+                200_000_000_000,
+                8_000,
+                2_000,
+                30,
+                993_901_447,
+            ),
+            (
+                1_000_000_000,
+                200_000_000_000,
+                8_000_000_000_000,
+                2_000,
+                8_000,
+                30,
+                9_939_052_886,
+            ),
+            (
+                300_000_000_000,
+                1_000_000_000_000,
+                1_000_000_000_000,
+                100,
+                9_900,
+                0,
+                2_646_635_553,
+            ),
+            (
+                300_000_000_000,
+                1_000_000_000_000,
+                1_000_000_000_000,
+                9_900,
+                100,
+                0,
+                999_999_999_994,
+            ),
+            (
+                1_000,
+                1_000_000_000_000,
+                1_000_000_000_000,
+                2_000,
+                8_000,
+                0,
+                249,
+            ),
+            (10, 1_000_000_000_000, 1_000_000_000_000, 5_000, 5_000, 0, 9),
+        ];
+        for (amount_in, reserve_in, reserve_out, weight_in, weight_out, fee_bps, expected) in cases
+        {
+            let amount_out = calculate_weighted_swap_output(
+                amount_in,
+                reserve_in,
+                reserve_out,
+                weight_in,
+                weight_out,
+                fee_bps,
+                10_000,
+            )
+            .unwrap();
+            // Rounding costs at most the pow error bound on the output reserve, plus the final floor
+            assert!(
+                amount_out <= expected
+                    && expected - amount_out <= max_pow_error(reserve_out as u128) as u64 + 1,
+                "weights {weight_in}/{weight_out}, in {amount_in}: {amount_out}, expected {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn even_weights_trade_like_constant_product() {
+        let reserve = 1_000_000_000_000;
+        for amount_in in [1_000_000, 1_000_000_000, 100_000_000_000] {
+            let weighted = calculate_weighted_swap_output(
+                amount_in, reserve, reserve, 5_000, 5_000, 30, 10_000,
+            )
+            .unwrap();
+            let constant_product =
+                calculate_output_amount(amount_in, reserve, reserve, 30, 10_000).unwrap();
+            assert!(
+                weighted <= constant_product
+                    && constant_product - weighted <= max_pow_error(reserve as u128) as u64 + 1
+            );
+        }
+    }
+
+    #[test]
+    fn weighted_swaps_are_capped_at_the_max_in_ratio() {
+        let reserve = 1_000_000_000_000;
+        let max_in = reserve * WEIGHTED_MAX_IN_RATIO_BPS / 10_000;
+        assert!(
+            calculate_weighted_swap_output(max_in, reserve, reserve, 8_000, 2_000, 0, 10_000)
+                .is_ok()
+        );
+        assert_eq!(
+            calculate_weighted_swap_output(max_in + 1, reserve, reserve, 8_000, 2_000, 0, 10_000)
+                .unwrap_err(),
+            error!(CustomError::WeightedSwapTooLarge)
+        );
+    }
+
+    #[test]
+    fn weighted_invariant_rounds_down_within_the_pow_error() {
+        // The pow error plus the bound subtracted to round down
+        let max_error = |value: u64| 2 * max_pow_error(value as u128) as u64;
+
+        // 8e12^0.8 * 2e12^0.2 = 6_062_866_266_041.59
+        let expected = 6_062_866_266_041u64;
+        let invariant =
+            calculate_weighted_invariant(8_000_000_000_000, 2_000_000_000_000, 8_000, 2_000)
+                .unwrap();
+        assert!(invariant <= expected && expected - invariant <= max_error(expected));
+
+        // Even weights reduce to sqrt(a * b)
+        let invariant = calculate_weighted_invariant(u64::MAX, u64::MAX, 5_000, 5_000).unwrap();
+        assert!(invariant < u64::MAX && u64::MAX - invariant <= max_error(u64::MAX));
+    }
+
+    // ------------------------------------------------------------------------
+    // Module 2.4: Exact-out withdrawal
+    // ------------------------------------------------------------------------
+
+    /// Pool of the given type whose LP supply is the invariant of its reserves
+    fn pool_with_reserves(pool_type: PoolType, reserve_a: u64, reserve_b: u64) -> LiquidityPool {
+        let mut pool = zeroed_pool();
+        pool.pool_type = pool_type;
+        pool.reserve_a = reserve_a;
+        pool.reserve_b = reserve_b;
+        pool.fee_denominator = 10_000;
+        pool.amp_coefficient = 100;
+        pool.weight_a_bps = 8_000;
+        pool.weight_b_bps = 2_000;
+        pool.total_lp_supply =
+            calculate_pool_invariant(&pool, reserve_a, reserve_b, false).unwrap() as u64;
+        pool
+    }
+
+    #[test]
+    fn exact_out_proportional_withdrawal_costs_its_lp_share() {
+        let pool = pool_with_reserves(
+            PoolType::ConstantProduct,
+            1_000_000_000_000,
+            5_000_000_000_000,
+        );
+        let (lp_tokens, fee_a, fee_b) =
+            calculate_exact_out_lp_tokens(&pool, 10_000_000_000, 50_000_000_000, 30).unwrap();
+
+        assert_eq!(lp_tokens, 22_360_679_777);
+        // Never cheaper than remove_liquidity for the same amounts
+        assert!(lp_tokens >= pool.total_lp_supply / 100);
+        // Only rounding dust is charged
+        assert!(fee_a <= 1 && fee_b <= 1);
+    }
+
+    #[test]
+    fn exact_out_imbalanced_withdrawal_pays_the_fee_on_the_swapped_portion() {
+        let pool = pool_with_reserves(
+            PoolType::ConstantProduct,
+            1_000_000_000_000,
+            5_000_000_000_000,
+        );
+        let (with_fee, fee_a, fee_b) =
+            calculate_exact_out_lp_tokens(&pool, 10_000_000_000, 0, 30).unwrap();
+        let (without_fee, _, _) =
+            calculate_exact_out_lp_tokens(&pool, 10_000_000_000, 0, 0).unwrap();
+
+        assert_eq!(with_fee, 11_225_201_882);
+        assert_eq!((fee_a, fee_b), (7_481_156, 37_594_222));
+        // Roughly half of a one-sided withdrawal is an implicit swap: 0.3% of half
+        let fee_share_bps = (with_fee - without_fee) * 100_000 / without_fee;
+        assert!(
+            (140..=160).contains(&fee_share_bps),
+            "fee share {fee_share_bps}/100_000"
+        );
+    }
+
+    #[test]
+    fn exact_out_never_dilutes_remaining_lps() {
+        let withdrawals = [
+            (123_456_789u64, 987_654_321_000u64),
+            (10_000_000_000, 0),
+            (0, 1),
+            (900_000_000_000, 4_500_000_000_000),
+        ];
+        for pool_type in [
+            PoolType::ConstantProduct,
+            PoolType::StableSwap,
+            PoolType::Weighted,
+        ] {
+            let pool = pool_with_reserves(pool_type, 1_000_000_000_000, 5_000_000_000_000);
+            let d_before =
+                calculate_pool_invariant(&pool, pool.reserve_a, pool.reserve_b, true).unwrap();
+
+            for (amount_a, amount_b) in withdrawals {
+                let (lp_tokens, _, _) =
+                    calculate_exact_out_lp_tokens(&pool, amount_a, amount_b, 30).unwrap();
+                let d_after = calculate_pool_invariant(
+                    &pool,
+                    pool.reserve_a - amount_a,
+                    pool.reserve_b - amount_b,
+                    false,
+                )
+                .unwrap();
+
+                // Invariant per LP token: d_after / (supply - burned) >= d_before / supply
+                let remaining_supply = (pool.total_lp_supply - lp_tokens) as u128;
+                assert!(
+                    U256::from(d_after) * U256::from(pool.total_lp_supply)
+                        >= U256::from(d_before) * U256::from(remaining_supply),
+                    "{:?}: withdrawing ({amount_a}, {amount_b}) for {lp_tokens} LP dilutes",
+                    pool.pool_type
+                );
+            }
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // Module 2.6: Single-sided zap
+    // ------------------------------------------------------------------------
+
+    #[test]
+    fn zap_leaves_the_remaining_input_in_the_post_swap_ratio() {
+        let (reserve_a, reserve_b) = (1_000_000_000_000u64, 5_000_000_000_000u64);
+        for amount_in in [1_000_000u64, 100_000_000_000, 2_000_000_000_000] {
+            let swap_amount =
+                calculate_single_sided_swap_amount(amount_in, reserve_a, 30, 10_000).unwrap();
+            let amount_out =
+                calculate_output_amount(swap_amount, reserve_a, reserve_b, 30, 10_000).unwrap();
+            let deposit_a = (amount_in - swap_amount) as u128;
+            let (new_reserve_a, new_reserve_b) = (
+                (reserve_a + swap_amount) as u128,
+                (reserve_b - amount_out) as u128,
+            );
+
+            // deposit_a / new_reserve_a == amount_out / new_reserve_b to within 1e-6
+            let lhs = deposit_a * new_reserve_b;
+            let rhs = amount_out as u128 * new_reserve_a;
+            assert!(
+                lhs.abs_diff(rhs) * 1_000_000 < lhs,
+                "zap of {amount_in} leaves {deposit_a} against {amount_out}"
+            );
+        }
+    }
+
+    #[test]
+    fn zap_swaps_about_half_of_a_small_deposit() {
+        let swap_amount =
+            calculate_single_sided_swap_amount(1_000, 1_000_000_000_000, 30, 10_000).unwrap();
+        assert!((499..=501).contains(&swap_amount));
+    }
+
+    #[test]
+    fn zap_rejects_deposits_it_cannot_split() {
+        // One unit cannot be split into two non-zero legs
+        assert_eq!(
+            calculate_single_sided_swap_amount(1, 1_000_000, 30, 10_000).unwrap_err(),
+            error!(CustomError::InvalidAmount)
+        );
+        assert_eq!(
+            calculate_single_sided_swap_amount(1_000, 1_000_000, 10_000, 10_000).unwrap_err(),
+            error!(CustomError::InvalidFeeParameters)
+        );
+    }
+
+    // ------------------------------------------------------------------------
+    // Module 2.3: Optimal-ratio deposits
+    // ------------------------------------------------------------------------
+
+    #[test]
+    fn optimal_amounts_clamp_the_excess_side_to_the_pool_ratio() {
+        // Excess B
+        assert_eq!(
+            calculate_optimal_liquidity_amounts(100, 600, 0, 0, 1_000, 5_000).unwrap(),
+            (100, 500)
+        );
+        // Excess A
+        assert_eq!(
+            calculate_optimal_liquidity_amounts(200, 500, 0, 0, 1_000, 5_000).unwrap(),
+            (100, 500)
+        );
+        // Far off the ratio is clamped, not rejected
+        assert_eq!(
+            calculate_optimal_liquidity_amounts(100, 10_000, 0, 0, 1_000, 5_000).unwrap(),
+            (100, 500)
+        );
+    }
+
+    #[test]
+    fn optimal_amounts_enforce_the_minimums() {
+        assert_eq!(
+            calculate_optimal_liquidity_amounts(100, 600, 0, 550, 1_000, 5_000).unwrap_err(),
+            error!(CustomError::SlippageTooHigh)
+        );
+        assert_eq!(
+            calculate_optimal_liquidity_amounts(200, 500, 150, 0, 1_000, 5_000).unwrap_err(),
+            error!(CustomError::SlippageTooHigh)
+        );
+        // Minimums at exactly the clamped amounts pass
+        assert_eq!(
+            calculate_optimal_liquidity_amounts(200, 500, 100, 500, 1_000, 5_000).unwrap(),
+            (100, 500)
+        );
+    }
+
+    // ------------------------------------------------------------------------
+    // Module 2.7: Reserve reconciliation
+    // ------------------------------------------------------------------------
+
+    #[test]
+    fn vault_surplus_excludes_reserves_and_protocol_fees() {
+        let mut pool = zeroed_pool();
+        pool.reserve_a = 1_000_000;
+        pool.reserve_b = 5_000_000;
+        pool.protocol_fees_a = 500;
+
+        assert_eq!(
+            calculate_vault_surplus(&pool, 1_000_500 + 12_345, 5_000_000).unwrap(),
+            (12_345, 0)
+        );
+        assert_eq!(
+            calculate_vault_surplus(&pool, 1_000_500, 5_000_000).unwrap(),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn vault_below_its_bookkeeping_violates_the_invariant() {
+        let mut pool = zeroed_pool();
+        pool.reserve_a = 1_000;
+        pool.protocol_fees_b = 1;
+
+        for (vault_a, vault_b) in [(999, 1), (1_000, 0)] {
+            assert_eq!(
+                calculate_vault_surplus(&pool, vault_a, vault_b).unwrap_err(),
+                error!(CustomError::VaultInvariantViolated)
+            );
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // Module 2.13: Concentrated liquidity math
+    // ------------------------------------------------------------------------
+
+    const Q64: u128 = 1 << 64;
+
+    #[test]
+    fn sqrt_price_at_tick_matches_reference_values() {
+        assert_eq!(get_sqrt_price_at_tick(0).unwrap(), Q64);
+        assert_eq!(
+            get_sqrt_price_at_tick(1).unwrap(),
+            18_447_666_387_855_959_851
+        );
+        assert_eq!(
+            get_sqrt_price_at_tick(-1).unwrap(),
+            18_445_821_805_675_392_312
+        );
+        assert_eq!(
+            get_sqrt_price_at_tick(100).unwrap(),
+            18_539_204_128_674_405_813
+        );
+        assert_eq!(
+            get_sqrt_price_at_tick(-100).unwrap(),
+            18_354_745_142_194_483_564
+        );
+        assert_eq!(
+            get_sqrt_price_at_tick(MIN_TICK).unwrap(),
+            MIN_SQRT_PRICE_X64
+        );
+        assert_eq!(
+            get_sqrt_price_at_tick(MAX_TICK).unwrap(),
+            MAX_SQRT_PRICE_X64
+        );
+
+        // sqrt(1.0001^100) = 1.005012269...
+        let sqrt_price = get_sqrt_price_at_tick(100).unwrap() as f64 / Q64 as f64;
+        assert!((sqrt_price - 1.0001f64.powi(50)).abs() < 1e-12);
+
+        assert_eq!(
+            get_sqrt_price_at_tick(MAX_TICK + 1).unwrap_err(),
+            error!(CustomError::InvalidTickIndex)
+        );
+        assert_eq!(
+            get_sqrt_price_at_tick(MIN_TICK - 1).unwrap_err(),
+            error!(CustomError::InvalidTickIndex)
+        );
+    }
+
+    #[test]
+    fn tick_at_sqrt_price_floors_to_the_tick_at_or_below_the_price() {
+        for tick in [0, 1, -1, 12_345, -54_321, MIN_TICK, MAX_TICK - 1] {
+            let sqrt_price = get_sqrt_price_at_tick(tick).unwrap();
+            let next_sqrt_price = get_sqrt_price_at_tick(tick + 1).unwrap();
+            assert_eq!(get_tick_at_sqrt_price(sqrt_price).unwrap(), tick);
+            assert_eq!(get_tick_at_sqrt_price(next_sqrt_price - 1).unwrap(), tick);
+        }
+        assert_eq!(get_tick_at_sqrt_price(Q64 + 12_345_678_901_234).unwrap(), 0);
+
+        assert_eq!(
+            get_tick_at_sqrt_price(MIN_SQRT_PRICE_X64 - 1).unwrap_err(),
+            error!(CustomError::InvalidSqrtPrice)
+        );
+        assert_eq!(
+            get_tick_at_sqrt_price(MAX_SQRT_PRICE_X64).unwrap_err(),
+            error!(CustomError::InvalidSqrtPrice)
+        );
+    }
+
+    #[test]
+    fn swap_step_matches_reference_values() {
+        let liquidity = 1_000_000_000_000;
+
+        // A to B stops inside the range: the whole input is used
+        let target = get_sqrt_price_at_tick(-100).unwrap();
+        let partial = compute_swap_step(Q64, target, liquidity, 1_000_000_000, 30, 10_000).unwrap();
+        assert_eq!(partial.sqrt_price_next, 18_428_370_987_834_680_440);
+        assert_eq!(partial.amount_in, 997_000_000);
+        assert_eq!(partial.amount_out, 996_006_981);
+        assert_eq!(partial.fee_amount, 3_000_000);
+
+        // B to A reaches the target tick: only what the range needs is charged
+        let target = get_sqrt_price_at_tick(100).unwrap();
+        let full =
+            compute_swap_step(Q64, target, liquidity, 1_000_000_000_000, 30, 10_000).unwrap();
+        assert_eq!(full.sqrt_price_next, target);
+        assert_eq!(full.amount_in, 5_012_269_624);
+        assert_eq!(full.amount_out, 4_987_272_070);
+        assert_eq!(full.fee_amount, 15_082_056);
+    }
+
+    #[test]
+    fn position_amounts_depend_on_where_the_price_sits_in_the_range() {
+        let liquidity = 1_000_000_000_000;
+
+        assert_eq!(
+            get_amounts_for_liquidity(Q64, 0, -100, 100, liquidity, true).unwrap(),
+            (4_987_272_071, 4_987_272_071)
+        );
+
+        // Price below the range: token A only; above it: token B only
+        let (below_a, below_b) =
+            get_amounts_for_liquidity(Q64, 0, 100, 200, liquidity, true).unwrap();
+        assert!(below_a > 0 && below_b == 0);
+        let (above_a, above_b) =
+            get_amounts_for_liquidity(Q64, 0, -200, -100, liquidity, true).unwrap();
+        assert!(above_a == 0 && above_b > 0);
+
+        // Deposits round up and withdrawals round down, so a round trip never pays out more
+        let (deposit_a, deposit_b) =
+            get_amounts_for_liquidity(Q64, 0, -100, 100, 12_345, true).unwrap();
+        let (withdraw_a, withdraw_b) =
+            get_amounts_for_liquidity(Q64, 0, -100, 100, 12_345, false).unwrap();
+        assert!(withdraw_a <= deposit_a && withdraw_b <= deposit_b);
+        assert_eq!(deposit_a - withdraw_a, 1);
+    }
+
+    // ------------------------------------------------------------------------
+    // Module 2.14: Dynamic fees
+    // ------------------------------------------------------------------------
+
+    /// Balanced constant-product pool in dynamic fee mode
+    fn dynamic_fee_pool(min_fee_bps: u16, max_fee_bps: u16) -> LiquidityPool {
+        let mut pool = pool_with_reserves(
+            PoolType::ConstantProduct,
+            1_000_000_000_000,
+            1_000_000_000_000,
+        );
+        pool.fee_numerator = 30;
+        pool.dynamic_fee_enabled = true;
+        pool.min_fee_bps = min_fee_bps;
+        pool.max_fee_bps = max_fee_bps;
+        pool
+    }
+
+    /// Same fee and accumulator bookkeeping as swap; returns the fee charged in bps
+    fn dynamic_fee_swap(
+        pool: &mut LiquidityPool,
+        amount_in: u64,
+        is_a_to_b: bool,
+        now: i64,
+    ) -> u64 {
+        let fee_numerator = calculate_effective_fee_numerator(pool, now);
+        let price_before = calculate_spot_price(pool).unwrap();
+        let amount_out = calculate_swap_output(pool, amount_in, is_a_to_b, fee_numerator).unwrap();
+        if is_a_to_b {
+            pool.reserve_a += amount_in;
+            pool.reserve_b -= amount_out;
+        } else {
+            pool.reserve_b += amount_in;
+            pool.reserve_a -= amount_out;
+        }
+        update_volatility_accumulator(pool, price_before, now).unwrap();
+        fee_numerator
+    }
+
+    #[test]
+    fn volatility_decays_linearly_between_swaps() {
+        assert_eq!(decay_volatility_accumulator(400, 0, 0), 400);
+        assert_eq!(
+            decay_volatility_accumulator(400, 0, VOLATILITY_DECAY_PERIOD / 2),
+            200
+        );
+        assert_eq!(
+            decay_volatility_accumulator(400, 0, VOLATILITY_DECAY_PERIOD - 1),
+            1
+        );
+        assert_eq!(
+            decay_volatility_accumulator(400, 0, VOLATILITY_DECAY_PERIOD),
+            0
+        );
+        // Clock skew never grows the accumulator
+        assert_eq!(decay_volatility_accumulator(400, 100, 50), 400);
+    }
+
+    #[test]
+    fn dynamic_fee_scales_with_volatility_up_to_the_max() {
+        assert_eq!(calculate_dynamic_fee_bps(5, 100, 0), 5);
+        assert_eq!(calculate_dynamic_fee_bps(5, 100, 200), 43);
+        assert_eq!(
+            calculate_dynamic_fee_bps(5, 100, VOLATILITY_SATURATION_BPS),
+            100
+        );
+        assert_eq!(
+            calculate_dynamic_fee_bps(5, 100, MAX_VOLATILITY_ACCUMULATOR),
+            100
+        );
+        // Equal bounds pin the fee
+        assert_eq!(
+            calculate_dynamic_fee_bps(30, 30, MAX_VOLATILITY_ACCUMULATOR),
+            30
+        );
+    }
+
+    #[test]
+    fn price_movement_is_capped_and_ignores_unpriced_pools() {
+        assert_eq!(calculate_price_movement_bps(1_000_000, 1_020_000), 200);
+        assert_eq!(calculate_price_movement_bps(1_000_000, 980_000), 200);
+        assert_eq!(
+            calculate_price_movement_bps(1_000_000, 100_000_000),
+            MAX_VOLATILITY_ACCUMULATOR
+        );
+        assert_eq!(calculate_price_movement_bps(0, 1_000), 0);
+    }
+
+    #[test]
+    fn fee_tier_applies_outside_dynamic_mode() {
+        let mut pool = dynamic_fee_pool(5, 100);
+        pool.dynamic_fee_enabled = false;
+        pool.volatility_accumulator = MAX_VOLATILITY_ACCUMULATOR;
+
+        assert_eq!(dynamic_fee_swap(&mut pool, 10_000_000_000, true, 1_000), 30);
+        assert_eq!(pool.volatility_accumulator, MAX_VOLATILITY_ACCUMULATOR);
+        assert_eq!(pool.last_swap_timestamp, 0);
+    }
+
+    #[test]
+    fn volatile_swaps_raise_the_fee_for_later_swaps_only() {
+        let mut pool = dynamic_fee_pool(5, 100);
+
+        // Dust does not register as movement
+        assert_eq!(dynamic_fee_swap(&mut pool, 1_000_000, true, 1_000), 5);
+        assert_eq!(pool.volatility_accumulator, 0);
+
+        // 1% of the reserve moves the price ~2%, but the swap pays the fee it was quoted
+        assert_eq!(dynamic_fee_swap(&mut pool, 10_000_000_000, true, 1_000), 5);
+        let accumulator = pool.volatility_accumulator;
+        assert!((190..=200).contains(&accumulator), "{accumulator}");
+
+        // The next swap pays for it
+        assert_eq!(
+            dynamic_fee_swap(&mut pool, 10_000_000_000, false, 1_000),
+            calculate_dynamic_fee_bps(5, 100, accumulator) as u64
+        );
+
+        // Sustained volatility saturates at max_fee_bps
+        for i in 0..4 {
+            dynamic_fee_swap(&mut pool, 10_000_000_000, i % 2 == 0, 1_000);
+        }
+        assert!(pool.volatility_accumulator >= VOLATILITY_SATURATION_BPS);
+        assert_eq!(dynamic_fee_swap(&mut pool, 1_000_000, true, 1_000), 100);
+
+        // ... and calms back down to min_fee_bps once the decay period passes
+        assert_eq!(
+            dynamic_fee_swap(&mut pool, 1_000_000, true, 1_000 + VOLATILITY_DECAY_PERIOD),
+            5
+        );
+    }
+
+    // ------------------------------------------------------------------------
+    // Module 2.15: Position earnings
+    // ------------------------------------------------------------------------
+
+    /// Balanced constant-product pool with a 0.3% fee and a 20% protocol share
+    fn fee_earning_pool() -> LiquidityPool {
+        let mut pool = pool_with_reserves(
+            PoolType::ConstantProduct,
+            1_000_000_000_000,
+            1_000_000_000_000,
+        );
+        pool.fee_numerator = 30;
+        pool.protocol_fee_share_bps = 2_000;
+        pool
+    }
+
+    fn zeroed_position() -> UserLiquidityPosition {
+        UserLiquidityPosition::deserialize(&mut &[0u8; UserLiquidityPosition::SIZE][..]).unwrap()
+    }
+
+    /// Same reserve and fee bookkeeping as swap; returns the LP share of the fee
+    fn fee_earning_swap(pool: &mut LiquidityPool, amount_in: u64, is_a_to_b: bool) -> u64 {
+        let amount_out = calculate_swap_output(pool, amount_in, is_a_to_b, 30).unwrap();
+        let fee = amount_in * 30 / 10_000;
+        let protocol_fee = calculate_protocol_fee(fee, pool.protocol_fee_share_bps).unwrap();
+        if is_a_to_b {
+            pool.reserve_a += amount_in - protocol_fee;
+            pool.reserve_b -= amount_out;
+        } else {
+            pool.reserve_b += amount_in - protocol_fee;
+            pool.reserve_a -= amount_out;
+        }
+        accrue_lp_fee_growth(pool, fee - protocol_fee, is_a_to_b);
+        fee - protocol_fee
+    }
+
+    /// Same position bookkeeping as add_liquidity
+    fn fee_earning_deposit(
+        pool: &mut LiquidityPool,
+        position: &mut UserLiquidityPosition,
+        amount_a: u64,
+        amount_b: u64,
+    ) {
+        let minted = calculate_deposit_lp_tokens(pool, amount_a, amount_b).unwrap();
+        pool.reserve_a += amount_a;
+        pool.reserve_b += amount_b;
+        pool.total_lp_supply += minted;
+        record_position_deposit(position, pool, minted, amount_a, amount_b).unwrap();
+    }
+
+    #[test]
+    fn fees_earned_survive_fee_growth_wrapping() {
+        // Growth counters wrap; the difference since the checkpoint is still 2.0 per unit
+        assert_eq!(
+            calculate_fees_earned(10, 1 << 64, u128::MAX - (1 << 64) + 1).unwrap(),
+            20
+        );
+        assert_eq!(calculate_fee_growth(1_000, 0), 0);
+    }
+
+    #[test]
+    fn positions_earn_lp_fees_in_proportion_to_their_share() {
+        let mut pool = fee_earning_pool();
+        let mut position = zeroed_position();
+        // Half the pool
+        fee_earning_deposit(
+            &mut pool,
+            &mut position,
+            1_000_000_000_000,
+            1_000_000_000_000,
+        );
+
+        let (mut lp_fees_a, mut lp_fees_b) = (0, 0);
+        for _ in 0..10 {
+            lp_fees_a += fee_earning_swap(&mut pool, 5_000_000_000, true);
+            lp_fees_b += fee_earning_swap(&mut pool, 5_000_000_000, false);
+        }
+        // 0.3% of each swap less the 20% protocol share
+        assert_eq!(lp_fees_a, 10 * 12_000_000);
+
+        let (fees_a, fees_b) = calculate_pending_position_fees(&position, &pool).unwrap();
+        assert!(fees_a <= lp_fees_a / 2 && lp_fees_a / 2 - fees_a <= 10);
+        assert!(fees_b <= lp_fees_b / 2 && lp_fees_b / 2 - fees_b <= 10);
+    }
+
+    #[test]
+    fn later_deposits_do_not_earn_earlier_fees() {
+        let mut pool = fee_earning_pool();
+        fee_earning_swap(&mut pool, 10_000_000_000, true);
+
+        let mut position = zeroed_position();
+        fee_earning_deposit(&mut pool, &mut position, 1_000_000_000, 1_000_000_000);
+        assert_eq!(
+            calculate_pending_position_fees(&position, &pool).unwrap(),
+            (0, 0)
+        );
+
+        let lp_fee = fee_earning_swap(&mut pool, 10_000_000_000, true);
+        let (fees_a, _) = calculate_pending_position_fees(&position, &pool).unwrap();
+        let share = lp_fee * position.lp_tokens / pool.total_lp_supply;
+        assert!(fees_a <= share && share - fees_a <= 1);
+    }
+
+    #[test]
+    fn settling_keeps_fees_across_balance_changes() {
+        let mut pool = fee_earning_pool();
+        let mut position = zeroed_position();
+        fee_earning_deposit(
+            &mut pool,
+            &mut position,
+            1_000_000_000_000,
+            1_000_000_000_000,
+        );
+        fee_earning_swap(&mut pool, 20_000_000_000, true);
+        let (pending_a, _) = calculate_pending_position_fees(&position, &pool).unwrap();
+        assert!(pending_a > 0);
+
+        // Topping up moves pending fees into the position's totals
+        fee_earning_deposit(&mut pool, &mut position, 1_000_000_000, 1_000_000_000);
+        assert_eq!(position.fees_earned_a, pending_a);
+        assert_eq!(
+            calculate_pending_position_fees(&position, &pool).unwrap(),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn withdrawals_settle_fees_and_saturate_the_lp_counter() {
+        let mut pool = fee_earning_pool();
+        let mut position = zeroed_position();
+        fee_earning_deposit(&mut pool, &mut position, 1_000_000_000, 1_000_000_000);
+        fee_earning_swap(&mut pool, 20_000_000, false);
+        let (_, pending_b) = calculate_pending_position_fees(&position, &pool).unwrap();
+        assert!(pending_b > 0);
+
+        // LP received by transfer can burn past the position's own counter
+        let lp_tokens = position.lp_tokens;
+        record_position_withdrawal(&mut position, &pool, lp_tokens + 1, 7, 9).unwrap();
+        assert_eq!(position.lp_tokens, 0);
+        assert_eq!((position.withdrawn_a, position.withdrawn_b), (7, 9));
+        assert_eq!(position.fees_earned_b, pending_b);
+        assert_eq!(position.fee_growth_checkpoint_b, pool.fee_growth_global_b);
+    }
+
+    #[test]
+    fn impermanent_loss_matches_the_constant_product_formula() {
+        let mut pool = fee_earning_pool();
+        let mut position = zeroed_position();
+        fee_earning_deposit(
+            &mut pool,
+            &mut position,
+            1_000_000_000_000,
+            1_000_000_000_000,
+        );
+
+        // Same inputs as get_position_earnings
+        let impermanent_loss = |pool: &LiquidityPool, position: &UserLiquidityPosition| {
+            let (amount_a, amount_b) = calculate_remove_liquidity_amounts(
+                position.lp_tokens,
+                pool.total_lp_supply,
+                pool.reserve_a,
+                pool.reserve_b,
+            )
+            .unwrap();
+            let (fees_a, fees_b) = calculate_pending_position_fees(position, pool).unwrap();
+            calculate_impermanent_loss(
+                position.deposited_a as i128,
+                position.deposited_b as i128,
+                amount_a,
+                amount_b,
+                fees_a,
+                fees_b,
+                calculate_spot_price(pool).unwrap(),
+            )
+            .unwrap()
+        };
+        assert!(impermanent_loss(&pool, &position).abs() <= 1);
+
+        // Push the price of A up ~2.25x in one swap
+        fee_earning_swap(&mut pool, 1_000_000_000_000, false);
+        let price = calculate_spot_price(&pool).unwrap() as f64 / 1e6;
+        let hold_value = 1e12 * price + 1e12;
+        let expected = 1.0 - 2.0 * price.sqrt() / (1.0 + price);
+
+        let loss = impermanent_loss(&pool, &position) as f64 / hold_value;
+        assert!((loss - expected).abs() < 0.001, "{loss} vs {expected}");
+        // The swap fee is reported separately rather than netted against the loss
+        assert!(calculate_pending_position_fees(&position, &pool).unwrap().1 > 0);
+    }
+
+    // ------------------------------------------------------------------------
+    // Module 3.5: Flash loans
+    // ------------------------------------------------------------------------
+
+    #[test]
+    fn flash_loan_fee_rounds_up() {
+        assert_eq!(calculate_flash_loan_fee(1_000_000, 9).unwrap(), 900);
+        assert_eq!(
+            calculate_flash_loan_fee(1_000_000_000, 30).unwrap(),
+            3_000_000
+        );
+        // Dust and partial units still pay a base unit
+        assert_eq!(calculate_flash_loan_fee(1, 9).unwrap(), 1);
+        assert_eq!(calculate_flash_loan_fee(1_111, 9).unwrap(), 1);
+        assert_eq!(calculate_flash_loan_fee(1_112, 9).unwrap(), 2);
+    }
+
+    #[test]
+    fn flash_loan_fee_covers_the_whole_u64_range() {
+        assert_eq!(
+            calculate_flash_loan_fee(u64::MAX, MAX_FLASH_LOAN_FEE_BPS).unwrap(),
+            u64::MAX / 10 + 1
+        );
+        assert_eq!(calculate_flash_loan_fee(u64::MAX, 0).unwrap(), 0);
+    }
+}
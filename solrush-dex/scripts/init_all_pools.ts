@@ -7,11 +7,14 @@ import {
     createSyncNativeInstruction,
     NATIVE_MINT,
     TOKEN_PROGRAM_ID,
+    TOKEN_2022_PROGRAM_ID,
     ASSOCIATED_TOKEN_PROGRAM_ID
 } from "@solana/spl-token";
 import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY, Transaction } from "@solana/web3.js";
 import * as fs from "fs";
 
+const POOLS_PER_REGISTRY_PAGE = 32;
+
 async function main() {
     console.log("Initializing 3 additional pools on Devnet...\n");
 
@@ -41,22 +44,40 @@ async function main() {
             name: "SOL/USDC",
             tokenA: wsolMint,
             tokenB: usdcMint,
+            symbolA: "SOL",
+            symbolB: "USDC",
             depositA: 1 * 1e9, // 1 SOL
             depositB: 100 * 1e6, // 100 USDC
+            feeTierBps: 30, // 0.3%
+            poolType: { constantProduct: {} },
+            ampCoefficient: 0,
+            weightABps: 0,
         },
         {
             name: "SOL/USDT",
             tokenA: wsolMint,
             tokenB: usdtMint,
+            symbolA: "SOL",
+            symbolB: "USDT",
             depositA: 1 * 1e9, // 1 SOL
             depositB: 100 * 1e6, // 100 USDT
+            feeTierBps: 30, // 0.3%
+            poolType: { constantProduct: {} },
+            ampCoefficient: 0,
+            weightABps: 0,
         },
         {
             name: "USDC/USDT",
             tokenA: usdcMint,
             tokenB: usdtMint,
+            symbolA: "USDC",
+            symbolB: "USDT",
             depositA: 10000 * 1e6, // 10,000 USDC
             depositB: 10000 * 1e6, // 10,000 USDT
+            feeTierBps: 5, // 0.05% for the stable pair
+            poolType: { stableSwap: {} },
+            ampCoefficient: 100, // amplified curve for the pegged pair
+            weightABps: 0,
         }
     ];
 
@@ -67,21 +88,49 @@ async function main() {
     };
 
     for (const poolConfig of poolsToInit) {
+        // The program only accepts pools whose mints are in canonical byte order
+        if (poolConfig.tokenA.toBuffer().compare(poolConfig.tokenB.toBuffer()) > 0) {
+            [poolConfig.tokenA, poolConfig.tokenB] = [poolConfig.tokenB, poolConfig.tokenA];
+            [poolConfig.depositA, poolConfig.depositB] = [poolConfig.depositB, poolConfig.depositA];
+            [poolConfig.symbolA, poolConfig.symbolB] = [poolConfig.symbolB, poolConfig.symbolA];
+        }
+
         console.log(`\n${"=".repeat(60)}`);
         console.log(`Initializing ${poolConfig.name} Pool`);
         console.log(`${"=".repeat(60)}`);
 
         try {
-            // Derive pool PDA
+            // Derive pool PDA (fee tier is a little-endian u16 seed)
+            const feeTierSeed = Buffer.alloc(2);
+            feeTierSeed.writeUInt16LE(poolConfig.feeTierBps);
             const [poolPda, poolBump] = PublicKey.findProgramAddressSync(
                 [
                     Buffer.from("pool"),
                     poolConfig.tokenA.toBuffer(),
-                    poolConfig.tokenB.toBuffer()
+                    poolConfig.tokenB.toBuffer(),
+                    feeTierSeed
                 ],
                 program.programId
             );
 
+            const [dexConfig] = PublicKey.findProgramAddressSync(
+                [Buffer.from("dex_config")],
+                program.programId
+            );
+
+            // New pools are appended to the current on-chain registry page
+            const [poolRegistry] = PublicKey.findProgramAddressSync(
+                [Buffer.from("pool_registry")],
+                program.programId
+            );
+            const registry = await program.account.poolRegistry.fetch(poolRegistry);
+            const pageSeed = Buffer.alloc(4);
+            pageSeed.writeUInt32LE(Math.floor(registry.poolCount.toNumber() / POOLS_PER_REGISTRY_PAGE));
+            const [registryPage] = PublicKey.findProgramAddressSync(
+                [Buffer.from("registry_page"), pageSeed],
+                program.programId
+            );
+
             console.log("Pool PDA:", poolPda.toString());
 
             // Derive LP token mint PDA
@@ -166,10 +215,12 @@ async function main() {
             console.log("User Token B Account:", userTokenB.address.toString());
 
             // Get user LP token account address (will be created by the program)
+            // LP mints are Token-2022 so they can carry on-chain metadata
             const userLpTokenAccount = await getAssociatedTokenAddress(
                 lpTokenMint,
                 wallet.publicKey,
-                true // allowOwnerOffCurve
+                true, // allowOwnerOffCurve
+                TOKEN_2022_PROGRAM_ID
             );
 
             console.log("User LP Token Account:", userLpTokenAccount.toString());
@@ -179,10 +230,19 @@ async function main() {
             const tx = await program.methods
                 .initializePool(
                     new anchor.BN(poolConfig.depositA),
-                    new anchor.BN(poolConfig.depositB)
+                    new anchor.BN(poolConfig.depositB),
+                    poolConfig.feeTierBps,
+                    poolConfig.symbolA,
+                    poolConfig.symbolB,
+                    poolConfig.poolType,
+                    new anchor.BN(poolConfig.ampCoefficient),
+                    poolConfig.weightABps
                 )
                 .accounts({
                     pool: poolPda,
+                    dexConfig,
+                    poolRegistry,
+                    registryPage,
                     tokenAMint: poolConfig.tokenA,
                     tokenBMint: poolConfig.tokenB,
                     lpTokenMint: lpTokenMint,
@@ -193,7 +253,9 @@ async function main() {
                     lpTokenAccount: userLpTokenAccount,
                     authority: wallet.publicKey,
                     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-                    tokenProgram: TOKEN_PROGRAM_ID,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    tokenAProgram: TOKEN_PROGRAM_ID,
+                    tokenBProgram: TOKEN_PROGRAM_ID,
                     systemProgram: SystemProgram.programId,
                     rent: SYSVAR_RENT_PUBKEY,
                 } as any)
@@ -214,6 +276,7 @@ async function main() {
                 tokenBVault: tokenBVault.publicKey.toString(),
                 initialDepositA: poolConfig.depositA,
                 initialDepositB: poolConfig.depositB,
+                feeTierBps: poolConfig.feeTierBps,
                 transactionSignature: tx
             });
 
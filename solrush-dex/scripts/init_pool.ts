@@ -7,6 +7,7 @@ import {
     getOrCreateAssociatedTokenAccount,
     mintTo,
     TOKEN_PROGRAM_ID,
+    TOKEN_2022_PROGRAM_ID,
     ASSOCIATED_TOKEN_PROGRAM_ID,
     createSyncNativeInstruction,
     createAssociatedTokenAccountInstruction,
@@ -42,13 +43,36 @@ async function main() {
     const wsolMint = new anchor.web3.PublicKey("So11111111111111111111111111111111111111112");
     console.log("WSOL Mint Address:", wsolMint.toString());
 
-    // 3. Derive Pool PDA
+    // 3. Derive Pool PDA (mints in canonical byte order, fee tier is a little-endian u16 seed)
+    const wsolIsTokenA = wsolMint.toBuffer().compare(rushMint.toBuffer()) < 0;
+    const [tokenAMint, tokenBMint] = wsolIsTokenA ? [wsolMint, rushMint] : [rushMint, wsolMint];
+    const feeTierBps = 30; // 0.3%
+    const feeTierSeed = Buffer.alloc(2);
+    feeTierSeed.writeUInt16LE(feeTierBps);
     const [poolPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("pool"), wsolMint.toBuffer(), rushMint.toBuffer()],
+        [Buffer.from("pool"), tokenAMint.toBuffer(), tokenBMint.toBuffer(), feeTierSeed],
         program.programId
     );
     console.log("Pool PDA:", poolPda.toString());
 
+    const [dexConfig] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("dex_config")],
+        program.programId
+    );
+
+    // New pools are appended to the current on-chain registry page (32 pools per page)
+    const [poolRegistry] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool_registry")],
+        program.programId
+    );
+    const registry = await program.account.poolRegistry.fetch(poolRegistry);
+    const pageSeed = Buffer.alloc(4);
+    pageSeed.writeUInt32LE(Math.floor(registry.poolCount.toNumber() / 32));
+    const [registryPage] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("registry_page"), pageSeed],
+        program.programId
+    );
+
     // 4. Create Vault Keypairs
     const tokenAVault = anchor.web3.Keypair.generate();
     const tokenBVault = anchor.web3.Keypair.generate();
@@ -97,30 +121,53 @@ async function main() {
     // 6c. User LP Token Account (ATA)
     const userLpTokenAccount = await getAssociatedTokenAddress(
         lpTokenMint,
-        provider.wallet.publicKey
+        provider.wallet.publicKey,
+        false,
+        TOKEN_2022_PROGRAM_ID
     );
     console.log("User LP Token Account:", userLpTokenAccount.toString());
 
     // 7. Initialize Pool
-    const initialDepositA = new anchor.BN(10000000); // 0.01 SOL
-    const initialDepositB = new anchor.BN(100000000); // 100 RUSH
+    const wsolDeposit = new anchor.BN(10000000); // 0.01 SOL
+    const rushDeposit = new anchor.BN(100000000); // 100 RUSH
+    const [initialDepositA, initialDepositB] = wsolIsTokenA
+        ? [wsolDeposit, rushDeposit]
+        : [rushDeposit, wsolDeposit];
+    const [symbolA, symbolB] = wsolIsTokenA ? ["SOL", "RUSH"] : ["RUSH", "SOL"];
+    const [userTokenA, userTokenB] = wsolIsTokenA
+        ? [userWsolAccount.address, userRushAccount.address]
+        : [userRushAccount.address, userWsolAccount.address];
 
     try {
         const ix = await program.methods
-            .initializePool(initialDepositA, initialDepositB)
+            .initializePool(
+                initialDepositA,
+                initialDepositB,
+                feeTierBps,
+                symbolA,
+                symbolB,
+                { constantProduct: {} },
+                new anchor.BN(0),
+                0
+            )
             .accounts({
                 pool: poolPda,
-                tokenAMint: wsolMint,
-                tokenBMint: rushMint,
+                dexConfig,
+                poolRegistry,
+                registryPage,
+                tokenAMint,
+                tokenBMint,
                 lpTokenMint: lpTokenMint,
                 tokenAVault: tokenAVault.publicKey,
                 tokenBVault: tokenBVault.publicKey,
-                userTokenA: userWsolAccount.address,
-                userTokenB: userRushAccount.address,
+                userTokenA,
+                userTokenB,
                 lpTokenAccount: userLpTokenAccount,
                 authority: provider.wallet.publicKey,
                 associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-                tokenProgram: TOKEN_PROGRAM_ID,
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+                tokenAProgram: TOKEN_PROGRAM_ID,
+                tokenBProgram: TOKEN_PROGRAM_ID,
                 systemProgram: SystemProgram.programId,
                 rent: SYSVAR_RENT_PUBKEY,
             } as any)
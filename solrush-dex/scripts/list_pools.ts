@@ -0,0 +1,46 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolrushDex } from "../target/types/solrush_dex";
+import { PublicKey } from "@solana/web3.js";
+
+// Enumerates every pool the program has created by walking the on-chain registry pages.
+async function main() {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.SolrushDex as Program<SolrushDex>;
+
+    const [poolRegistry] = PublicKey.findProgramAddressSync(
+        [Buffer.from("pool_registry")],
+        program.programId
+    );
+    const registry = await program.account.poolRegistry.fetch(poolRegistry);
+
+    console.log("Pool Registry:", poolRegistry.toString());
+    console.log(`Pools: ${registry.poolCount.toString()} | Pages: ${registry.pageCount}\n`);
+
+    for (let pageIndex = 0; pageIndex < registry.pageCount; pageIndex++) {
+        const pageSeed = Buffer.alloc(4);
+        pageSeed.writeUInt32LE(pageIndex);
+        const [registryPage] = PublicKey.findProgramAddressSync(
+            [Buffer.from("registry_page"), pageSeed],
+            program.programId
+        );
+
+        const page = await program.account.poolRegistryPage.fetch(registryPage);
+        page.entries.forEach((entry, i) => {
+            console.log(
+                `#${pageIndex * 32 + i} ${entry.pool.toString()} | ` +
+                `A=${entry.tokenAMint.toString()} | B=${entry.tokenBMint.toString()} | ` +
+                `Fee=${entry.feeTierBps} bps | Created=${new Date(entry.createdAt.toNumber() * 1000).toISOString()}`
+            );
+        });
+    }
+}
+
+main()
+    .then(() => process.exit(0))
+    .catch((error) => {
+        console.error("Error:", error);
+        process.exit(1);
+    });
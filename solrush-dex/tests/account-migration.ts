@@ -0,0 +1,391 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { createAccount, mintTo } from "@solana/spl-token";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { assert } from "chai";
+import { createHash } from "crypto";
+import * as fs from "fs";
+import * as path from "path";
+
+import { SolrushDex } from "../target/types/solrush_dex";
+import {
+  POOLS_PER_REGISTRY_PAGE,
+  dexConfigPda,
+  ensureDexConfig,
+  expectError,
+  getEvents,
+  getTokenBalance,
+  poolRegistryPda,
+  registryPagePda,
+  swapAccounts,
+} from "./helpers/pool";
+
+// Mirrors state::ACCOUNT_VERSION
+const ACCOUNT_VERSION = 1;
+
+// Mirrors LiquidityPool::LEGACY_FEE_TIER_BPS and FEE_DENOMINATOR_BPS
+const LEGACY_FEE_TIER_BPS = 30;
+const FEE_DENOMINATOR_BPS = 10_000;
+
+type FieldKind = "pubkey" | "u64" | "i64" | "u8" | "bool";
+
+// Field order of each account before versioning, as serialized by Borsh
+const LEGACY_LAYOUTS: Record<string, [string, FieldKind][]> = {
+  LiquidityPool: [
+    ["authority", "pubkey"],
+    ["tokenAMint", "pubkey"],
+    ["tokenBMint", "pubkey"],
+    ["tokenAVault", "pubkey"],
+    ["tokenBVault", "pubkey"],
+    ["lpTokenMint", "pubkey"],
+    ["reserveA", "u64"],
+    ["reserveB", "u64"],
+    ["totalLpSupply", "u64"],
+    ["feeNumerator", "u64"],
+    ["feeDenominator", "u64"],
+    ["bump", "u8"],
+  ],
+  UserLiquidityPosition: [
+    ["owner", "pubkey"],
+    ["pool", "pubkey"],
+    ["lpTokens", "u64"],
+    ["depositTimestamp", "i64"],
+    ["lastClaimTimestamp", "i64"],
+    ["totalRushClaimed", "u64"],
+    ["bump", "u8"],
+  ],
+  LimitOrder: [
+    ["owner", "pubkey"],
+    ["pool", "pubkey"],
+    ["sellToken", "pubkey"],
+    ["buyToken", "pubkey"],
+    ["sellAmount", "u64"],
+    ["targetPrice", "u64"],
+    ["minimumReceive", "u64"],
+    ["createdAt", "i64"],
+    ["expiresAt", "i64"],
+    ["status", "u8"],
+    ["bump", "u8"],
+  ],
+  RushConfig: [
+    ["mint", "pubkey"],
+    ["authority", "pubkey"],
+    ["totalSupply", "u64"],
+    ["mintedSoFar", "u64"],
+    ["rewardsPerSecond", "u64"],
+    ["apyNumerator", "u64"],
+    ["apyDenominator", "u64"],
+    ["startTimestamp", "i64"],
+    ["isPaused", "bool"],
+    ["bump", "u8"],
+  ],
+};
+
+// Mirrors the LEGACY_SIZE / SIZE constants in state.rs
+const SIZES: Record<string, { legacy: number; current: number }> = {
+  LiquidityPool: { legacy: 241, current: 518 },
+  UserLiquidityPosition: { legacy: 105, current: 234 },
+  LimitOrder: { legacy: 194, current: 259 },
+  RushConfig: { legacy: 122, current: 251 },
+};
+
+const FIELD_SIZES: Record<FieldKind, number> = { pubkey: 32, u64: 8, i64: 8, u8: 1, bool: 1 };
+
+interface LegacyFixture {
+  type: string;
+  legacySize: number;
+  address: string;
+  fields: Record<string, string | number | boolean>;
+  data: string;
+}
+
+// Loaded into the validator through the [[test.validator.account]] entries in Anchor.toml
+const FIXTURES_DIR = path.join(__dirname, "fixtures");
+const fixtureFile = JSON.parse(fs.readFileSync(path.join(FIXTURES_DIR, "legacy-accounts.json"), "utf-8"));
+const fixtures: LegacyFixture[] = fixtureFile.accounts;
+const fixture = (type: string) => fixtures.find((f) => f.type === type)!;
+
+function accountDiscriminator(name: string): Buffer {
+  return createHash("sha256").update(`account:${name}`).digest().subarray(0, 8);
+}
+
+// Decode the legacy fields; returns the values and the offset just past the last field
+function decodeLegacy(type: string, data: Buffer): { fields: Record<string, string | number | boolean>; end: number } {
+  const fields: Record<string, string | number | boolean> = {};
+  let offset = 8;
+  for (const [name, kind] of LEGACY_LAYOUTS[type]) {
+    const raw = data.subarray(offset, offset + FIELD_SIZES[kind]);
+    switch (kind) {
+      case "pubkey":
+        fields[name] = new PublicKey(raw).toBase58();
+        break;
+      case "u64":
+      case "i64":
+        fields[name] = new BN(raw, "le").toString();
+        break;
+      case "u8":
+        fields[name] = raw[0];
+        break;
+      case "bool":
+        fields[name] = raw[0] !== 0;
+        break;
+    }
+    offset += FIELD_SIZES[kind];
+  }
+  return { fields, end: offset };
+}
+
+describe("Module 5.4 - Account Versioning & Migration", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.SolrushDex as Program<SolrushDex>;
+  const connection = provider.connection;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+
+  const legacyPool = new PublicKey(fixture("LiquidityPool").address);
+
+  // migrate_account signed by `authority`; the current registry page is only written for pools
+  async function migrateAccount(account: PublicKey, authority: Keypair = payer) {
+    const poolRegistry = poolRegistryPda(program);
+    const registry = await program.account.poolRegistry.fetch(poolRegistry);
+    const registryPage = registryPagePda(
+      program,
+      Math.floor(registry.poolCount.toNumber() / POOLS_PER_REGISTRY_PAGE)
+    );
+
+    return program.methods
+      .migrateAccount()
+      .accountsPartial({
+        dexConfig: dexConfigPda(program),
+        account,
+        poolRegistry,
+        registryPage,
+        authority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers(authority === payer ? [] : [authority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function accountData(address: PublicKey): Promise<Buffer> {
+    const info = await connection.getAccountInfo(address, "confirmed");
+    assert.exists(info, `${address.toBase58()} should be loaded`);
+    return info!.data;
+  }
+
+  before(async () => {
+    await ensureDexConfig(program);
+  });
+
+  // Test 1: The validator holds the fixtures exactly as the previous program wrote them
+  it("Test 1: Legacy fixtures are loaded with their pre-versioning layouts", async () => {
+    assert.sameMembers(
+      fixtures.map((f) => f.type),
+      Object.keys(LEGACY_LAYOUTS),
+      "Every versioned account should have a legacy fixture"
+    );
+
+    for (const f of fixtures) {
+      const info = await connection.getAccountInfo(new PublicKey(f.address));
+      assert.exists(info, `${f.type} fixture is loaded`);
+      assert.isTrue(info!.owner.equals(program.programId), `${f.type} is owned by the program`);
+
+      const data = info!.data;
+      assert.equal(data.length, SIZES[f.type].legacy, `${f.type} legacy size`);
+      assert.equal(f.legacySize, SIZES[f.type].legacy, `${f.type} fixture size`);
+      assert.isTrue(data.equals(Buffer.from(f.data, "base64")), `${f.type} bytes match the fixture`);
+      assert.isTrue(data.subarray(0, 8).equals(accountDiscriminator(f.type)), `${f.type} discriminator`);
+      assert.deepEqual(decodeLegacy(f.type, data).fields, f.fields, `${f.type} fields`);
+    }
+  });
+
+  // Test 2: Positions, orders and RUSH configs grow in place, keep their bytes and report version 1
+  it("Test 2: Migrated accounts keep their fields and report version 1", async () => {
+    for (const type of ["UserLiquidityPosition", "LimitOrder", "RushConfig"]) {
+      const f = fixture(type);
+      const address = new PublicKey(f.address);
+      const legacy = Buffer.from(f.data, "base64");
+
+      const signature = await migrateAccount(address);
+
+      const migrated = await accountData(address);
+      assert.equal(migrated.length, SIZES[type].current, `${type} grows to SIZE`);
+
+      const { fields, end } = decodeLegacy(type, migrated);
+      assert.isTrue(migrated.subarray(0, end).equals(legacy.subarray(0, end)), `${type} bytes unchanged`);
+      assert.deepEqual(fields, f.fields, `${type} fields survive migration`);
+      assert.equal(migrated[end], ACCOUNT_VERSION, `${type} version`);
+      assert.isTrue(migrated.subarray(end + 1).every((b) => b === 0), `${type} new fields are zeroed`);
+
+      const event = (await getEvents(program, signature)).find((e) => e.name === "accountMigrated");
+      assert.exists(event, `${type} emits AccountMigrated`);
+      assert.equal(event!.data.accountType, type);
+      assert.equal(event!.data.previousSize, SIZES[type].legacy);
+      assert.equal(event!.data.newSize, SIZES[type].current);
+      assert.equal(event!.data.version, ACCOUNT_VERSION);
+      assert.isTrue(event!.data.migratedBy.equals(payer.publicKey));
+    }
+
+    const position = await program.account.userLiquidityPosition.fetch(
+      new PublicKey(fixture("UserLiquidityPosition").address)
+    );
+    assert.equal(position.version, ACCOUNT_VERSION);
+    assert.equal(position.stakedLp.toNumber(), 0, "Staking starts empty");
+
+    const order = await program.account.limitOrder.fetch(new PublicKey(fixture("LimitOrder").address));
+    assert.equal(order.version, ACCOUNT_VERSION);
+    assert.deepEqual(order.status, { pending: {} });
+
+    const config = await program.account.rushConfig.fetch(new PublicKey(fixture("RushConfig").address));
+    assert.equal(config.version, ACCOUNT_VERSION);
+    assert.isTrue(config.pendingAuthority.equals(PublicKey.default), "No authority transfer pending");
+  });
+
+  // Test 3: LimitOrder's legacy space had 16 unused bytes, so the version byte lands inside it
+  it("Test 3: Version byte follows the last serialized field, not LEGACY_SIZE", async () => {
+    const migrated = await accountData(new PublicKey(fixture("LimitOrder").address));
+    const { end } = decodeLegacy("LimitOrder", migrated);
+
+    assert.equal(end, 178, "Serialized LimitOrder fields end at byte 178");
+    assert.equal(migrated[end], ACCOUNT_VERSION);
+    assert.equal(migrated[SIZES.LimitOrder.legacy], 0, "Byte at LEGACY_SIZE is padding, not the version");
+  });
+
+  // Test 4: The authority tops the grown accounts up to rent exemption at their new size
+  it("Test 4: Migrated accounts stay rent-exempt", async () => {
+    for (const type of ["UserLiquidityPosition", "LimitOrder", "RushConfig"]) {
+      const info = await connection.getAccountInfo(new PublicKey(fixture(type).address));
+      const minimum = await connection.getMinimumBalanceForRentExemption(SIZES[type].current);
+      assert.equal(info!.lamports, minimum, `${type} holds exactly the new minimum balance`);
+    }
+  });
+
+  // Test 5: Re-runs, wrong sizes, foreign accounts and non-admin signers are rejected
+  it("Test 5: Migration guards", async () => {
+    await expectError(
+      migrateAccount(new PublicKey(fixture("UserLiquidityPosition").address)),
+      "AccountAlreadyMigrated"
+    );
+    await expectError(migrateAccount(new PublicKey(fixtureFile.truncatedPosition)), "UnexpectedAccountSize");
+    await expectError(migrateAccount(poolRegistryPda(program)), "UnsupportedAccountType");
+    await expectError(migrateAccount(legacyPool, Keypair.generate()), "InvalidAuthority");
+  });
+
+  // Test 6: A legacy pool's bytes anywhere but its pre-fee-tier PDA are refused
+  it("Test 6: Legacy pools away from their PDA are rejected", async () => {
+    const stray = new PublicKey(fixtureFile.strayPool);
+    await expectError(migrateAccount(stray), "LegacyPoolNotMigratable");
+
+    const data = await accountData(stray);
+    assert.equal(data.length, SIZES.LiquidityPool.legacy, "Rejected pool is left untouched");
+  });
+
+  // Test 7: Legacy pools are rewritten at fee tier 30 and registered, keeping their PDA
+  it("Test 7: Legacy pool is rewritten in place and registered", async () => {
+    const f = fixture("LiquidityPool");
+    const registry = await program.account.poolRegistry.fetch(poolRegistryPda(program));
+    const poolIndex = registry.poolCount.toNumber();
+
+    const signature = await migrateAccount(legacyPool);
+
+    const info = await connection.getAccountInfo(legacyPool, "confirmed");
+    assert.equal(info!.data.length, SIZES.LiquidityPool.current, "Pool grows to SIZE");
+    assert.equal(
+      info!.lamports,
+      await connection.getMinimumBalanceForRentExemption(SIZES.LiquidityPool.current),
+      "Pool stays rent-exempt"
+    );
+
+    const pool = await program.account.liquidityPool.fetch(legacyPool);
+    for (const key of ["authority", "tokenAMint", "tokenBMint", "tokenAVault", "tokenBVault", "lpTokenMint"]) {
+      assert.equal((pool as any)[key].toBase58(), f.fields[key], `${key} carried over`);
+    }
+    assert.equal(pool.reserveA.toString(), f.fields.reserveA);
+    assert.equal(pool.reserveB.toString(), f.fields.reserveB);
+    assert.equal(pool.totalLpSupply.toString(), f.fields.totalLpSupply);
+    assert.equal(pool.bump, f.fields.bump, "Bump of the pre-fee-tier PDA");
+
+    // 3/1000 becomes the same 0.3% expressed in basis points
+    assert.equal(pool.feeTierBps, LEGACY_FEE_TIER_BPS);
+    assert.equal(pool.feeNumerator.toNumber(), LEGACY_FEE_TIER_BPS);
+    assert.equal(pool.feeDenominator.toNumber(), FEE_DENOMINATOR_BPS);
+    assert.isTrue(pool.legacyPda, "Pool keeps signing with its pre-fee-tier seeds");
+    assert.equal(pool.version, ACCOUNT_VERSION);
+    assert.deepEqual(pool.status, { active: {} });
+    assert.deepEqual(pool.poolType, { constantProduct: {} });
+    assert.isFalse(pool.locked);
+
+    const page = await program.account.poolRegistryPage.fetch(
+      registryPagePda(program, Math.floor(poolIndex / POOLS_PER_REGISTRY_PAGE))
+    );
+    const entry = page.entries[poolIndex % POOLS_PER_REGISTRY_PAGE];
+    assert.isTrue(entry.pool.equals(legacyPool), "Registry entry points at the legacy PDA");
+    assert.equal(entry.feeTierBps, LEGACY_FEE_TIER_BPS);
+    assert.equal(entry.closedAt.toNumber(), 0);
+
+    const events = await getEvents(program, signature);
+    const registered = events.find((e) => e.name === "poolRegistered");
+    assert.exists(registered, "Migrated pool emits PoolRegistered");
+    assert.isTrue(registered!.data.pool.equals(legacyPool));
+    assert.equal(registered!.data.poolIndex.toNumber(), poolIndex);
+    const migrated = events.find((e) => e.name === "accountMigrated");
+    assert.equal(migrated!.data.accountType, "LiquidityPool");
+
+    await expectError(migrateAccount(legacyPool), "AccountAlreadyMigrated");
+  });
+
+  // Test 8: The vaults still belong to the pre-fee-tier PDA, so swaps must sign with those seeds
+  it("Test 8: Migrated legacy pool swaps out of its original vaults", async () => {
+    const f = fixture("LiquidityPool");
+    const mintAuthority = Keypair.fromSecretKey(
+      Uint8Array.from(
+        JSON.parse(fs.readFileSync(path.join(FIXTURES_DIR, "legacy-accounts", "mint-authority.json"), "utf-8"))
+      )
+    );
+    assert.equal(mintAuthority.publicKey.toBase58(), fixtureFile.mintAuthority);
+
+    const mintA = new PublicKey(f.fields.tokenAMint as string);
+    const mintB = new PublicKey(f.fields.tokenBMint as string);
+    const vaultA = new PublicKey(f.fields.tokenAVault as string);
+    const vaultB = new PublicKey(f.fields.tokenBVault as string);
+
+    const amountIn = 1_000_000_000;
+    const tokenA = await createAccount(connection, payer, mintA, payer.publicKey, Keypair.generate());
+    const tokenB = await createAccount(connection, payer, mintB, payer.publicKey, Keypair.generate());
+    await mintTo(connection, payer, mintA, tokenA, mintAuthority, amountIn);
+
+    const before = await program.account.liquidityPool.fetch(legacyPool);
+    const vaultBBefore = await getTokenBalance(connection, vaultB);
+
+    const fixturePool = {
+      pool: legacyPool,
+      mintA,
+      mintB,
+      vaultA,
+      vaultB,
+      lpMint: new PublicKey(f.fields.lpTokenMint as string),
+      feeTierBps: LEGACY_FEE_TIER_BPS,
+    };
+    await program.methods
+      .swap(new BN(amountIn), new BN(1), true, null)
+      .accountsPartial(swapAccounts(fixturePool, payer.publicKey, tokenA, tokenB, true))
+      .rpc({ commitment: "confirmed" });
+
+    // 0.3% fee, the same price the pool quoted before migration
+    const amountInWithFee = BigInt(amountIn) * BigInt(FEE_DENOMINATOR_BPS - LEGACY_FEE_TIER_BPS);
+    const expectedOut = Number(
+      (BigInt(before.reserveB.toString()) * amountInWithFee) /
+        (BigInt(before.reserveA.toString()) * BigInt(FEE_DENOMINATOR_BPS) + amountInWithFee)
+    );
+
+    const received = await getTokenBalance(connection, tokenB);
+    assert.approximately(received, expectedOut, 1, "Output priced at 0.3%");
+    assert.equal(await getTokenBalance(connection, vaultB), vaultBBefore - received, "Paid from the legacy vault");
+    assert.equal(await getTokenBalance(connection, vaultA), Number(f.fields.reserveA) + amountIn);
+
+    const after = await program.account.liquidityPool.fetch(legacyPool);
+    assert.equal(after.reserveA.toNumber(), before.reserveA.toNumber() + amountIn);
+    assert.equal(after.reserveB.toNumber(), before.reserveB.toNumber() - received);
+    assert.equal(await getTokenBalance(connection, tokenA), 0);
+  });
+});
@@ -0,0 +1,344 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createAccount,
+  createMint,
+  getAssociatedTokenAddressSync,
+  mintTo,
+} from "@solana/spl-token";
+import { Keypair, PublicKey, SYSVAR_RENT_PUBKEY, SystemProgram } from "@solana/web3.js";
+
+import { SolrushDex } from "../target/types/solrush_dex";
+import {
+  PoolFixture,
+  UserFixture,
+  addLiquidity,
+  createFundedUser,
+  createMintPair,
+  createPool,
+  ensureRushConfig,
+  expectError,
+  limitOrderPda,
+  positionPda,
+  rushConfigPda,
+  swapAccounts,
+  withdrawAccounts,
+} from "./helpers/pool";
+
+// ============================================================================
+// TEST SUITE: FOREIGN ACCOUNT SUBSTITUTION
+// ============================================================================
+//
+// Every case sends a real transaction with one account swapped for a valid
+// account that belongs to another pool, position, order or owner. Anchor checks
+// seeds before has_one and raw constraints, so a PDA of the wrong pool or owner
+// fails on ConstraintSeeds before any custom error is reached.
+
+describe("Account Validation - Foreign Account Substitution", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SolrushDex as Program<SolrushDex>;
+  const connection = provider.connection;
+  const payer = provider.wallet as anchor.Wallet;
+
+  const RESERVE_A = 1_000_000_000;
+  const RESERVE_B = 5_000_000_000;
+
+  // Two pools seeded by the provider wallet, which keeps a matching balance for deposits
+  let pool: PoolFixture;
+  let other: PoolFixture;
+  let creator: UserFixture;
+  let creatorOnOther: UserFixture;
+  // Holds a position and a pending limit order on `pool`
+  let victim: UserFixture;
+  // Mint outside either pool, with a provider-owned account
+  let foreignMint: PublicKey;
+  let foreignTokenAccount: PublicKey;
+
+  async function createCreatorPool(): Promise<{ pool: PoolFixture; creator: UserFixture }> {
+    const [mintA, mintB] = await createMintPair(connection, payer.payer);
+    const tokenA = await createAccount(connection, payer.payer, mintA, payer.publicKey);
+    const tokenB = await createAccount(connection, payer.payer, mintB, payer.publicKey);
+    await mintTo(connection, payer.payer, mintA, tokenA, payer.payer, 2 * RESERVE_A);
+    await mintTo(connection, payer.payer, mintB, tokenB, payer.payer, 2 * RESERVE_B);
+
+    const fixture = await createPool(program, {
+      mintA,
+      mintB,
+      userTokenA: tokenA,
+      userTokenB: tokenB,
+      amountA: RESERVE_A,
+      amountB: RESERVE_B,
+    });
+    return { pool: fixture, creator: { user: payer.payer, tokenA, tokenB } };
+  }
+
+  before(async () => {
+    ({ pool, creator } = await createCreatorPool());
+    ({ pool: other, creator: creatorOnOther } = await createCreatorPool());
+
+    // Positions for the provider wallet on both pools, and for the victim on `pool`
+    await addLiquidity(program, pool, creator, RESERVE_A / 10, RESERVE_B / 10);
+    await addLiquidity(program, other, creatorOnOther, RESERVE_A / 10, RESERVE_B / 10);
+    victim = await createFundedUser(connection, payer.payer, pool.mintA, pool.mintB, RESERVE_A, RESERVE_B);
+    await addLiquidity(program, pool, victim, RESERVE_A / 10, RESERVE_B / 10);
+
+    foreignMint = await createMint(connection, payer.payer, payer.publicKey, null, 6);
+    foreignTokenAccount = await createAccount(connection, payer.payer, foreignMint, payer.publicKey);
+  });
+
+  // =========================================================================
+  // SWAPS
+  // =========================================================================
+
+  describe("Swaps", () => {
+    const swap = (accounts: ReturnType<typeof swapAccounts>, isAToB: boolean = true) =>
+      program.methods
+        .swap(new anchor.BN(1_000_000), new anchor.BN(0), isAToB, null)
+        .accountsPartial(accounts)
+        .rpc();
+
+    const honest = () => swapAccounts(pool, payer.publicKey, creator.tokenA, creator.tokenB, true);
+
+    it("Rejects another pool's vaults", async () => {
+      await expectError(swap({ ...honest(), poolVaultOut: other.vaultB }), "InvalidVault");
+      await expectError(swap({ ...honest(), poolVaultIn: other.vaultA }), "InvalidVault");
+    });
+
+    it("Rejects the right vaults in the wrong direction", async () => {
+      await expectError(swap(honest(), false), "InvalidVault");
+    });
+
+    it("Rejects this pool's vaults behind another pool", async () => {
+      await expectError(swap({ ...honest(), pool: other.pool }), "InvalidVault");
+    });
+
+    it("Rejects a mint outside the pool", async () => {
+      await expectError(
+        swap({ ...honest(), userTokenOut: foreignTokenAccount, tokenOutMint: foreignMint }),
+        "InvalidTokenMint"
+      );
+    });
+  });
+
+  // =========================================================================
+  // LIQUIDITY POSITIONS
+  // =========================================================================
+
+  describe("Liquidity Positions", () => {
+    const removeLiquidity = (accounts: ReturnType<typeof withdrawAccounts>) =>
+      program.methods
+        .removeLiquidity(new anchor.BN(1_000), new anchor.BN(0), new anchor.BN(0), null)
+        .accountsPartial(accounts)
+        .rpc();
+
+    it("Rejects another LP's position", async () => {
+      await expectError(
+        removeLiquidity({
+          ...withdrawAccounts(program, pool, creator),
+          userPosition: positionPda(program, pool.pool, victim.user.publicKey),
+        }),
+        "ConstraintSeeds"
+      );
+    });
+
+    it("Rejects the signer's own position on another pool", async () => {
+      await expectError(
+        removeLiquidity({
+          ...withdrawAccounts(program, pool, creator),
+          userPosition: positionPda(program, other.pool, payer.publicKey),
+        }),
+        "ConstraintSeeds"
+      );
+    });
+
+    it("Rejects another pool's vaults", async () => {
+      await expectError(
+        removeLiquidity({ ...withdrawAccounts(program, pool, creator), tokenAVault: other.vaultA }),
+        "InvalidVault"
+      );
+    });
+  });
+
+  // =========================================================================
+  // LIMIT ORDERS
+  // =========================================================================
+
+  describe("Limit Orders", () => {
+    const SELL_AMOUNT = 1_000_000;
+
+    // Sell-A order on `pool`; `userTokenOut` and `sellTokenMint` can be substituted
+    const createSellOrder = (
+      owner: UserFixture,
+      overrides: { sellTokenMint?: PublicKey; userTokenOut?: PublicKey } = {}
+    ) => {
+      const orderVault = Keypair.generate();
+      const signers = owner.user === payer.payer ? [orderVault] : [owner.user, orderVault];
+      return {
+        orderVault: orderVault.publicKey,
+        rpc: () =>
+          program.methods
+            // Unreachable target keeps the order pending
+            .createLimitOrder(
+              new anchor.BN(SELL_AMOUNT),
+              new anchor.BN(1_000_000_000_000),
+              new anchor.BN(1),
+              new anchor.BN(30)
+            )
+            .accountsPartial({
+              pool: pool.pool,
+              limitOrder: limitOrderPda(program, pool.pool, owner.user.publicKey),
+              sellTokenMint: overrides.sellTokenMint ?? pool.mintA,
+              userTokenIn: owner.tokenA,
+              userTokenOut: overrides.userTokenOut ?? owner.tokenB,
+              orderVault: orderVault.publicKey,
+              user: owner.user.publicKey,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              systemProgram: SystemProgram.programId,
+              rent: SYSVAR_RENT_PUBKEY,
+            })
+            .signers(signers)
+            .rpc(),
+      };
+    };
+
+    let victimOrder: { limitOrder: PublicKey; orderVault: PublicKey };
+
+    // Fills are permissionless, so the keeper (provider wallet) picks every account
+    const executeAccounts = () => ({
+      pool: pool.pool,
+      limitOrder: victimOrder.limitOrder,
+      orderVault: victimOrder.orderVault,
+      userTokenOut: victim.tokenB,
+      poolVaultIn: pool.vaultA,
+      poolVaultOut: pool.vaultB,
+      sellTokenMint: pool.mintA,
+      buyTokenMint: pool.mintB,
+      sellTokenProgram: TOKEN_PROGRAM_ID,
+      buyTokenProgram: TOKEN_PROGRAM_ID,
+    });
+    const execute = (accounts: ReturnType<typeof executeAccounts>) =>
+      program.methods.executeLimitOrder().accountsPartial(accounts).rpc();
+
+    before(async () => {
+      const order = createSellOrder(victim);
+      await order.rpc();
+      victimOrder = {
+        limitOrder: limitOrderPda(program, pool.pool, victim.user.publicKey),
+        orderVault: order.orderVault,
+      };
+    });
+
+    it("Creation rejects a sell mint outside the pool", async () => {
+      await expectError(createSellOrder(creator, { sellTokenMint: foreignMint }).rpc(), "InvalidTokenMint");
+    });
+
+    it("Creation rejects an output account that does not hold the other side", async () => {
+      await expectError(
+        createSellOrder(creator, { userTokenOut: foreignTokenAccount }).rpc(),
+        "InvalidTokenMint"
+      );
+    });
+
+    it("Execution pays proceeds only to the order owner", async () => {
+      await expectError(
+        execute({ ...executeAccounts(), userTokenOut: creator.tokenB }),
+        "UnauthorizedOrderOwner"
+      );
+    });
+
+    it("Execution rejects another pool's vaults", async () => {
+      await expectError(execute({ ...executeAccounts(), poolVaultOut: other.vaultB }), "InvalidVault");
+    });
+
+    it("Execution rejects an order placed on another pool", async () => {
+      await expectError(execute({ ...executeAccounts(), pool: other.pool }), "ConstraintSeeds");
+    });
+
+    it("Only the owner can cancel", async () => {
+      await expectError(
+        program.methods
+          .cancelLimitOrder()
+          .accountsPartial({
+            limitOrder: victimOrder.limitOrder,
+            orderVault: victimOrder.orderVault,
+            userTokenIn: creator.tokenA,
+            sellTokenMint: pool.mintA,
+            user: payer.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc(),
+        "ConstraintSeeds"
+      );
+    });
+  });
+
+  // =========================================================================
+  // RUSH REWARDS
+  // =========================================================================
+
+  describe("RUSH Rewards", () => {
+    let rushMint: PublicKey;
+
+    const claim = (overrides: { position?: PublicKey; rushMint?: PublicKey }) =>
+      program.methods
+        .claimRushRewards()
+        .accountsPartial({
+          position: overrides.position ?? positionPda(program, pool.pool, payer.publicKey),
+          pool: pool.pool,
+          rushConfig: rushConfigPda(program),
+          rushMint: overrides.rushMint ?? rushMint,
+          userRushAccount: getAssociatedTokenAddressSync(rushMint, payer.publicKey, false, TOKEN_2022_PROGRAM_ID),
+          user: payer.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    before(async () => {
+      rushMint = await ensureRushConfig(program);
+    });
+
+    it("Claims reject another LP's position", async () => {
+      await expectError(
+        claim({ position: positionPda(program, pool.pool, victim.user.publicKey) }),
+        "ConstraintSeeds"
+      );
+    });
+
+    it("Claims reject the signer's own position on another pool", async () => {
+      await expectError(
+        claim({ position: positionPda(program, other.pool, payer.publicKey) }),
+        "ConstraintSeeds"
+      );
+    });
+
+    it("Claims reject a mint other than RUSH", async () => {
+      await expectError(claim({ rushMint: pool.mintA }), "InvalidRushMint");
+    });
+
+    it("APY and pause updates require the config authority", async () => {
+      const rushConfig = rushConfigPda(program);
+
+      await expectError(
+        program.methods
+          .updateRushApy(new anchor.BN(10))
+          .accountsPartial({ rushConfig, authority: victim.user.publicKey })
+          .signers([victim.user])
+          .rpc(),
+        "InvalidAuthority"
+      );
+      await expectError(
+        program.methods
+          .pauseRushRewards()
+          .accountsPartial({ rushConfig, authority: victim.user.publicKey })
+          .signers([victim.user])
+          .rpc(),
+        "InvalidAuthority"
+      );
+    });
+  });
+});
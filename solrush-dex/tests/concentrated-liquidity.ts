@@ -0,0 +1,342 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  AccountMeta,
+  ComputeBudgetProgram,
+  Keypair,
+  PublicKey,
+  SYSVAR_RENT_PUBKEY,
+  SystemProgram,
+} from "@solana/web3.js";
+import { assert } from "chai";
+
+import { SolrushDex } from "../target/types/solrush_dex";
+import {
+  DEFAULT_FEE_TIER_BPS,
+  POOLS_PER_REGISTRY_PAGE,
+  UserFixture,
+  concentratedPositionPda,
+  createFundedUser,
+  createMintPair,
+  dexConfigPda,
+  ensureDexConfig,
+  expectError,
+  getEvents,
+  getTokenBalance,
+  poolPda,
+  poolRegistryPda,
+  registryPagePda,
+  tickArrayPda,
+} from "./helpers/pool";
+
+// Mirrors state::TICK_ARRAY_SIZE
+const TICK_ARRAY_SIZE = 16;
+
+// Tick 0: a price of 1.0 as a Q64.64 sqrt price
+const SQRT_PRICE_ONE = new anchor.BN(1).shln(64);
+
+const U64_MAX = new anchor.BN("18446744073709551615");
+
+describe("Module 2.13 - Concentrated Liquidity", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SolrushDex as Program<SolrushDex>;
+  const connection = provider.connection;
+  const payer = provider.wallet as anchor.Wallet;
+
+  const TICK_SPACING = 10;
+  const SPAN = TICK_SPACING * TICK_ARRAY_SIZE;
+  const WIDE_LIQUIDITY = 1_000_000_000_000;
+  const UPPER_LIQUIDITY = 2_000_000_000_000;
+  const SWAP_AMOUNT_B = 8_000_000_000;
+
+  let mintA: PublicKey;
+  let mintB: PublicKey;
+  let pool: PublicKey;
+  let vaultA: PublicKey;
+  let vaultB: PublicKey;
+  let wideOwner: UserFixture;
+  let upperOwner: UserFixture;
+  let trader: UserFixture;
+  let swapFeeB: number;
+
+  // Positions: [-100, 100) around the initial price and [100, 200) just above it
+  const wide = { lower: -100, upper: 100 };
+  const upper = { lower: 100, upper: 200 };
+
+  const tickArray = (startTickIndex: number) => tickArrayPda(program, pool, startTickIndex);
+  const arrayStart = (tick: number) => Math.floor(tick / SPAN) * SPAN;
+  const positionOf = (owner: UserFixture, range: { lower: number; upper: number }) =>
+    concentratedPositionPda(program, pool, owner.user.publicKey, range.lower, range.upper);
+
+  const initializeTickArray = (startTickIndex: number) =>
+    program.methods
+      .initializeTickArray(startTickIndex)
+      .accountsPartial({
+        pool,
+        tickArray: tickArray(startTickIndex),
+        payer: payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  const openPosition = (owner: UserFixture, tickLower: number, tickUpper: number) =>
+    program.methods
+      .openConcentratedPosition(tickLower, tickUpper)
+      .accountsPartial({
+        pool,
+        position: concentratedPositionPda(program, pool, owner.user.publicKey, tickLower, tickUpper),
+        owner: owner.user.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner.user])
+      .rpc();
+
+  // Accounts shared by increase/decrease liquidity and fee collection
+  const modifyAccounts = (
+    owner: UserFixture,
+    range: { lower: number; upper: number },
+    tickArrayLower: PublicKey = tickArray(arrayStart(range.lower)),
+    tickArrayUpper: PublicKey = tickArray(arrayStart(range.upper))
+  ) => ({
+    pool,
+    position: positionOf(owner, range),
+    tickArrayLower,
+    tickArrayUpper,
+    tokenAMint: mintA,
+    tokenBMint: mintB,
+    tokenAVault: vaultA,
+    tokenBVault: vaultB,
+    userTokenA: owner.tokenA,
+    userTokenB: owner.tokenB,
+    owner: owner.user.publicKey,
+    tokenAProgram: TOKEN_PROGRAM_ID,
+    tokenBProgram: TOKEN_PROGRAM_ID,
+  });
+
+  const increaseLiquidity = (owner: UserFixture, range: { lower: number; upper: number }, liquidity: number) =>
+    program.methods
+      .increaseConcentratedLiquidity(new anchor.BN(liquidity), U64_MAX, U64_MAX)
+      .accountsPartial(modifyAccounts(owner, range))
+      .signers([owner.user])
+      .rpc({ commitment: "confirmed" });
+
+  const collectFees = (owner: UserFixture, range: { lower: number; upper: number }) =>
+    program.methods
+      .collectConcentratedFees()
+      .accountsPartial(modifyAccounts(owner, range))
+      .signers([owner.user])
+      .rpc({ commitment: "confirmed" });
+
+  // B -> A by default; tick arrays go in remaining accounts in the order the price walks them
+  const swap = (amountIn: number, tickArrays: PublicKey[], isAToB: boolean = false) =>
+    program.methods
+      .swapConcentrated(new anchor.BN(amountIn), new anchor.BN(0), isAToB)
+      .accountsPartial({
+        pool,
+        tokenAMint: mintA,
+        tokenBMint: mintB,
+        tokenAVault: vaultA,
+        tokenBVault: vaultB,
+        userTokenA: trader.tokenA,
+        userTokenB: trader.tokenB,
+        user: trader.user.publicKey,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        tickArrays.map((pubkey): AccountMeta => ({ pubkey, isSigner: false, isWritable: true }))
+      )
+      // The swap walks ticks with U256 math, which can outgrow the default budget
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .signers([trader.user])
+      .rpc({ commitment: "confirmed" });
+
+  const initializePool = async (tickSpacing: number) => {
+    const registry = await program.account.poolRegistry.fetch(poolRegistryPda(program));
+    const newVaultA = Keypair.generate();
+    const newVaultB = Keypair.generate();
+    await program.methods
+      .initializeConcentratedPool(DEFAULT_FEE_TIER_BPS, tickSpacing, SQRT_PRICE_ONE)
+      .accountsPartial({
+        pool: poolPda(program, mintA, mintB, DEFAULT_FEE_TIER_BPS),
+        dexConfig: dexConfigPda(program),
+        poolRegistry: poolRegistryPda(program),
+        registryPage: registryPagePda(
+          program,
+          Math.floor(registry.poolCount.toNumber() / POOLS_PER_REGISTRY_PAGE)
+        ),
+        tokenAMint: mintA,
+        tokenBMint: mintB,
+        tokenAVault: newVaultA.publicKey,
+        tokenBVault: newVaultB.publicKey,
+        authority: payer.publicKey,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([newVaultA, newVaultB])
+      .rpc();
+    return [newVaultA.publicKey, newVaultB.publicKey];
+  };
+
+  before(async () => {
+    await ensureDexConfig(program);
+    [mintA, mintB] = await createMintPair(connection, payer.payer);
+    pool = poolPda(program, mintA, mintB, DEFAULT_FEE_TIER_BPS);
+
+    wideOwner = await createFundedUser(connection, payer.payer, mintA, mintB, 100_000_000_000, 100_000_000_000);
+    upperOwner = await createFundedUser(connection, payer.payer, mintA, mintB, 100_000_000_000, 100_000_000_000);
+    trader = await createFundedUser(connection, payer.payer, mintA, mintB, 0, SWAP_AMOUNT_B);
+  });
+
+  it("Rejects a tick spacing outside [1, 1000]", async () => {
+    await expectError(initializePool(0), "InvalidTickSpacing");
+    await expectError(initializePool(1_001), "InvalidTickSpacing");
+  });
+
+  it("Initializes the pool at tick 0 and allocates its tick arrays", async () => {
+    [vaultA, vaultB] = await initializePool(TICK_SPACING);
+
+    const poolData = await program.account.liquidityPool.fetch(pool);
+    assert.deepEqual(poolData.poolType, { concentrated: {} });
+    assert.equal(poolData.tickSpacing, TICK_SPACING);
+    assert.equal(poolData.tickCurrentIndex, 0);
+    assert.isTrue(poolData.sqrtPriceX64.eq(SQRT_PRICE_ONE));
+    assert.isTrue(poolData.activeLiquidity.isZero());
+
+    for (const start of [-SPAN, 0, SPAN]) {
+      await initializeTickArray(start);
+      const array = await program.account.tickArray.fetch(tickArray(start));
+      assert.equal(array.startTickIndex, start);
+      assert.isTrue(array.pool.equals(pool));
+    }
+  });
+
+  it("Tick array starts must be aligned to the array span", async () => {
+    await expectError(initializeTickArray(TICK_SPACING), "InvalidTickIndex");
+    await expectError(initializeTickArray(-SPAN / 2), "InvalidTickIndex");
+  });
+
+  it("Position ranges are validated against the tick spacing", async () => {
+    await expectError(openPosition(wideOwner, 100, 100), "InvalidTickRange");
+    await expectError(openPosition(wideOwner, 100, -100), "InvalidTickRange");
+    await expectError(openPosition(wideOwner, -105, 100), "InvalidTickIndex");
+  });
+
+  it("Opens positions and deposits by where the price sits in the range", async () => {
+    await openPosition(wideOwner, wide.lower, wide.upper);
+    await openPosition(upperOwner, upper.lower, upper.upper);
+
+    const opened = await program.account.concentratedPosition.fetch(positionOf(wideOwner, wide));
+    assert.isTrue(opened.owner.equals(wideOwner.user.publicKey));
+    assert.equal(opened.tickLowerIndex, wide.lower);
+    assert.equal(opened.tickUpperIndex, wide.upper);
+    assert.isTrue(opened.liquidity.isZero());
+
+    // Price inside the range: both tokens, same amounts as get_amounts_for_liquidity
+    const wideEvent = (await getEvents(program, await increaseLiquidity(wideOwner, wide, WIDE_LIQUIDITY))).find(
+      (e) => e.name === "concentratedLiquidityIncreased"
+    );
+    assert.exists(wideEvent, "ConcentratedLiquidityIncreased emitted");
+    assert.equal(wideEvent!.data.amountA.toNumber(), 4_987_272_071);
+    assert.equal(wideEvent!.data.amountB.toNumber(), 4_987_272_071);
+    assert.equal(wideEvent!.data.activeLiquidity.toNumber(), WIDE_LIQUIDITY);
+
+    // Price below the range: token A only, and the range is not active yet
+    const upperEvent = (await getEvents(program, await increaseLiquidity(upperOwner, upper, UPPER_LIQUIDITY))).find(
+      (e) => e.name === "concentratedLiquidityIncreased"
+    );
+    assert.isTrue(upperEvent!.data.amountA.toNumber() > 0);
+    assert.equal(upperEvent!.data.amountB.toNumber(), 0);
+    assert.equal(upperEvent!.data.activeLiquidity.toNumber(), WIDE_LIQUIDITY);
+
+    const poolData = await program.account.liquidityPool.fetch(pool);
+    assert.equal(poolData.reserveA.toNumber(), 4_987_272_071 + upperEvent!.data.amountA.toNumber());
+    assert.equal(poolData.reserveB.toNumber(), 4_987_272_071);
+    assert.equal(await getTokenBalance(connection, vaultA), poolData.reserveA.toNumber());
+
+    const lowerArray = await program.account.tickArray.fetch(tickArray(0));
+    const tick100 = lowerArray.ticks[100 / TICK_SPACING];
+    assert.isTrue(tick100.initialized);
+    assert.equal(tick100.liquidityNet.toString(), (UPPER_LIQUIDITY - WIDE_LIQUIDITY).toString());
+    assert.equal(tick100.liquidityGross.toString(), (UPPER_LIQUIDITY + WIDE_LIQUIDITY).toString());
+  });
+
+  it("Liquidity changes reject tick arrays that do not hold the position's ticks", async () => {
+    const wrongArrays = modifyAccounts(wideOwner, wide, tickArray(0), tickArray(0));
+    await expectError(
+      program.methods
+        .increaseConcentratedLiquidity(new anchor.BN(1_000), new anchor.BN(1_000_000), new anchor.BN(1_000_000))
+        .accountsPartial(wrongArrays)
+        .signers([wideOwner.user])
+        .rpc(),
+      "InvalidTickArray"
+    );
+  });
+
+  it("Swaps reject missing, misplaced and non-contiguous tick arrays", async () => {
+    await expectError(swap(1_000_000, []), "TickArrayMissing");
+    // B to A searches upward from tick 10, which lives in the array at 0
+    await expectError(swap(1_000_000, [tickArray(SPAN)]), "InvalidTickArray");
+    await expectError(swap(1_000_000, [tickArray(0), tickArray(-SPAN)]), "InvalidTickArray");
+  });
+
+  it("A swap crosses tick 100 and hands the active liquidity to the upper range", async () => {
+    const traderABefore = await getTokenBalance(connection, trader.tokenA);
+    const signature = await swap(SWAP_AMOUNT_B, [tickArray(0), tickArray(SPAN)]);
+
+    const poolData = await program.account.liquidityPool.fetch(pool);
+    assert.isAtLeast(poolData.tickCurrentIndex, upper.lower, "Price moved into the upper range");
+    assert.isBelow(poolData.tickCurrentIndex, upper.upper);
+    assert.equal(poolData.activeLiquidity.toNumber(), UPPER_LIQUIDITY, "Crossing tick 100 swapped the active liquidity");
+    assert.isTrue(poolData.feeGrowthGlobalA.isZero());
+    assert.isTrue(poolData.feeGrowthGlobalB.gtn(0));
+
+    const event = (await getEvents(program, signature)).find((e) => e.name === "swapExecuted");
+    assert.exists(event, "SwapExecuted emitted");
+    assert.equal(event!.data.amountIn.toNumber(), SWAP_AMOUNT_B);
+    swapFeeB = event!.data.feeAmount.toNumber();
+    assert.equal(await getTokenBalance(connection, trader.tokenA), traderABefore + event!.data.amountOut.toNumber());
+    assert.equal(await getTokenBalance(connection, trader.tokenB), 0);
+
+    // The crossed tick's outside growth now holds what accrued below it
+    const crossed = (await program.account.tickArray.fetch(tickArray(0))).ticks[100 / TICK_SPACING];
+    assert.isTrue(crossed.feeGrowthOutsideB.gtn(0));
+    assert.isTrue(crossed.feeGrowthOutsideB.lt(poolData.feeGrowthGlobalB));
+  });
+
+  it("Positions collect only the fees earned while in range", async () => {
+    const poolBefore = await program.account.liquidityPool.fetch(pool);
+    const wideBefore = await getTokenBalance(connection, wideOwner.tokenB);
+    const wideEvent = (await getEvents(program, await collectFees(wideOwner, wide))).find(
+      (e) => e.name === "concentratedFeesCollected"
+    );
+    assert.exists(wideEvent, "ConcentratedFeesCollected emitted");
+    // The wide range was active only for the step up to tick 100, whose fee was 15_082_056
+    assert.equal(wideEvent!.data.feesA.toNumber(), 0);
+    assert.equal(wideEvent!.data.feesB.toNumber(), 15_082_055);
+    assert.equal(await getTokenBalance(connection, wideOwner.tokenB), wideBefore + 15_082_055);
+
+    const upperEvent = (await getEvents(program, await collectFees(upperOwner, upper))).find(
+      (e) => e.name === "concentratedFeesCollected"
+    );
+    const upperFees = upperEvent!.data.feesB.toNumber();
+    assert.isTrue(upperFees > 0);
+
+    // Fees split without overpaying: each position rounds down
+    const collected = 15_082_055 + upperFees;
+    assert.isAtMost(collected, swapFeeB);
+    assert.isAtMost(swapFeeB - collected, 2);
+
+    const poolAfter = await program.account.liquidityPool.fetch(pool);
+    assert.equal(poolAfter.reserveB.toNumber(), poolBefore.reserveB.toNumber() - collected);
+    assert.equal(await getTokenBalance(connection, vaultB), poolAfter.reserveB.toNumber());
+
+    // Nothing left to collect
+    await expectError(collectFees(wideOwner, wide), "InvalidAmount");
+  });
+});
@@ -0,0 +1,200 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID, createAccount, mintTo } from "@solana/spl-token";
+import { AccountMeta, Keypair, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+
+import { SolrushDex } from "../target/types/solrush_dex";
+import {
+  PoolFixture,
+  UserFixture,
+  createFundedUser,
+  createMintPair,
+  createPool,
+  expectError,
+  getEvents,
+  getTokenBalance,
+} from "./helpers/pool";
+
+// SPL Memo ships with every local validator: an executable receiver that never repays.
+// It only accepts UTF-8 data, so loan amounts and fees below keep every byte under 0x80
+const MEMO_PROGRAM_ID = new PublicKey("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+// Mirrors the first receiver_data byte understood by programs/flash-loan-receiver
+const RECEIVER_REPAY = 0;
+const RECEIVER_REENTER_SWAP = 1;
+
+// Mirrors state::MAX_FLASH_LOAN_FEE_BPS
+const MAX_FLASH_LOAN_FEE_BPS = 1_000;
+
+describe("Module 3.5 - Flash Loans", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SolrushDex as Program<SolrushDex>;
+  const receiver = anchor.workspace.FlashLoanReceiver as Program;
+  const connection = provider.connection;
+  const payer = provider.wallet as anchor.Wallet;
+
+  const RESERVE_A = 1_000_000_000;
+  const RESERVE_B = 5_000_000_000;
+  const LOAN_AMOUNT = 1_000_000;
+
+  let pool: PoolFixture;
+  let borrower: UserFixture;
+
+  const setFlashLoanFee = (feeBps: number, authority?: Keypair) =>
+    program.methods
+      .setFlashLoanFee(feeBps)
+      .accountsPartial({ pool: pool.pool, authority: (authority ?? payer.payer).publicKey })
+      .signers(authority ? [authority] : [])
+      .rpc({ commitment: "confirmed" });
+
+  // Borrow token A into the borrower's account
+  const flashLoan = (
+    amount: number,
+    receiverProgram: PublicKey = MEMO_PROGRAM_ID,
+    receiverData: Buffer = Buffer.alloc(0),
+    remainingAccounts: AccountMeta[] = []
+  ) =>
+    program.methods
+      .flashLoan(new anchor.BN(amount), true, receiverData)
+      .accountsPartial({
+        pool: pool.pool,
+        tokenAVault: pool.vaultA,
+        tokenBVault: pool.vaultB,
+        tokenMint: pool.mintA,
+        borrowerTokenAccount: borrower.tokenA,
+        receiverProgram,
+        borrower: borrower.user.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([borrower.user])
+      .rpc({ commitment: "confirmed" });
+
+  const writable = (pubkey: PublicKey): AccountMeta => ({ pubkey, isSigner: false, isWritable: true });
+  const readonly = (pubkey: PublicKey): AccountMeta => ({ pubkey, isSigner: false, isWritable: false });
+
+  before(async () => {
+    // The provider wallet seeds the pool and so becomes its authority
+    const [mintA, mintB] = await createMintPair(connection, payer.payer);
+    const tokenA = await createAccount(connection, payer.payer, mintA, payer.publicKey);
+    const tokenB = await createAccount(connection, payer.payer, mintB, payer.publicKey);
+    await mintTo(connection, payer.payer, mintA, tokenA, payer.payer, RESERVE_A);
+    await mintTo(connection, payer.payer, mintB, tokenB, payer.payer, RESERVE_B);
+    pool = await createPool(program, {
+      mintA,
+      mintB,
+      userTokenA: tokenA,
+      userTokenB: tokenB,
+      amountA: RESERVE_A,
+      amountB: RESERVE_B,
+    });
+
+    borrower = await createFundedUser(connection, payer.payer, mintA, mintB, 0, 0);
+  });
+
+  // The borrower's own tokens cover the fee the repaying receiver adds on top of the principal
+  const fundBorrowerFee = (fee: number) => mintTo(connection, payer.payer, pool.mintA, borrower.tokenA, payer.payer, fee);
+
+  it("Pools start with flash loans disabled", async () => {
+    await expectError(flashLoan(LOAN_AMOUNT), "FlashLoansDisabled");
+  });
+
+  it("Only the pool authority can set the fee", async () => {
+    await expectError(setFlashLoanFee(9, borrower.user), "InvalidAuthority");
+  });
+
+  it("Fee is capped at 10%", async () => {
+    await expectError(setFlashLoanFee(MAX_FLASH_LOAN_FEE_BPS + 1), "InvalidFlashLoanFee");
+
+    const signature = await setFlashLoanFee(MAX_FLASH_LOAN_FEE_BPS);
+    const event = (await getEvents(program, signature)).find((e) => e.name === "flashLoanFeeUpdated");
+    assert.exists(event, "FlashLoanFeeUpdated emitted");
+    assert.equal(event!.data.previousFeeBps, 0);
+    assert.equal(event!.data.newFeeBps, MAX_FLASH_LOAN_FEE_BPS);
+
+    // 1 bps of LOAN_AMOUNT is 100, which keeps the receiver header valid UTF-8
+    await setFlashLoanFee(1);
+  });
+
+  it("Rejects empty loans and loans beyond the curve reserve", async () => {
+    await expectError(flashLoan(0), "InvalidAmount");
+    await expectError(flashLoan(RESERVE_A + 1), "InsufficientLiquidity");
+  });
+
+  it("Rejects this program as the receiver", async () => {
+    await expectError(flashLoan(LOAN_AMOUNT, program.programId), "InvalidFlashLoanReceiver");
+  });
+
+  it("A receiver that does not repay reverts the loan", async () => {
+    await expectError(flashLoan(LOAN_AMOUNT), "FlashLoanNotRepaid");
+
+    const poolData = await program.account.liquidityPool.fetch(pool.pool);
+    assert.isFalse(poolData.locked, "The lock is rolled back with the loan");
+    assert.equal(poolData.reserveA.toNumber(), RESERVE_A);
+    assert.equal(await getTokenBalance(connection, borrower.tokenA), 0, "Principal is clawed back");
+  });
+
+  it("A receiver that repays principal plus fee grows the reserve and LP fees", async () => {
+    const fee = 100; // ceil(LOAN_AMOUNT * 1 / 10_000)
+    await fundBorrowerFee(fee);
+
+    const before = await program.account.liquidityPool.fetch(pool.pool);
+    const vaultBefore = await getTokenBalance(connection, pool.vaultA);
+
+    // Accounts: borrower token account, mint, pool vault, borrower, token program
+    const signature = await flashLoan(LOAN_AMOUNT, receiver.programId, Buffer.from([RECEIVER_REPAY]), [
+      writable(borrower.tokenA),
+      readonly(pool.mintA),
+      writable(pool.vaultA),
+      { pubkey: borrower.user.publicKey, isSigner: true, isWritable: false },
+      readonly(TOKEN_PROGRAM_ID),
+    ]);
+
+    const after = await program.account.liquidityPool.fetch(pool.pool);
+    assert.isFalse(after.locked, "The lock is released once the loan is repaid");
+    assert.equal(after.reserveA.toNumber(), before.reserveA.toNumber() + fee, "The fee joins the reserve");
+    assert.equal(after.reserveB.toNumber(), before.reserveB.toNumber());
+    assert.equal(await getTokenBalance(connection, pool.vaultA), vaultBefore + fee);
+    assert.equal(await getTokenBalance(connection, borrower.tokenA), 0, "The borrower paid the fee");
+    assert.isTrue(after.feeGrowthGlobalA.gt(before.feeGrowthGlobalA), "The fee accrues to LPs");
+    assert.isTrue(after.feeGrowthGlobalB.eq(before.feeGrowthGlobalB));
+
+    const event = (await getEvents(program, signature)).find((e) => e.name === "flashLoanExecuted");
+    assert.exists(event, "FlashLoanExecuted emitted");
+    assert.isTrue(event!.data.receiverProgram.equals(receiver.programId));
+    assert.equal(event!.data.amount.toNumber(), LOAN_AMOUNT);
+    assert.equal(event!.data.feeAmount.toNumber(), fee);
+    assert.equal(event!.data.newReserveA.toNumber(), after.reserveA.toNumber());
+  });
+
+  it("A receiver that swaps against the pool mid-loan reverts the loan", async () => {
+    const before = await program.account.liquidityPool.fetch(pool.pool);
+
+    // Accounts: the Swap accounts for A -> B, then the DEX program.
+    // The runtime only allows direct self-recursion, so the swap CPI is refused before
+    // the handler runs; the pool lock backs this up should that rule ever relax.
+    const error = flashLoan(LOAN_AMOUNT, receiver.programId, Buffer.from([RECEIVER_REENTER_SWAP]), [
+      writable(pool.pool),
+      writable(borrower.tokenA),
+      writable(borrower.tokenB),
+      writable(pool.vaultA),
+      writable(pool.vaultB),
+      readonly(pool.mintA),
+      readonly(pool.mintB),
+      { pubkey: borrower.user.publicKey, isSigner: true, isWritable: true },
+      readonly(TOKEN_PROGRAM_ID),
+      readonly(TOKEN_PROGRAM_ID),
+      readonly(program.programId),
+    ]);
+    await expectError(error, "reentrancy not allowed");
+
+    const after = await program.account.liquidityPool.fetch(pool.pool);
+    assert.isFalse(after.locked);
+    assert.equal(after.reserveA.toNumber(), before.reserveA.toNumber());
+    assert.equal(after.reserveB.toNumber(), before.reserveB.toNumber());
+    assert.equal(await getTokenBalance(connection, borrower.tokenA), 0, "Principal is clawed back");
+  });
+});
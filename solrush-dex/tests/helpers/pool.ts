@@ -0,0 +1,517 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createAccount,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMint,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+  mintTo,
+} from "@solana/spl-token";
+import {
+  Connection,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SYSVAR_RENT_PUBKEY,
+  SystemProgram,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import { assert } from "chai";
+
+import { SolrushDex } from "../../target/types/solrush_dex";
+
+// ============================================================================
+// SHARED FIXTURES FOR PROGRAM-LEVEL TESTS
+// ============================================================================
+
+// Mirrors state::POOLS_PER_REGISTRY_PAGE
+export const POOLS_PER_REGISTRY_PAGE = 32;
+
+export const DEFAULT_FEE_TIER_BPS = 30;
+
+const BPF_LOADER_UPGRADEABLE_ID = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+export type PoolFixture = {
+  pool: PublicKey;
+  mintA: PublicKey;
+  mintB: PublicKey;
+  vaultA: PublicKey;
+  vaultB: PublicKey;
+  lpMint: PublicKey;
+  feeTierBps: number;
+};
+
+export type UserFixture = {
+  user: Keypair;
+  tokenA: PublicKey;
+  tokenB: PublicKey;
+};
+
+// ----------------------------------------------------------------------------
+// PDAs
+// ----------------------------------------------------------------------------
+
+function findPda(program: Program<SolrushDex>, seeds: Buffer[]): PublicKey {
+  return PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+}
+
+export function poolPda(
+  program: Program<SolrushDex>,
+  mintA: PublicKey,
+  mintB: PublicKey,
+  feeTierBps: number
+): PublicKey {
+  const feeTier = Buffer.alloc(2);
+  feeTier.writeUInt16LE(feeTierBps);
+  return findPda(program, [Buffer.from("pool"), mintA.toBuffer(), mintB.toBuffer(), feeTier]);
+}
+
+export const lpMintPda = (program: Program<SolrushDex>, pool: PublicKey) =>
+  findPda(program, [Buffer.from("lp_mint"), pool.toBuffer()]);
+
+export const positionPda = (program: Program<SolrushDex>, pool: PublicKey, owner: PublicKey) =>
+  findPda(program, [Buffer.from("position"), pool.toBuffer(), owner.toBuffer()]);
+
+export const lpStakeVaultPda = (program: Program<SolrushDex>, pool: PublicKey) =>
+  findPda(program, [Buffer.from("lp_stake_vault"), pool.toBuffer()]);
+
+export const limitOrderPda = (program: Program<SolrushDex>, pool: PublicKey, owner: PublicKey) =>
+  findPda(program, [Buffer.from("limit_order"), pool.toBuffer(), owner.toBuffer()]);
+
+function i32Le(value: number): Buffer {
+  const buf = Buffer.alloc(4);
+  buf.writeInt32LE(value);
+  return buf;
+}
+
+export const tickArrayPda = (program: Program<SolrushDex>, pool: PublicKey, startTickIndex: number) =>
+  findPda(program, [Buffer.from("tick_array"), pool.toBuffer(), i32Le(startTickIndex)]);
+
+export const concentratedPositionPda = (
+  program: Program<SolrushDex>,
+  pool: PublicKey,
+  owner: PublicKey,
+  tickLowerIndex: number,
+  tickUpperIndex: number
+) =>
+  findPda(program, [
+    Buffer.from("cl_position"),
+    pool.toBuffer(),
+    owner.toBuffer(),
+    i32Le(tickLowerIndex),
+    i32Le(tickUpperIndex),
+  ]);
+
+export const rushConfigPda = (program: Program<SolrushDex>) =>
+  findPda(program, [Buffer.from("rush_config")]);
+
+export const dexConfigPda = (program: Program<SolrushDex>) =>
+  findPda(program, [Buffer.from("dex_config")]);
+
+// ProgramData account of the upgradeable loader, holding the upgrade authority
+export const programDataPda = (program: Program<SolrushDex>) =>
+  PublicKey.findProgramAddressSync([program.programId.toBuffer()], BPF_LOADER_UPGRADEABLE_ID)[0];
+
+export const poolRegistryPda = (program: Program<SolrushDex>) =>
+  findPda(program, [Buffer.from("pool_registry")]);
+
+export function registryPagePda(program: Program<SolrushDex>, pageIndex: number): PublicKey {
+  const index = Buffer.alloc(4);
+  index.writeUInt32LE(pageIndex);
+  return findPda(program, [Buffer.from("registry_page"), index]);
+}
+
+// ----------------------------------------------------------------------------
+// Setup
+// ----------------------------------------------------------------------------
+
+// initialize_dex_config signed by `authority`; only the upgrade authority (the provider wallet) succeeds
+export const initializeDexConfig = (program: Program<SolrushDex>, authority: Keypair, treasury: PublicKey) =>
+  program.methods
+    .initializeDexConfig(treasury)
+    .accountsPartial({
+      dexConfig: dexConfigPda(program),
+      program: program.programId,
+      programData: programDataPda(program),
+      authority: authority.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([authority])
+    .rpc();
+
+// Initialize the DEX config and pool registry once per validator
+export async function ensureDexConfig(program: Program<SolrushDex>): Promise<void> {
+  const authority = program.provider.publicKey!;
+  const dexConfig = dexConfigPda(program);
+
+  if (!(await program.account.dexConfig.fetchNullable(dexConfig))) {
+    const payer = ((program.provider as anchor.AnchorProvider).wallet as anchor.Wallet).payer;
+    await initializeDexConfig(program, payer, authority);
+  }
+
+  const poolRegistry = poolRegistryPda(program);
+  if (!(await program.account.poolRegistry.fetchNullable(poolRegistry))) {
+    await program.methods
+      .initializePoolRegistry()
+      .accountsPartial({
+        poolRegistry,
+        dexConfig,
+        authority,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+}
+
+// Initialize the RUSH config and its Token-2022 mint once per validator; returns the mint
+export async function ensureRushConfig(program: Program<SolrushDex>): Promise<PublicKey> {
+  const rushConfig = rushConfigPda(program);
+  const existing = await program.account.rushConfig.fetchNullable(rushConfig);
+  if (existing) return existing.mint;
+
+  const rushMint = Keypair.generate();
+  await program.methods
+    .initializeRushToken()
+    .accountsPartial({
+      rushConfig,
+      rushMint: rushMint.publicKey,
+      authority: program.provider.publicKey!,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      rent: SYSVAR_RENT_PUBKEY,
+    })
+    .signers([rushMint])
+    .rpc();
+  return rushMint.publicKey;
+}
+
+// Create two classic SPL mints whose addresses are already in pool order (a < b)
+export async function createMintPair(
+  connection: Connection,
+  payer: Keypair,
+  decimals: number = 6,
+  decimalsB: number = decimals
+): Promise<[PublicKey, PublicKey]> {
+  const [a, b] = [Keypair.generate(), Keypair.generate()].sort((x, y) =>
+    Buffer.compare(x.publicKey.toBuffer(), y.publicKey.toBuffer())
+  );
+  const mintA = await createMint(connection, payer, payer.publicKey, null, decimals, a);
+  const mintB = await createMint(connection, payer, payer.publicKey, null, decimalsB, b);
+  return [mintA, mintB];
+}
+
+// Token-2022 mint with a TransferFeeConfig, paired with a classic SPL mint in pool order
+// Returns [mintA, mintB] and which of the two carries the transfer fee
+export async function createTransferFeeMintPair(
+  connection: Connection,
+  payer: Keypair,
+  feeBps: number,
+  maximumFee: bigint
+): Promise<{ mints: [PublicKey, PublicKey]; feeMintIsA: boolean }> {
+  const feeMint = Keypair.generate();
+  const classicMint = Keypair.generate();
+  const decimals = 6;
+
+  const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+  const tx = new Transaction().add(
+    SystemProgram.createAccount({
+      fromPubkey: payer.publicKey,
+      newAccountPubkey: feeMint.publicKey,
+      space: mintLen,
+      lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+      programId: TOKEN_2022_PROGRAM_ID,
+    }),
+    createInitializeTransferFeeConfigInstruction(
+      feeMint.publicKey,
+      payer.publicKey,
+      payer.publicKey,
+      feeBps,
+      maximumFee,
+      TOKEN_2022_PROGRAM_ID
+    ),
+    createInitializeMintInstruction(feeMint.publicKey, decimals, payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+  );
+  await sendAndConfirmTransaction(connection, tx, [payer, feeMint]);
+  await createMint(connection, payer, payer.publicKey, null, decimals, classicMint);
+
+  const feeMintIsA = Buffer.compare(feeMint.publicKey.toBuffer(), classicMint.publicKey.toBuffer()) < 0;
+  return {
+    mints: feeMintIsA
+      ? [feeMint.publicKey, classicMint.publicKey]
+      : [classicMint.publicKey, feeMint.publicKey],
+    feeMintIsA,
+  };
+}
+
+// Fresh keypair with SOL for rent and fees, plus funded token accounts for both mints
+export async function createFundedUser(
+  connection: Connection,
+  payer: Keypair,
+  mintA: PublicKey,
+  mintB: PublicKey,
+  amountA: number | bigint,
+  amountB: number | bigint
+): Promise<UserFixture> {
+  const user = Keypair.generate();
+  const sig = await connection.requestAirdrop(user.publicKey, 2 * LAMPORTS_PER_SOL);
+  await connection.confirmTransaction(sig, "confirmed");
+
+  const tokenA = await createAccount(connection, payer, mintA, user.publicKey);
+  const tokenB = await createAccount(connection, payer, mintB, user.publicKey);
+  if (amountA > 0) await mintTo(connection, payer, mintA, tokenA, payer, amountA);
+  if (amountB > 0) await mintTo(connection, payer, mintB, tokenB, payer, amountB);
+
+  return { user, tokenA, tokenB };
+}
+
+// Curve parameters for initialize_pool; constant product when omitted
+export type PoolCurve = {
+  poolType: any;
+  ampCoefficient?: number;
+  weightABps?: number;
+};
+
+// Create a pool seeded by the provider wallet
+export async function createPool(
+  program: Program<SolrushDex>,
+  args: {
+    mintA: PublicKey;
+    mintB: PublicKey;
+    userTokenA: PublicKey;
+    userTokenB: PublicKey;
+    amountA: number;
+    amountB: number;
+    feeTierBps?: number;
+    symbols?: [string, string];
+    curve?: PoolCurve;
+    tokenAProgram?: PublicKey;
+    tokenBProgram?: PublicKey;
+  }
+): Promise<PoolFixture> {
+  const feeTierBps = args.feeTierBps ?? DEFAULT_FEE_TIER_BPS;
+  const [symbolA, symbolB] = args.symbols ?? ["SOL", "USDC"];
+  const curve = args.curve ?? { poolType: { constantProduct: {} } };
+  const authority = program.provider.publicKey!;
+
+  await ensureDexConfig(program);
+
+  const pool = poolPda(program, args.mintA, args.mintB, feeTierBps);
+  const lpMint = lpMintPda(program, pool);
+  const poolRegistry = poolRegistryPda(program);
+  const registry = await program.account.poolRegistry.fetch(poolRegistry);
+  const registryPage = registryPagePda(
+    program,
+    Math.floor(registry.poolCount.toNumber() / POOLS_PER_REGISTRY_PAGE)
+  );
+
+  // Vaults are plain token accounts owned by the pool, created from fresh keypairs
+  const vaultA = Keypair.generate();
+  const vaultB = Keypair.generate();
+
+  await program.methods
+    .initializePool(
+      new anchor.BN(args.amountA),
+      new anchor.BN(args.amountB),
+      feeTierBps,
+      symbolA,
+      symbolB,
+      curve.poolType,
+      new anchor.BN(curve.ampCoefficient ?? 0),
+      curve.weightABps ?? 0
+    )
+    .accountsPartial({
+      pool,
+      dexConfig: dexConfigPda(program),
+      poolRegistry,
+      registryPage,
+      tokenAMint: args.mintA,
+      tokenBMint: args.mintB,
+      lpTokenMint: lpMint,
+      tokenAVault: vaultA.publicKey,
+      tokenBVault: vaultB.publicKey,
+      userTokenA: args.userTokenA,
+      userTokenB: args.userTokenB,
+      lpTokenAccount: getAssociatedTokenAddressSync(lpMint, authority, false, TOKEN_2022_PROGRAM_ID),
+      authority,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenAProgram: args.tokenAProgram ?? TOKEN_PROGRAM_ID,
+      tokenBProgram: args.tokenBProgram ?? TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
+    })
+    .signers([vaultA, vaultB])
+    .rpc({ commitment: "confirmed" });
+
+  return {
+    pool,
+    mintA: args.mintA,
+    mintB: args.mintB,
+    vaultA: vaultA.publicKey,
+    vaultB: vaultB.publicKey,
+    lpMint,
+    feeTierBps,
+  };
+}
+
+// Pool seeded by the provider wallet, which keeps `spare` of each token for later deposits
+export async function createProviderPool(
+  program: Program<SolrushDex>,
+  amountA: number,
+  amountB: number,
+  spare: number = 0,
+  extra: { feeTierBps?: number; curve?: PoolCurve; symbols?: [string, string] } = {}
+): Promise<{ pool: PoolFixture; creator: UserFixture }> {
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+  const [mintA, mintB] = await createMintPair(provider.connection, payer);
+  const tokenA = await createAccount(provider.connection, payer, mintA, payer.publicKey);
+  const tokenB = await createAccount(provider.connection, payer, mintB, payer.publicKey);
+  await mintTo(provider.connection, payer, mintA, tokenA, payer, amountA + spare);
+  await mintTo(provider.connection, payer, mintB, tokenB, payer, amountB + spare);
+
+  const pool = await createPool(program, {
+    mintA,
+    mintB,
+    userTokenA: tokenA,
+    userTokenB: tokenB,
+    amountA,
+    amountB,
+    ...extra,
+  });
+  return { pool, creator: { user: payer, tokenA, tokenB } };
+}
+
+// Deposit both tokens from a funded user; opens their position on first use
+export async function addLiquidity(
+  program: Program<SolrushDex>,
+  fixture: PoolFixture,
+  owner: UserFixture,
+  amountA: number,
+  amountB: number,
+  amountAMin: number = 0,
+  amountBMin: number = 0
+): Promise<void> {
+  await program.methods
+    .addLiquidity(
+      new anchor.BN(amountA),
+      new anchor.BN(amountB),
+      new anchor.BN(amountAMin),
+      new anchor.BN(amountBMin),
+      null
+    )
+    .accountsPartial({
+      pool: fixture.pool,
+      tokenAMint: fixture.mintA,
+      tokenBMint: fixture.mintB,
+      lpTokenMint: fixture.lpMint,
+      userPosition: positionPda(program, fixture.pool, owner.user.publicKey),
+      tokenAVault: fixture.vaultA,
+      tokenBVault: fixture.vaultB,
+      userTokenA: owner.tokenA,
+      userTokenB: owner.tokenB,
+      userLpTokenAccount: lpTokenAccount(fixture, owner.user.publicKey),
+      user: owner.user.publicKey,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenAProgram: TOKEN_PROGRAM_ID,
+      tokenBProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
+    })
+    .signers([owner.user])
+    .rpc();
+}
+
+// ----------------------------------------------------------------------------
+// Account sets
+// ----------------------------------------------------------------------------
+
+// LP tokens live in the owner's Token-2022 associated account
+export const lpTokenAccount = (fixture: PoolFixture, owner: PublicKey) =>
+  getAssociatedTokenAddressSync(fixture.lpMint, owner, false, TOKEN_2022_PROGRAM_ID);
+
+// Accounts shared by remove_liquidity and remove_liquidity_exact_out
+export const withdrawAccounts = (program: Program<SolrushDex>, fixture: PoolFixture, owner: UserFixture) => ({
+  pool: fixture.pool,
+  lpTokenMint: fixture.lpMint,
+  tokenAMint: fixture.mintA,
+  tokenBMint: fixture.mintB,
+  userPosition: positionPda(program, fixture.pool, owner.user.publicKey),
+  tokenAVault: fixture.vaultA,
+  tokenBVault: fixture.vaultB,
+  userLpTokenAccount: lpTokenAccount(fixture, owner.user.publicKey),
+  userTokenA: owner.tokenA,
+  userTokenB: owner.tokenB,
+  user: owner.user.publicKey,
+  tokenProgram: TOKEN_2022_PROGRAM_ID,
+  tokenAProgram: TOKEN_PROGRAM_ID,
+  tokenBProgram: TOKEN_PROGRAM_ID,
+});
+
+// Accounts for swap / market_buy / market_sell in the given direction
+export function swapAccounts(
+  fixture: PoolFixture,
+  user: PublicKey,
+  userTokenA: PublicKey,
+  userTokenB: PublicKey,
+  isAToB: boolean
+) {
+  return {
+    pool: fixture.pool,
+    userTokenIn: isAToB ? userTokenA : userTokenB,
+    userTokenOut: isAToB ? userTokenB : userTokenA,
+    poolVaultIn: isAToB ? fixture.vaultA : fixture.vaultB,
+    poolVaultOut: isAToB ? fixture.vaultB : fixture.vaultA,
+    tokenInMint: isAToB ? fixture.mintA : fixture.mintB,
+    tokenOutMint: isAToB ? fixture.mintB : fixture.mintA,
+    user,
+    tokenInProgram: TOKEN_PROGRAM_ID,
+    tokenOutProgram: TOKEN_PROGRAM_ID,
+  };
+}
+
+// ----------------------------------------------------------------------------
+// Assertions
+// ----------------------------------------------------------------------------
+
+export async function getTokenBalance(connection: Connection, tokenAccount: PublicKey): Promise<number> {
+  const balance = await connection.getTokenAccountBalance(tokenAccount);
+  return Number(balance.value.amount);
+}
+
+// Events emitted by a confirmed transaction, decoded with the program's IDL
+export async function getEvents(program: Program<SolrushDex>, signature: string) {
+  const tx = await program.provider.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const parser = new anchor.EventParser(program.programId, program.coder);
+  return [...parser.parseLogs(tx?.meta?.logMessages ?? [])];
+}
+
+// Expect a transaction to fail with the given Anchor error code (or a log line containing it)
+export async function expectError(promise: Promise<unknown>, code: string): Promise<void> {
+  let err: any;
+  try {
+    await promise;
+  } catch (e) {
+    err = e;
+  }
+  assert.exists(err, `Expected ${code}, but the transaction succeeded`);
+
+  const anchorCode: string | undefined = err.error?.errorCode?.code;
+  if (anchorCode) {
+    assert.equal(anchorCode, code);
+  } else {
+    const logs: string[] = err.logs ?? err.transactionLogs ?? [];
+    assert.include([err.message, ...logs].join("\n"), code);
+  }
+}
@@ -0,0 +1,1000 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, createAccount, mintTo, transfer } from "@solana/spl-token";
+import { PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+
+import { SolrushDex } from "../target/types/solrush_dex";
+import {
+  PoolCurve,
+  PoolFixture,
+  UserFixture,
+  addLiquidity,
+  createFundedUser,
+  createMintPair,
+  createPool,
+  createTransferFeeMintPair,
+  ensureRushConfig,
+  expectError,
+  getEvents,
+  getTokenBalance,
+  lpStakeVaultPda,
+  lpTokenAccount,
+  poolRegistryPda,
+  positionPda,
+  registryPagePda,
+  rushConfigPda,
+  swapAccounts,
+  withdrawAccounts,
+} from "./helpers/pool";
+
+// ============================================================================
+// TEST SUITE: LIQUIDITY INSTRUCTIONS AGAINST THE DEPLOYED PROGRAM (Module 2)
+// ============================================================================
+
+describe("Liquidity Instructions - Module 2", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SolrushDex as Program<SolrushDex>;
+  const connection = provider.connection;
+  const payer = provider.wallet as anchor.Wallet;
+
+  const INITIAL_AMOUNT_A = 1_000_000_000;  // 1000 tokens at 6 decimals
+  const INITIAL_AMOUNT_B = 5_000_000_000;  // 1:5 price
+
+  let fixture: PoolFixture;
+  let lp: UserFixture;
+
+  type Snapshot = {
+    reserveA: anchor.BN;
+    reserveB: anchor.BN;
+    supply: anchor.BN;
+    tokenA: number;
+    tokenB: number;
+    lpTokens: number;
+  };
+
+  // Pool reserves and an owner's balances, read together
+  async function snapshot(owner: UserFixture): Promise<Snapshot> {
+    const pool = await program.account.liquidityPool.fetch(fixture.pool);
+    return {
+      reserveA: pool.reserveA,
+      reserveB: pool.reserveB,
+      supply: pool.totalLpSupply,
+      tokenA: await getTokenBalance(connection, owner.tokenA),
+      tokenB: await getTokenBalance(connection, owner.tokenB),
+      lpTokens: await getTokenBalance(connection, lpTokenAccount(fixture, owner.user.publicKey)),
+    };
+  }
+
+  // Fresh pool seeded by the provider wallet, which keeps `spare` of each token for later deposits
+  async function createCreatorPool(
+    amountA: number,
+    amountB: number,
+    spare: number = 0,
+    curve?: PoolCurve,
+    decimals: [number, number] = [6, 6]
+  ): Promise<{ pool: PoolFixture; creator: UserFixture }> {
+    const [mintA, mintB] = await createMintPair(connection, payer.payer, ...decimals);
+    const tokenA = await createAccount(connection, payer.payer, mintA, payer.publicKey);
+    const tokenB = await createAccount(connection, payer.payer, mintB, payer.publicKey);
+    await mintTo(connection, payer.payer, mintA, tokenA, payer.payer, amountA + spare);
+    await mintTo(connection, payer.payer, mintB, tokenB, payer.payer, amountB + spare);
+
+    const pool = await createPool(program, {
+      mintA,
+      mintB,
+      userTokenA: tokenA,
+      userTokenB: tokenB,
+      amountA,
+      amountB,
+      curve,
+    });
+    return { pool, creator: { user: payer.payer, tokenA, tokenB } };
+  }
+
+  before(async () => {
+    ({ pool: fixture } = await createCreatorPool(INITIAL_AMOUNT_A, INITIAL_AMOUNT_B));
+
+    // A second LP deposits 10% of the pool and opens a position
+    lp = await createFundedUser(
+      connection,
+      payer.payer,
+      fixture.mintA,
+      fixture.mintB,
+      INITIAL_AMOUNT_A,
+      INITIAL_AMOUNT_B
+    );
+    await addLiquidity(program, fixture, lp, INITIAL_AMOUNT_A / 10, INITIAL_AMOUNT_B / 10);
+  });
+
+  // =========================================================================
+  // MINIMUM LIQUIDITY LOCK
+  // =========================================================================
+
+  describe("Minimum Liquidity Lock", () => {
+    // Mirrors state::MINIMUM_LIQUIDITY
+    const MINIMUM_LIQUIDITY = 1_000;
+
+    it("Rejects pools whose initial LP does not exceed the lock", async () => {
+      await expectError(createCreatorPool(MINIMUM_LIQUIDITY, MINIMUM_LIQUIDITY), "InitialLiquidityTooLow");
+    });
+
+    it("Creator receives sqrt(a * b) minus the locked amount", async () => {
+      const { pool, creator } = await createCreatorPool(100_000, 400_000);
+      const poolData = await program.account.liquidityPool.fetch(pool.pool);
+
+      assert.equal(poolData.totalLpSupply.toNumber(), 200_000, "Supply counts the locked share");
+      assert.equal(
+        await getTokenBalance(connection, lpTokenAccount(pool, creator.user.publicKey)),
+        200_000 - MINIMUM_LIQUIDITY,
+        "Locked share is never minted"
+      );
+    });
+
+    it("Creator can exit completely without draining the reserves", async () => {
+      const { pool, creator } = await createCreatorPool(1_000_000, 1_000_000, 1_000);
+      // Any deposit opens the position that withdrawals require
+      await addLiquidity(program, pool, creator, 1_000, 1_000);
+      const lpTokens = await getTokenBalance(connection, lpTokenAccount(pool, creator.user.publicKey));
+
+      await program.methods
+        .removeLiquidity(new anchor.BN(lpTokens), new anchor.BN(0), new anchor.BN(0), null)
+        .accountsPartial(withdrawAccounts(program, pool, creator))
+        .rpc();
+
+      const poolData = await program.account.liquidityPool.fetch(pool.pool);
+      assert.equal(poolData.totalLpSupply.toNumber(), MINIMUM_LIQUIDITY, "Locked share remains");
+      assert.isAbove(poolData.reserveA.toNumber(), 0, "Locked share keeps token A");
+      assert.isAbove(poolData.reserveB.toNumber(), 0, "Locked share keeps token B");
+    });
+  });
+
+  // =========================================================================
+  // OPTIMAL-RATIO ADD LIQUIDITY (Module 2.3)
+  // =========================================================================
+
+  describe("Optimal-Ratio Add Liquidity", () => {
+    it("Excess of either token stays in the depositor's wallet", async () => {
+      for (const excessSide of ["A", "B"]) {
+        const before = await snapshot(lp);
+        // Match 10 tokens of one side at the live ratio, then offer 20% more of the other
+        let amountA = 10_000_000;
+        let amountB = new anchor.BN(amountA).mul(before.reserveB).div(before.reserveA).toNumber();
+        if (excessSide === "A") {
+          amountA = Math.floor(amountA * 1.2);
+        } else {
+          amountB = Math.floor(amountB * 1.2);
+        }
+
+        await addLiquidity(program, fixture, lp, amountA, amountB);
+        const after = await snapshot(lp);
+        const spentA = before.tokenA - after.tokenA;
+        const spentB = before.tokenB - after.tokenB;
+
+        assert.isBelow(excessSide === "A" ? spentA : spentB, excessSide === "A" ? amountA : amountB);
+        // What was spent sits at the pool ratio, so nothing was donated; re-quoting B
+        // from a floored amount of A can be off by up to one unit of A's price
+        const expectedB = new anchor.BN(spentA).mul(before.reserveB).div(before.reserveA).toNumber();
+        const tolerance = before.reserveB.div(before.reserveA).toNumber() + 1;
+        assert.approximately(spentB, expectedB, tolerance, `Excess ${excessSide} is not transferred`);
+      }
+    });
+
+    it("Minimums protect against ratio moves", async () => {
+      const { reserveA, reserveB } = await snapshot(lp);
+      const amountA = 10_000_000;
+      const quoteB = new anchor.BN(amountA).mul(reserveB).div(reserveA).toNumber();
+
+      // Offering excess B clamps B to the quote, which is below the minimum asked for
+      await expectError(
+        addLiquidity(program, fixture, lp, amountA, quoteB * 2, 0, quoteB + 1),
+        "SlippageTooHigh"
+      );
+    });
+  });
+
+  // =========================================================================
+  // SINGLE-SIDED DEPOSIT (ZAP)
+  // =========================================================================
+
+  describe("Single-Sided Deposit", () => {
+    const zap = (owner: UserFixture, amountIn: number, deadline: anchor.BN | null) =>
+      program.methods
+        .addLiquiditySingleSided(new anchor.BN(amountIn), true, new anchor.BN(0), deadline)
+        .accountsPartial({
+          pool: fixture.pool,
+          tokenAMint: fixture.mintA,
+          tokenBMint: fixture.mintB,
+          lpTokenMint: fixture.lpMint,
+          userPosition: positionPda(program, fixture.pool, owner.user.publicKey),
+          tokenAVault: fixture.vaultA,
+          tokenBVault: fixture.vaultB,
+          userTokenIn: owner.tokenA,
+          userLpTokenAccount: lpTokenAccount(fixture, owner.user.publicKey),
+          user: owner.user.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          tokenAProgram: TOKEN_PROGRAM_ID,
+          tokenBProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([owner.user])
+        .rpc();
+
+    const blockTime = async () => (await connection.getBlockTime(await connection.getSlot()))!;
+
+    it("Mints LP for a deposit of token A alone", async () => {
+      const before = await snapshot(lp);
+      await zap(lp, 10_000_000, new anchor.BN((await blockTime()) + 60));
+      const after = await snapshot(lp);
+
+      assert.equal(before.tokenA - after.tokenA, 10_000_000);
+      assert.equal(after.tokenB, before.tokenB, "Token B is never pulled");
+      assert.isAbove(after.lpTokens, before.lpTokens);
+    });
+
+    it("Rejects a deposit after its deadline", async () => {
+      await expectError(zap(lp, 10_000_000, new anchor.BN((await blockTime()) - 60)), "DeadlineExceeded");
+    });
+  });
+
+  // =========================================================================
+  // SINGLE-SIDED WITHDRAWAL
+  // =========================================================================
+
+  describe("Single-Sided Withdrawal", () => {
+    const removeSingleSided = (
+      owner: UserFixture,
+      lpTokensToBurn: number,
+      isTokenAOut: boolean,
+      minAmountOut: number,
+      userTokenOut: PublicKey = isTokenAOut ? owner.tokenA : owner.tokenB
+    ) =>
+      program.methods
+        .removeLiquiditySingleSided(new anchor.BN(lpTokensToBurn), isTokenAOut, new anchor.BN(minAmountOut), null)
+        .accountsPartial({
+          pool: fixture.pool,
+          lpTokenMint: fixture.lpMint,
+          tokenAMint: fixture.mintA,
+          tokenBMint: fixture.mintB,
+          userPosition: positionPda(program, fixture.pool, owner.user.publicKey),
+          tokenAVault: fixture.vaultA,
+          tokenBVault: fixture.vaultB,
+          userLpTokenAccount: lpTokenAccount(fixture, owner.user.publicKey),
+          userTokenOut,
+          user: owner.user.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          tokenAProgram: TOKEN_PROGRAM_ID,
+          tokenBProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([owner.user]);
+
+    it("Pays out only the requested token and reports it in LiquidityRemoved", async () => {
+      const before = await snapshot(lp);
+      const lpTokensToBurn = Math.floor(before.lpTokens / 4);
+      const shareA = new anchor.BN(lpTokensToBurn).mul(before.reserveA).div(before.supply).toNumber();
+
+      const signature = await removeSingleSided(lp, lpTokensToBurn, true, 0).rpc({ commitment: "confirmed" });
+      const after = await snapshot(lp);
+      const received = after.tokenA - before.tokenA;
+
+      assert.equal(after.tokenB, before.tokenB, "Token B stays in the pool");
+      assert.isAbove(received, shareA, "Swapped-back side adds to the proportional share");
+      assert.equal(after.lpTokens, before.lpTokens - lpTokensToBurn, "LP burned from the wallet");
+      assert.isTrue(after.reserveB.gte(before.reserveB), "Token B share is swapped straight back in");
+
+      const event = (await getEvents(program, signature)).find((e) => e.name === "liquidityRemoved");
+      assert.exists(event, "LiquidityRemoved emitted");
+      assert.equal(event!.data.amountAReceived.toNumber(), received);
+      assert.equal(event!.data.amountBReceived.toNumber(), 0);
+      assert.isTrue(event!.data.outputMint.equals(fixture.mintA), "Event names the output mint");
+    });
+
+    it("Remaining LPs are never diluted", async () => {
+      // k per LP^2, scaled so the integer division keeps precision
+      const kPerShare = (s: Snapshot) =>
+        s.reserveA.mul(s.reserveB).mul(new anchor.BN(1_000_000)).div(s.supply.mul(s.supply));
+
+      const before = await snapshot(lp);
+      await removeSingleSided(lp, Math.floor(before.lpTokens / 4), false, 0).rpc();
+      const after = await snapshot(lp);
+
+      assert.isTrue(kPerShare(after).gte(kPerShare(before)), "Swap fee stays with the pool");
+    });
+
+    it("min_amount_out guards the combined payout", async () => {
+      const { lpTokens } = await snapshot(lp);
+
+      await expectError(
+        removeSingleSided(lp, Math.floor(lpTokens / 4), true, INITIAL_AMOUNT_A).rpc(),
+        "SlippageTooHigh"
+      );
+    });
+
+    it("Output account must hold the requested token", async () => {
+      const { lpTokens } = await snapshot(lp);
+
+      await expectError(
+        removeSingleSided(lp, Math.floor(lpTokens / 4), true, 0, lp.tokenB).rpc(),
+        "InvalidTokenMint"
+      );
+    });
+  });
+
+  // =========================================================================
+  // EXACT-OUT WITHDRAWAL (Module 2.4)
+  // =========================================================================
+
+  describe("Exact-Out Withdrawal", () => {
+    const removeExactOut = (owner: UserFixture, amountA: number, amountB: number, maxLpTokensToBurn: number) =>
+      program.methods
+        .removeLiquidityExactOut(new anchor.BN(amountA), new anchor.BN(amountB), new anchor.BN(maxLpTokensToBurn), null)
+        .accountsPartial(withdrawAccounts(program, fixture, owner))
+        .signers([owner.user]);
+
+    it("Pays exactly the requested amounts within max_lp_tokens_to_burn", async () => {
+      const before = await snapshot(lp);
+      const amountA = 1_000_000;
+      // Cost of a proportional withdrawal of amountA, plus room for the imbalance fee
+      const maxLpTokensToBurn = new anchor.BN(amountA).mul(before.supply).div(before.reserveA).toNumber() * 2;
+
+      await removeExactOut(lp, amountA, 0, maxLpTokensToBurn).rpc();
+      const after = await snapshot(lp);
+      const burned = before.lpTokens - after.lpTokens;
+
+      assert.equal(after.tokenA - before.tokenA, amountA, "Exact token A amount received");
+      assert.equal(after.tokenB, before.tokenB, "No token B requested, none paid");
+      assert.isAbove(burned, 0);
+      assert.isAtMost(burned, maxLpTokensToBurn);
+    });
+
+    it("max_lp_tokens_to_burn guards the cost", async () => {
+      await expectError(removeExactOut(lp, 1_000_000, 0, 1).rpc(), "SlippageTooHigh");
+    });
+  });
+
+  // =========================================================================
+  // RESERVE RECONCILIATION (Module 2.7)
+  // =========================================================================
+
+  describe("Reserve Reconciliation (Sync / Skim)", () => {
+    const DONATION = 12_345;
+
+    // Send tokens straight into a vault, bypassing the pool's bookkeeping
+    const donate = (source: PublicKey, vault: PublicKey) =>
+      transfer(connection, payer.payer, source, vault, lp.user, DONATION);
+
+    const skim = () =>
+      program.methods
+        .skim()
+        .accountsPartial({
+          pool: fixture.pool,
+          tokenAMint: fixture.mintA,
+          tokenBMint: fixture.mintB,
+          tokenAVault: fixture.vaultA,
+          tokenBVault: fixture.vaultB,
+          recipientTokenA: lp.tokenA,
+          recipientTokenB: lp.tokenB,
+          caller: lp.user.publicKey,
+          tokenAProgram: TOKEN_PROGRAM_ID,
+          tokenBProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([lp.user]);
+
+    it("Skim pays a direct donation to the recipient and leaves reserves alone", async () => {
+      await donate(lp.tokenA, fixture.vaultA);
+      const before = await snapshot(lp);
+
+      await skim().rpc();
+      const after = await snapshot(lp);
+      const pool = await program.account.liquidityPool.fetch(fixture.pool);
+
+      assert.equal(after.tokenA - before.tokenA, DONATION, "Donation skimmed to the recipient");
+      assert.isTrue(after.reserveA.eq(before.reserveA), "Reserves are untouched");
+      assert.equal(
+        await getTokenBalance(connection, fixture.vaultA),
+        pool.reserveA.add(pool.protocolFeesA).toNumber(),
+        "Vault holds exactly reserves plus protocol fees"
+      );
+    });
+
+    it("Skim with no surplus is rejected", async () => {
+      await expectError(skim().rpc(), "NoSurplus");
+    });
+
+    it("Sync folds a donation into reserves but keeps protocol fees out", async () => {
+      await donate(lp.tokenB, fixture.vaultB);
+
+      await program.methods
+        .sync()
+        .accountsPartial({
+          pool: fixture.pool,
+          tokenAVault: fixture.vaultA,
+          tokenBVault: fixture.vaultB,
+          caller: lp.user.publicKey,
+        })
+        .signers([lp.user])
+        .rpc();
+
+      const pool = await program.account.liquidityPool.fetch(fixture.pool);
+      assert.equal(
+        pool.reserveB.toNumber(),
+        (await getTokenBalance(connection, fixture.vaultB)) - pool.protocolFeesB.toNumber(),
+        "Synced reserve is the vault minus uncollected protocol fees"
+      );
+    });
+  });
+
+  // =========================================================================
+  // TOKEN-2022 TRANSFER FEES (Module 2.8)
+  // =========================================================================
+
+  describe("Token-2022 Transfer Fees (Module 2.8)", () => {
+    const FEE_BPS = 100; // 1% withheld on every transfer of the fee mint
+    const RESERVE = 100_000_000;
+    const AMOUNT_IN = 1_000_000;
+
+    let pool: PoolFixture;
+    let creator: UserFixture;
+    let feeMintIsA: boolean;
+
+    // Reserve plus uncollected protocol fees on one side: everything the curve owns in that vault
+    const poolSide = async (isA: boolean) => {
+      const data = await program.account.liquidityPool.fetch(pool.pool);
+      return isA ? data.reserveA.add(data.protocolFeesA).toNumber() : data.reserveB.add(data.protocolFeesB).toNumber();
+    };
+
+    const swap = (isAToB: boolean) => {
+      const accounts = swapAccounts(pool, creator.user.publicKey, creator.tokenA, creator.tokenB, isAToB);
+      const feeMintIn = isAToB === feeMintIsA;
+      return program.methods
+        .swap(new anchor.BN(AMOUNT_IN), new anchor.BN(0), isAToB, null)
+        .accountsPartial({
+          ...accounts,
+          tokenInProgram: feeMintIn ? TOKEN_2022_PROGRAM_ID : TOKEN_PROGRAM_ID,
+          tokenOutProgram: feeMintIn ? TOKEN_PROGRAM_ID : TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+    };
+
+    before(async () => {
+      const pair = await createTransferFeeMintPair(connection, payer.payer, FEE_BPS, BigInt(RESERVE));
+      feeMintIsA = pair.feeMintIsA;
+      const [mintA, mintB] = pair.mints;
+      const programA = feeMintIsA ? TOKEN_2022_PROGRAM_ID : TOKEN_PROGRAM_ID;
+      const programB = feeMintIsA ? TOKEN_PROGRAM_ID : TOKEN_2022_PROGRAM_ID;
+
+      const tokenA = await createAccount(connection, payer.payer, mintA, payer.publicKey, undefined, undefined, programA);
+      const tokenB = await createAccount(connection, payer.payer, mintB, payer.publicKey, undefined, undefined, programB);
+      await mintTo(connection, payer.payer, mintA, tokenA, payer.payer, 2 * RESERVE, [], undefined, programA);
+      await mintTo(connection, payer.payer, mintB, tokenB, payer.payer, 2 * RESERVE, [], undefined, programB);
+      creator = { user: payer.payer, tokenA, tokenB };
+
+      pool = await createPool(program, {
+        mintA,
+        mintB,
+        userTokenA: tokenA,
+        userTokenB: tokenB,
+        amountA: RESERVE,
+        amountB: RESERVE,
+        tokenAProgram: programA,
+        tokenBProgram: programB,
+      });
+    });
+
+    it("Initial deposit credits the reserve with what the vault received", async () => {
+      const feeVault = feeMintIsA ? pool.vaultA : pool.vaultB;
+      const classicVault = feeMintIsA ? pool.vaultB : pool.vaultA;
+
+      assert.equal(await getTokenBalance(connection, feeVault), RESERVE - RESERVE / 100, "1% withheld by the mint");
+      assert.equal(await poolSide(feeMintIsA), await getTokenBalance(connection, feeVault));
+      assert.equal(await poolSide(!feeMintIsA), RESERVE);
+      assert.equal(await getTokenBalance(connection, classicVault), RESERVE);
+    });
+
+    it("Swapping the fee token in grows the reserve by the amount received, not amount_in", async () => {
+      const feeVault = feeMintIsA ? pool.vaultA : pool.vaultB;
+      const vaultBefore = await getTokenBalance(connection, feeVault);
+      const sideBefore = await poolSide(feeMintIsA);
+
+      await swap(feeMintIsA);
+      const received = (await getTokenBalance(connection, feeVault)) - vaultBefore;
+
+      assert.equal(received, AMOUNT_IN - AMOUNT_IN / 100, "Vault receives amount_in minus the transfer fee");
+      assert.equal((await poolSide(feeMintIsA)) - sideBefore, received, "Reserve tracks the vault, not amount_in");
+    });
+
+    it("Swapping the fee token out debits the reserve by what left the vault", async () => {
+      const feeVault = feeMintIsA ? pool.vaultA : pool.vaultB;
+      const userFeeAccount = feeMintIsA ? creator.tokenA : creator.tokenB;
+      const vaultBefore = await getTokenBalance(connection, feeVault);
+      const sideBefore = await poolSide(feeMintIsA);
+      const userBefore = await getTokenBalance(connection, userFeeAccount);
+
+      await swap(!feeMintIsA);
+      const sent = vaultBefore - (await getTokenBalance(connection, feeVault));
+      const credited = (await getTokenBalance(connection, userFeeAccount)) - userBefore;
+
+      assert.equal(sideBefore - (await poolSide(feeMintIsA)), sent, "Reserve drops by the vault outflow");
+      assert.equal(credited, sent - Math.ceil((sent * FEE_BPS) / 10_000), "Trader bears the outbound transfer fee");
+    });
+  });
+
+  // =========================================================================
+  // POOL STATUS (Module 2.9)
+  // =========================================================================
+
+  describe("Pool Status (Emergency Modes)", () => {
+    const RESERVE = 100_000_000;
+    const AMOUNT = 1_000_000;
+
+    let pool: PoolFixture;
+    let creator: UserFixture;
+
+    before(async () => {
+      ({ pool, creator } = await createCreatorPool(RESERVE, RESERVE, RESERVE));
+      // Opens the creator's position so withdrawals can be exercised
+      await addLiquidity(program, pool, creator, AMOUNT, AMOUNT);
+    });
+
+    const setStatus = (status: object, authority?: anchor.web3.Keypair) =>
+      program.methods
+        .setPoolStatus(status as any)
+        .accountsPartial({ pool: pool.pool, authority: (authority ?? payer.payer).publicKey })
+        .signers(authority ? [authority] : [])
+        .rpc({ commitment: "confirmed" });
+
+    const swap = () =>
+      program.methods
+        .swap(new anchor.BN(AMOUNT), new anchor.BN(0), true, null)
+        .accountsPartial(swapAccounts(pool, creator.user.publicKey, creator.tokenA, creator.tokenB, true))
+        .rpc();
+
+    const deposit = () => addLiquidity(program, pool, creator, AMOUNT, AMOUNT);
+
+    const withdraw = () =>
+      program.methods
+        .removeLiquidity(new anchor.BN(AMOUNT / 10), new anchor.BN(0), new anchor.BN(0), null)
+        .accountsPartial(withdrawAccounts(program, pool, creator))
+        .rpc();
+
+    const sync = () =>
+      program.methods
+        .sync()
+        .accountsPartial({
+          pool: pool.pool,
+          tokenAVault: pool.vaultA,
+          tokenBVault: pool.vaultB,
+          caller: creator.user.publicKey,
+        })
+        .rpc();
+
+    it("Only the pool authority can change status, and only to a new value", async () => {
+      const stranger = anchor.web3.Keypair.generate();
+
+      await expectError(setStatus({ frozen: {} }, stranger), "InvalidAuthority");
+      await expectError(setStatus({ active: {} }), "PoolStatusUnchanged");
+    });
+
+    it("SwapsPaused blocks swaps but keeps deposits and withdrawals open", async () => {
+      const signature = await setStatus({ swapsPaused: {} });
+      const event = (await getEvents(program, signature)).find((e) => e.name === "poolStatusChanged");
+      assert.exists(event, "PoolStatusChanged emitted");
+      assert.isTrue(event!.data.pool.equals(pool.pool));
+      assert.deepEqual(event!.data.previousStatus, { active: {} });
+      assert.deepEqual(event!.data.newStatus, { swapsPaused: {} });
+      assert.isTrue(event!.data.changedBy.equals(payer.publicKey));
+
+      await expectError(swap(), "PoolSwapsDisabled");
+      await deposit();
+      await withdraw();
+    });
+
+    it("WithdrawOnly also blocks deposits", async () => {
+      await setStatus({ withdrawOnly: {} });
+
+      await expectError(swap(), "PoolSwapsDisabled");
+      await expectError(deposit(), "PoolDepositsDisabled");
+      await withdraw();
+      await sync();
+    });
+
+    it("Frozen blocks withdrawals and reconciliation", async () => {
+      await setStatus({ frozen: {} });
+
+      await expectError(swap(), "PoolSwapsDisabled");
+      await expectError(deposit(), "PoolDepositsDisabled");
+      await expectError(withdraw(), "PoolWithdrawalsDisabled");
+      await expectError(sync(), "PoolReconciliationDisabled");
+    });
+
+    it("Returning to Active reopens every instruction", async () => {
+      const signature = await setStatus({ active: {} });
+      const event = (await getEvents(program, signature)).find((e) => e.name === "poolStatusChanged");
+      assert.deepEqual(event!.data.previousStatus, { frozen: {} });
+
+      await swap();
+      await deposit();
+      await withdraw();
+    });
+  });
+
+  // =========================================================================
+  // CLOSE POOL (Module 2.10)
+  // =========================================================================
+
+  describe("Close Pool", () => {
+    const RESERVE = 10_000_000;
+
+    let pool: PoolFixture;
+    let creator: UserFixture;
+
+    const stakeVault = () => lpStakeVaultPda(program, pool.pool);
+
+    // Page holding the pool's live registry entry
+    async function registryPageOf(poolKey: PublicKey): Promise<PublicKey> {
+      const { pageCount } = await program.account.poolRegistry.fetch(poolRegistryPda(program));
+      for (let index = pageCount - 1; index >= 0; index--) {
+        const page = await program.account.poolRegistryPage.fetch(registryPagePda(program, index));
+        if (page.entries.some((e) => e.pool.equals(poolKey) && e.closedAt.isZero())) {
+          return registryPagePda(program, index);
+        }
+      }
+      throw new Error("Pool is not registered");
+    }
+
+    const closePool = async () =>
+      program.methods
+        .closePool()
+        .accountsPartial({
+          pool: pool.pool,
+          tokenAMint: pool.mintA,
+          tokenBMint: pool.mintB,
+          lpTokenMint: pool.lpMint,
+          lpStakeVault: stakeVault(),
+          tokenAVault: pool.vaultA,
+          tokenBVault: pool.vaultB,
+          recipientTokenA: creator.tokenA,
+          recipientTokenB: creator.tokenB,
+          registryPage: await registryPageOf(pool.pool),
+          authority: payer.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          tokenAProgram: TOKEN_PROGRAM_ID,
+          tokenBProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc({ commitment: "confirmed" });
+
+    const stakingAccounts = () => ({
+      pool: pool.pool,
+      rushConfig: rushConfigPda(program),
+      lpTokenMint: pool.lpMint,
+      userPosition: positionPda(program, pool.pool, creator.user.publicKey),
+      lpStakeVault: stakeVault(),
+      userLpTokenAccount: lpTokenAccount(pool, creator.user.publicKey),
+      user: creator.user.publicKey,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    });
+
+    before(async () => {
+      await ensureRushConfig(program);
+      ({ pool, creator } = await createCreatorPool(RESERVE, RESERVE, RESERVE));
+      // Opens the creator's position, then allocates the stake vault
+      await addLiquidity(program, pool, creator, RESERVE / 10, RESERVE / 10);
+      await program.methods.stakeLp(new anchor.BN(1_000)).accountsPartial(stakingAccounts()).rpc();
+    });
+
+    it("Outstanding LP blocks closing", async () => {
+      await expectError(closePool(), "PoolNotEmpty");
+    });
+
+    it("Drained pool closes every account it owns and returns the rent", async () => {
+      await program.methods.unstakeLp(new anchor.BN(1_000)).accountsPartial(stakingAccounts()).rpc();
+      const lpTokens = await getTokenBalance(connection, lpTokenAccount(pool, creator.user.publicKey));
+      await program.methods
+        .removeLiquidity(new anchor.BN(lpTokens), new anchor.BN(0), new anchor.BN(0), null)
+        .accountsPartial(withdrawAccounts(program, pool, creator))
+        .rpc();
+
+      const owned = [pool.pool, pool.vaultA, pool.vaultB, pool.lpMint, stakeVault()];
+      let rent = 0;
+      for (const account of owned) {
+        rent += (await connection.getAccountInfo(account))!.lamports;
+      }
+      const lamportsBefore = await connection.getBalance(payer.publicKey, "confirmed");
+      const tokenABefore = await getTokenBalance(connection, creator.tokenA);
+
+      const signature = await closePool();
+      const tx = await connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+
+      for (const account of owned) {
+        assert.isNull(await connection.getAccountInfo(account, "confirmed"), `${account.toBase58()} closed`);
+      }
+      assert.equal(
+        (await connection.getBalance(payer.publicKey, "confirmed")) - lamportsBefore,
+        rent - tx!.meta!.fee,
+        "Rent of the pool, both vaults, the LP mint and the stake vault returned"
+      );
+
+      const event = (await getEvents(program, signature)).find((e) => e.name === "poolClosed");
+      assert.exists(event, "PoolClosed emitted");
+      assert.isAbove(event!.data.sweptA.toNumber(), 0, "Locked dust swept");
+      assert.equal((await getTokenBalance(connection, creator.tokenA)) - tokenABefore, event!.data.sweptA.toNumber());
+
+      const entries = [];
+      const { pageCount } = await program.account.poolRegistry.fetch(poolRegistryPda(program));
+      for (let index = 0; index < pageCount; index++) {
+        const page = await program.account.poolRegistryPage.fetch(registryPagePda(program, index));
+        entries.push(...page.entries.filter((e) => e.pool.equals(pool.pool)));
+      }
+      assert.lengthOf(entries, 1);
+      assert.isTrue(entries[0].closedAt.eq(event!.data.closedAt), "Registry entry stamped closed");
+    });
+
+    it("The same pair and fee tier can be created again", async () => {
+      const recreated = await createPool(program, {
+        mintA: pool.mintA,
+        mintB: pool.mintB,
+        userTokenA: creator.tokenA,
+        userTokenB: creator.tokenB,
+        amountA: RESERVE,
+        amountB: RESERVE,
+      });
+
+      assert.isTrue(recreated.pool.equals(pool.pool), "Same pool address");
+      assert.isTrue(recreated.lpMint.equals(pool.lpMint), "Same LP mint address");
+      const poolData = await program.account.liquidityPool.fetch(recreated.pool);
+      assert.equal(poolData.reserveA.toNumber(), RESERVE);
+      assert.isAbove(await getTokenBalance(connection, lpTokenAccount(recreated, creator.user.publicKey)), 0);
+
+      // The new pool has its own live entry next to the closed one
+      const page = await program.account.poolRegistryPage.fetch(await registryPageOf(recreated.pool));
+      assert.isTrue(page.entries.some((e) => e.pool.equals(recreated.pool) && e.closedAt.isZero()));
+    });
+  });
+
+  // =========================================================================
+  // STABLESWAP POOLS (Module 2.11)
+  // =========================================================================
+
+  describe("StableSwap Pools (Module 2.11)", () => {
+    // Mirrors state::MAX_AMP_COEFFICIENT
+    const MAX_AMP_COEFFICIENT = 1_000_000;
+    const stable = (ampCoefficient: number): PoolCurve => ({ poolType: { stableSwap: {} }, ampCoefficient });
+
+    it("Rejects an amp coefficient outside its bounds", async () => {
+      await expectError(createCreatorPool(1_000_000, 1_000_000, 0, stable(0)), "InvalidAmpCoefficient");
+      await expectError(
+        createCreatorPool(1_000_000, 1_000_000, 0, stable(MAX_AMP_COEFFICIENT + 1)),
+        "InvalidAmpCoefficient"
+      );
+    });
+
+    it("Rejects an amp coefficient on a constant-product pool", async () => {
+      await expectError(
+        createCreatorPool(1_000_000, 1_000_000, 0, { poolType: { constantProduct: {} }, ampCoefficient: 100 }),
+        "InvalidAmpCoefficient"
+      );
+    });
+
+    it("Rejects tokens with different decimals", async () => {
+      await expectError(
+        createCreatorPool(1_000_000, 1_000_000, 0, stable(100), [9, 6]),
+        "StableSwapDecimalsMismatch"
+      );
+    });
+
+    it("A balanced first deposit mints D = x + y", async () => {
+      const { pool, creator } = await createCreatorPool(10_000_000, 10_000_000, 0, stable(100));
+      const poolData = await program.account.liquidityPool.fetch(pool.pool);
+
+      assert.equal(poolData.totalLpSupply.toNumber(), 20_000_000);
+      assert.equal(
+        await getTokenBalance(connection, lpTokenAccount(pool, creator.user.publicKey)),
+        20_000_000 - 1_000,
+        "Creator receives D minus the locked liquidity"
+      );
+    });
+
+    it("Pegged pair pays out more than a constant-product pool with the same reserves", async () => {
+      const RESERVE = 1_000_000_000_000;
+      const AMOUNT_IN = 10_000_000_000;
+
+      const received = async (curve?: PoolCurve) => {
+        const { pool, creator } = await createCreatorPool(RESERVE, RESERVE, AMOUNT_IN, curve);
+        const before = await getTokenBalance(connection, creator.tokenB);
+        await program.methods
+          .swap(new anchor.BN(AMOUNT_IN), new anchor.BN(0), true, null)
+          .accountsPartial(swapAccounts(pool, creator.user.publicKey, creator.tokenA, creator.tokenB, true))
+          .rpc();
+        return (await getTokenBalance(connection, creator.tokenB)) - before;
+      };
+
+      const stableOut = await received(stable(100));
+      const constantProductOut = await received();
+      assert.isAbove(stableOut, constantProductOut);
+      assert.isBelow(stableOut, AMOUNT_IN, "Still pays less than 1:1");
+    });
+  });
+
+  // =========================================================================
+  // WEIGHTED POOLS (Module 2.12)
+  // =========================================================================
+
+  describe("Weighted Pools (Module 2.12)", () => {
+    // Mirrors state::MIN_WEIGHT_BPS
+    const MIN_WEIGHT_BPS = 100;
+    const weighted = (weightABps: number): PoolCurve => ({ poolType: { weighted: {} }, weightABps });
+
+    it("Rejects weights outside the 1% - 99% range", async () => {
+      await expectError(
+        createCreatorPool(1_000_000, 1_000_000, 0, weighted(MIN_WEIGHT_BPS - 1)),
+        "InvalidPoolWeights"
+      );
+      await expectError(
+        createCreatorPool(1_000_000, 1_000_000, 0, weighted(10_000 - MIN_WEIGHT_BPS + 1)),
+        "InvalidPoolWeights"
+      );
+    });
+
+    it("Rejects curve parameters that belong to another pool type", async () => {
+      await expectError(
+        createCreatorPool(1_000_000, 1_000_000, 0, {
+          poolType: { weighted: {} },
+          ampCoefficient: 100,
+          weightABps: 8_000,
+        }),
+        "InvalidAmpCoefficient"
+      );
+      await expectError(
+        createCreatorPool(1_000_000, 1_000_000, 0, { poolType: { constantProduct: {} }, weightABps: 8_000 }),
+        "InvalidPoolWeights"
+      );
+    });
+
+    it("An 80/20 pool mints the weighted invariant a^0.8 * b^0.2", async () => {
+      const { pool } = await createCreatorPool(8_000_000_000, 2_000_000_000, 0, weighted(8_000));
+      const poolData = await program.account.liquidityPool.fetch(pool.pool);
+
+      assert.equal(poolData.weightABps, 8_000);
+      assert.equal(poolData.weightBBps, 2_000, "Token B takes the remaining weight");
+      // 8e9^0.8 * 2e9^0.2 = 6_062_866_266.04, rounded down by the pow error bound
+      assert.closeTo(poolData.totalLpSupply.toNumber(), 6_062_866_266, 2);
+    });
+
+    it("Rejects swaps above 30% of the input reserve", async () => {
+      const RESERVE = 1_000_000_000;
+      const { pool, creator } = await createCreatorPool(RESERVE, RESERVE, RESERVE, weighted(8_000));
+      const swap = (amountIn: number) =>
+        program.methods
+          .swap(new anchor.BN(amountIn), new anchor.BN(0), true, null)
+          .accountsPartial(swapAccounts(pool, creator.user.publicKey, creator.tokenA, creator.tokenB, true))
+          .rpc();
+
+      await expectError(swap(RESERVE / 2), "WeightedSwapTooLarge");
+      await swap(RESERVE / 10);
+    });
+  });
+
+  // =========================================================================
+  // DYNAMIC FEES (Module 2.14)
+  // =========================================================================
+
+  describe("Dynamic Fees (Module 2.14)", () => {
+    // Mirrors state::MAX_DYNAMIC_FEE_BPS
+    const MAX_DYNAMIC_FEE_BPS = 1_000;
+    const RESERVE = 1_000_000_000_000;
+
+    let pool: PoolFixture;
+    let creator: UserFixture;
+
+    const setDynamicFee = (enabled: boolean, minFeeBps: number, maxFeeBps: number) =>
+      program.methods
+        .setDynamicFee(enabled, minFeeBps, maxFeeBps)
+        .accountsPartial({ pool: pool.pool, authority: payer.publicKey })
+        .rpc();
+
+    // Swap 1% of the reserve and return the fee it was charged
+    async function swapFeeBps(isAToB: boolean): Promise<number> {
+      const signature = await program.methods
+        .swap(new anchor.BN(RESERVE / 100), new anchor.BN(0), isAToB, null)
+        .accountsPartial(swapAccounts(pool, creator.user.publicKey, creator.tokenA, creator.tokenB, isAToB))
+        .rpc({ commitment: "confirmed" });
+      const event = (await getEvents(program, signature)).find((e) => e.name === "swapExecuted");
+      return event!.data.feeBps;
+    }
+
+    before(async () => {
+      ({ pool, creator } = await createCreatorPool(RESERVE, RESERVE, RESERVE));
+    });
+
+    it("Rejects inverted, oversized or leftover bounds", async () => {
+      await expectError(setDynamicFee(true, 100, 5), "InvalidDynamicFeeBounds");
+      await expectError(setDynamicFee(true, 5, MAX_DYNAMIC_FEE_BPS + 1), "InvalidDynamicFeeBounds");
+      await expectError(setDynamicFee(false, 5, 100), "InvalidDynamicFeeBounds");
+    });
+
+    it("Only the pool authority can change the fee mode", async () => {
+      await expectError(
+        program.methods
+          .setDynamicFee(true, 5, 100)
+          .accountsPartial({ pool: pool.pool, authority: lp.user.publicKey })
+          .signers([lp.user])
+          .rpc(),
+        "InvalidAuthority"
+      );
+    });
+
+    it("A volatile swap raises the fee for the swaps after it", async () => {
+      await setDynamicFee(true, 5, 100);
+
+      assert.equal(await swapFeeBps(true), 5, "Calm pool charges min_fee_bps");
+      const poolData = await program.account.liquidityPool.fetch(pool.pool);
+      assert.isAbove(poolData.volatilityAccumulator.toNumber(), 0, "The swap's price movement is recorded");
+
+      assert.isAbove(await swapFeeBps(false), 5, "Next swap pays for the volatility");
+    });
+
+    it("Disabling dynamic fees restores the fee tier", async () => {
+      await setDynamicFee(false, 0, 0);
+      assert.equal(await swapFeeBps(true), pool.feeTierBps);
+    });
+  });
+
+  // =========================================================================
+  // POSITION EARNINGS (Module 2.15)
+  // =========================================================================
+
+  describe("Position Earnings (Module 2.15)", () => {
+    const RESERVE = 1_000_000_000_000;
+
+    let pool: PoolFixture;
+    let creator: UserFixture;
+
+    const earnings = (owner: UserFixture) =>
+      program.methods
+        .getPositionEarnings()
+        .accountsPartial({ position: positionPda(program, pool.pool, owner.user.publicKey), pool: pool.pool })
+        .view();
+
+    const swap = (amountIn: number, isAToB: boolean) =>
+      program.methods
+        .swap(new anchor.BN(amountIn), new anchor.BN(0), isAToB, null)
+        .accountsPartial(swapAccounts(pool, creator.user.publicKey, creator.tokenA, creator.tokenB, isAToB))
+        .rpc();
+
+    before(async () => {
+      ({ pool, creator } = await createCreatorPool(RESERVE, RESERVE, 2 * RESERVE));
+      // The creator's deposit opens the position earnings are reported for
+      await addLiquidity(program, pool, creator, RESERVE, RESERVE);
+    });
+
+    it("Fees accrue to the side that was swapped in", async () => {
+      await swap(RESERVE / 100, true);
+      const report = await earnings(creator);
+
+      assert.isAbove(report.feesEarnedA.toNumber(), 0);
+      assert.equal(report.feesEarnedB.toNumber(), 0);
+      assert.equal(report.depositedA.toNumber(), RESERVE);
+    });
+
+    it("Later deposits do not earn earlier fees", async () => {
+      const late = await createFundedUser(connection, payer.payer, pool.mintA, pool.mintB, RESERVE, RESERVE);
+      await addLiquidity(program, pool, late, RESERVE / 10, RESERVE / 10);
+      const report = await earnings(late);
+
+      assert.equal(report.feesEarnedA.toNumber(), 0);
+      assert.equal(report.feesEarnedB.toNumber(), 0);
+    });
+
+    it("A price move shows up as impermanent loss, reported apart from fees", async () => {
+      await swap(RESERVE / 2, false);
+      const report = await earnings(creator);
+
+      assert.isAbove(report.impermanentLossB.toNumber(), 0);
+      assert.isAbove(report.feesEarnedB.toNumber(), 0);
+    });
+  });
+});
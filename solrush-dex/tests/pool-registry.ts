@@ -0,0 +1,106 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+
+import { SolrushDex } from "../target/types/solrush_dex";
+import {
+  POOLS_PER_REGISTRY_PAGE,
+  PoolFixture,
+  createProviderPool,
+  ensureDexConfig,
+  getEvents,
+  poolRegistryPda,
+  registryPagePda,
+} from "./helpers/pool";
+
+// ============================================================================
+// TEST SUITE: POOL REGISTRY (Module 5.3)
+// ============================================================================
+
+describe("Module 5.3 - Pool Registry", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SolrushDex as Program<SolrushDex>;
+  const connection = provider.connection;
+
+  const RESERVE = 10_000;
+
+  const poolCount = async () =>
+    (await program.account.poolRegistry.fetch(poolRegistryPda(program))).poolCount.toNumber();
+
+  // PoolRegistered emitted by the transaction that created the pool
+  async function registeredEvent(pool: PoolFixture) {
+    const [creation] = await connection.getSignaturesForAddress(pool.pool, { limit: 1 }, "confirmed");
+    const event = (await getEvents(program, creation.signature)).find((e) => e.name === "poolRegistered");
+    assert.exists(event, "PoolRegistered emitted");
+    return event!.data;
+  }
+
+  before(async () => {
+    await ensureDexConfig(program);
+  });
+
+  it("Each pool is appended to the current page and announced with PoolRegistered", async () => {
+    const index = await poolCount();
+    const { pool } = await createProviderPool(program, RESERVE, RESERVE);
+
+    assert.equal(await poolCount(), index + 1, "pool_count advances by one");
+
+    const pageIndex = Math.floor(index / POOLS_PER_REGISTRY_PAGE);
+    const page = await program.account.poolRegistryPage.fetch(registryPagePda(program, pageIndex));
+    const entry = page.entries[index % POOLS_PER_REGISTRY_PAGE];
+    assert.equal(page.pageIndex, pageIndex);
+    assert.isTrue(entry.pool.equals(pool.pool));
+    assert.isTrue(entry.tokenAMint.equals(pool.mintA));
+    assert.isTrue(entry.tokenBMint.equals(pool.mintB));
+    assert.equal(entry.feeTierBps, pool.feeTierBps);
+
+    const event = await registeredEvent(pool);
+    assert.isTrue(event.pool.equals(pool.pool));
+    assert.isTrue(event.registryPage.equals(registryPagePda(program, pageIndex)));
+    assert.equal(event.poolIndex.toNumber(), index);
+    assert.isTrue(event.tokenAMint.equals(pool.mintA));
+    assert.isTrue(event.tokenBMint.equals(pool.mintB));
+    assert.equal(event.feeTierBps, pool.feeTierBps);
+    assert.isTrue(event.createdAt.eq(entry.createdAt));
+  });
+
+  it("A new page is allocated once the current one holds POOLS_PER_REGISTRY_PAGE pools", async () => {
+    // Fill the current page, then create the first pool of the next one
+    const remaining = POOLS_PER_REGISTRY_PAGE - ((await poolCount()) % POOLS_PER_REGISTRY_PAGE);
+    for (let i = 0; i < remaining - 1; i++) {
+      await createProviderPool(program, RESERVE, RESERVE);
+    }
+
+    const index = await poolCount();
+    const fullPageIndex = Math.floor(index / POOLS_PER_REGISTRY_PAGE);
+    const fullPage = await program.account.poolRegistryPage.fetch(registryPagePda(program, fullPageIndex));
+    const pagesBefore = (await program.account.poolRegistry.fetch(poolRegistryPda(program))).pageCount;
+    assert.equal(fullPage.entries.length, POOLS_PER_REGISTRY_PAGE - 1, "One slot left on the current page");
+
+    const { pool: last } = await createProviderPool(program, RESERVE, RESERVE);
+    assert.equal(
+      (await program.account.poolRegistryPage.fetch(registryPagePda(program, fullPageIndex))).entries.length,
+      POOLS_PER_REGISTRY_PAGE
+    );
+
+    const nextPage = registryPagePda(program, fullPageIndex + 1);
+    assert.isNull(await program.account.poolRegistryPage.fetchNullable(nextPage), "Pages are allocated lazily");
+
+    const { pool: first } = await createProviderPool(program, RESERVE, RESERVE);
+    const registry = await program.account.poolRegistry.fetch(poolRegistryPda(program));
+    const page = await program.account.poolRegistryPage.fetch(nextPage);
+
+    assert.equal(registry.poolCount.toNumber(), index + 2);
+    assert.equal(registry.pageCount, pagesBefore + 1, "page_count counts the new page");
+    assert.equal(page.pageIndex, fullPageIndex + 1);
+    assert.lengthOf(page.entries, 1);
+    assert.isTrue(page.entries[0].pool.equals(first.pool));
+    assert.isFalse(page.entries[0].pool.equals(last.pool));
+
+    const event = await registeredEvent(first);
+    assert.isTrue(event.registryPage.equals(nextPage));
+    assert.equal(event.poolIndex.toNumber(), index + 1);
+  });
+});
@@ -0,0 +1,444 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID, createAccount, mintTo } from "@solana/spl-token";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SYSVAR_RENT_PUBKEY, SystemProgram } from "@solana/web3.js";
+import { assert } from "chai";
+
+import { SolrushDex } from "../target/types/solrush_dex";
+import {
+  PoolFixture,
+  UserFixture,
+  createFundedUser,
+  createMintPair,
+  createPool,
+  createProviderPool,
+  dexConfigPda,
+  ensureDexConfig,
+  ensureRushConfig,
+  expectError,
+  getEvents,
+  getTokenBalance,
+  initializeDexConfig,
+  limitOrderPda,
+  rushConfigPda,
+  swapAccounts,
+} from "./helpers/pool";
+
+describe("Module 5 - Protocol Configuration", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SolrushDex as Program<SolrushDex>;
+  const connection = provider.connection;
+  const payer = provider.wallet as anchor.Wallet;
+
+  describe("Module 5.1 - Fee Tiers", () => {
+    const RESERVE = 100_000_000;
+    // Not in DexConfig::DEFAULT_FEE_TIERS
+    const UNLISTED_TIER = 7;
+
+    const outsider = Keypair.generate();
+
+    const updateFeeTiers = (method: "addFeeTier" | "removeFeeTier", tier: number, authority?: Keypair) =>
+      program.methods[method](tier)
+        .accountsPartial({ dexConfig: dexConfigPda(program), authority: (authority ?? payer.payer).publicKey })
+        .signers(authority ? [authority] : [])
+        .rpc({ commitment: "confirmed" });
+
+    const activeFeeTiers = async () => {
+      const config = await program.account.dexConfig.fetch(dexConfigPda(program));
+      return config.feeTiers.slice(0, config.feeTierCount);
+    };
+
+    before(async () => {
+      const sig = await connection.requestAirdrop(outsider.publicKey, 2 * LAMPORTS_PER_SOL);
+      await connection.confirmTransaction(sig, "confirmed");
+    });
+
+    it("Only the program upgrade authority can create the config", async () => {
+      // The gate is what stops an outsider on a fresh validator; once the
+      // config exists, its PDA can never be initialized again
+      const exists = await program.account.dexConfig.fetchNullable(dexConfigPda(program));
+      await expectError(
+        initializeDexConfig(program, outsider, outsider.publicKey),
+        exists ? "already in use" : "InvalidAuthority"
+      );
+
+      await ensureDexConfig(program);
+      const config = await program.account.dexConfig.fetch(dexConfigPda(program));
+      assert.isTrue(config.authority.equals(payer.publicKey), "Upgrade authority is the admin");
+    });
+
+    it("Each fee tier gets its own pool for the same pair", async () => {
+      const [mintA, mintB] = await createMintPair(connection, payer.payer);
+      const tokenA = await createAccount(connection, payer.payer, mintA, payer.publicKey);
+      const tokenB = await createAccount(connection, payer.payer, mintB, payer.publicKey);
+      await mintTo(connection, payer.payer, mintA, tokenA, payer.payer, 2 * RESERVE);
+      await mintTo(connection, payer.payer, mintB, tokenB, payer.payer, 2 * RESERVE);
+      const seed = { mintA, mintB, userTokenA: tokenA, userTokenB: tokenB, amountA: RESERVE, amountB: RESERVE };
+
+      const low = await createPool(program, { ...seed, feeTierBps: 5 });
+      const standard = await createPool(program, { ...seed, feeTierBps: 30 });
+
+      assert.isFalse(low.pool.equals(standard.pool));
+      assert.equal((await program.account.liquidityPool.fetch(low.pool)).feeTierBps, 5);
+      assert.equal((await program.account.liquidityPool.fetch(standard.pool)).feeTierBps, 30);
+    });
+
+    it("initialize_pool rejects tiers off the allowlist", async () => {
+      await expectError(
+        createProviderPool(program, RESERVE, RESERVE, 0, { feeTierBps: UNLISTED_TIER }),
+        "FeeTierNotAllowed"
+      );
+    });
+
+    it("Only the admin edits the allowlist", async () => {
+      await expectError(updateFeeTiers("addFeeTier", UNLISTED_TIER, outsider), "InvalidAuthority");
+      await expectError(updateFeeTiers("addFeeTier", 1_001), "InvalidFeeParameters");
+
+      const added = await updateFeeTiers("addFeeTier", UNLISTED_TIER);
+      const addedEvent = (await getEvents(program, added)).find((e) => e.name === "feeTierAdded");
+      assert.exists(addedEvent, "FeeTierAdded emitted");
+      assert.equal(addedEvent!.data.feeTierBps, UNLISTED_TIER);
+      assert.include(await activeFeeTiers(), UNLISTED_TIER);
+      await expectError(updateFeeTiers("addFeeTier", UNLISTED_TIER), "FeeTierAlreadyExists");
+
+      const { pool } = await createProviderPool(program, RESERVE, RESERVE, 0, { feeTierBps: UNLISTED_TIER });
+      assert.equal((await program.account.liquidityPool.fetch(pool.pool)).feeTierBps, UNLISTED_TIER);
+
+      await expectError(updateFeeTiers("removeFeeTier", UNLISTED_TIER, outsider), "InvalidAuthority");
+      const removed = await updateFeeTiers("removeFeeTier", UNLISTED_TIER);
+      assert.exists((await getEvents(program, removed)).find((e) => e.name === "feeTierRemoved"));
+      assert.notInclude(await activeFeeTiers(), UNLISTED_TIER);
+      await expectError(updateFeeTiers("removeFeeTier", UNLISTED_TIER), "FeeTierNotAllowed");
+
+      // Existing pools keep their tier, but no new ones can use it
+      await expectError(
+        createProviderPool(program, RESERVE, RESERVE, 0, { feeTierBps: UNLISTED_TIER }),
+        "FeeTierNotAllowed"
+      );
+    });
+
+    it("Swaps are charged the pool's fee tier", async () => {
+      const { pool } = await createProviderPool(program, RESERVE, RESERVE, 0, { feeTierBps: 100 });
+      const trader = await createFundedUser(connection, payer.payer, pool.mintA, pool.mintB, 1_000_000, 0);
+
+      const signature = await program.methods
+        .swap(new anchor.BN(1_000_000), new anchor.BN(0), true, null)
+        .accountsPartial(swapAccounts(pool, trader.user.publicKey, trader.tokenA, trader.tokenB, true))
+        .signers([trader.user])
+        .rpc({ commitment: "confirmed" });
+
+      const event = (await getEvents(program, signature)).find((e) => e.name === "swapExecuted");
+      assert.exists(event, "SwapExecuted emitted");
+      assert.equal(event!.data.feeBps, 100);
+      assert.equal(event!.data.feeAmount.toNumber(), 10_000, "1% of the input");
+    });
+
+    it("initialize_pool only accepts the pair in byte order", async () => {
+      const [mintA, mintB] = await createMintPair(connection, payer.payer);
+      const tokenA = await createAccount(connection, payer.payer, mintA, payer.publicKey);
+      const tokenB = await createAccount(connection, payer.payer, mintB, payer.publicKey);
+      await mintTo(connection, payer.payer, mintA, tokenA, payer.payer, RESERVE);
+      await mintTo(connection, payer.payer, mintB, tokenB, payer.payer, RESERVE);
+
+      await expectError(
+        createPool(program, {
+          mintA: mintB,
+          mintB: mintA,
+          userTokenA: tokenB,
+          userTokenB: tokenA,
+          amountA: RESERVE,
+          amountB: RESERVE,
+        }),
+        "InvalidMintOrder"
+      );
+
+      // The same pair in canonical order is accepted
+      const pool = await createPool(program, {
+        mintA,
+        mintB,
+        userTokenA: tokenA,
+        userTokenB: tokenB,
+        amountA: RESERVE,
+        amountB: RESERVE,
+      });
+      const poolData = await program.account.liquidityPool.fetch(pool.pool);
+      assert.isTrue(poolData.tokenAMint.equals(mintA));
+      assert.isTrue(poolData.tokenBMint.equals(mintB));
+    });
+  });
+
+  describe("Module 5.2 - Protocol Fee Switch", () => {
+    const RESERVE_A = 1_000_000_000;
+    const RESERVE_B = 5_000_000_000;
+    const TRADE_AMOUNT = 10_000_000;
+    // 20% of each 30 bps fee: 30_000 fee on TRADE_AMOUNT, 6_000 to the protocol
+    const SHARE_BPS = 2_000;
+    const PROTOCOL_FEE = 6_000;
+
+    let pool: PoolFixture;
+    let trader: UserFixture;
+    let treasury: PublicKey;
+    let treasuryA: PublicKey;
+    let treasuryB: PublicKey;
+
+    const setShare = (shareBps: number, authority?: Keypair) =>
+      program.methods
+        .setProtocolFeeShare(shareBps)
+        .accountsPartial({
+          dexConfig: dexConfigPda(program),
+          pool: pool.pool,
+          authority: (authority ?? payer.payer).publicKey,
+        })
+        .signers(authority ? [authority] : [])
+        .rpc({ commitment: "confirmed" });
+
+    const collect = () =>
+      program.methods
+        .collectProtocolFees()
+        .accountsPartial({
+          dexConfig: dexConfigPda(program),
+          pool: pool.pool,
+          tokenAMint: pool.mintA,
+          tokenBMint: pool.mintB,
+          tokenAVault: pool.vaultA,
+          tokenBVault: pool.vaultB,
+          treasuryTokenA: treasuryA,
+          treasuryTokenB: treasuryB,
+          authority: payer.publicKey,
+          tokenAProgram: TOKEN_PROGRAM_ID,
+          tokenBProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc({ commitment: "confirmed" });
+
+    // Protocol fees sit in the vault outside the curve reserve
+    const vaultSurplusA = async () => {
+      const poolData = await program.account.liquidityPool.fetch(pool.pool);
+      return (await getTokenBalance(connection, pool.vaultA)) - poolData.reserveA.toNumber();
+    };
+
+    before(async () => {
+      ({ pool } = await createProviderPool(program, RESERVE_A, RESERVE_B));
+      trader = await createFundedUser(connection, payer.payer, pool.mintA, pool.mintB, RESERVE_A, 0);
+
+      // Fresh accounts owned by the configured treasury
+      treasury = (await program.account.dexConfig.fetch(dexConfigPda(program))).treasury;
+      treasuryA = await createAccount(connection, payer.payer, pool.mintA, treasury, Keypair.generate());
+      treasuryB = await createAccount(connection, payer.payer, pool.mintB, treasury, Keypair.generate());
+    });
+
+    it("Only the DEX admin sets the share, up to 50% of the fee", async () => {
+      await expectError(setShare(SHARE_BPS, trader.user), "InvalidAuthority");
+      await expectError(setShare(5_001), "InvalidProtocolFeeShare");
+
+      const signature = await setShare(SHARE_BPS);
+      const event = (await getEvents(program, signature)).find((e) => e.name === "protocolFeeShareUpdated");
+      assert.exists(event, "ProtocolFeeShareUpdated emitted");
+      assert.equal(event!.data.previousShareBps, 0);
+      assert.equal(event!.data.newShareBps, SHARE_BPS);
+    });
+
+    it("Swaps accrue the protocol share outside the reserves", async () => {
+      await program.methods
+        .swap(new anchor.BN(TRADE_AMOUNT), new anchor.BN(0), true, null)
+        .accountsPartial(swapAccounts(pool, trader.user.publicKey, trader.tokenA, trader.tokenB, true))
+        .signers([trader.user])
+        .rpc();
+
+      const poolData = await program.account.liquidityPool.fetch(pool.pool);
+      assert.equal(poolData.protocolFeesA.toNumber(), PROTOCOL_FEE);
+      assert.equal(await vaultSurplusA(), PROTOCOL_FEE, "Vault holds the fee on top of the reserve");
+    });
+
+    it("Limit order fills pay the same protocol share as swaps", async () => {
+      const orderVault = Keypair.generate();
+      const limitOrder = limitOrderPda(program, pool.pool, trader.user.publicKey);
+      await program.methods
+        // Any positive price satisfies a sell target of 1
+        .createLimitOrder(new anchor.BN(TRADE_AMOUNT), new anchor.BN(1), new anchor.BN(1), new anchor.BN(1))
+        .accountsPartial({
+          pool: pool.pool,
+          limitOrder,
+          sellTokenMint: pool.mintA,
+          userTokenIn: trader.tokenA,
+          userTokenOut: trader.tokenB,
+          orderVault: orderVault.publicKey,
+          user: trader.user.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([trader.user, orderVault])
+        .rpc();
+
+      await program.methods
+        .executeLimitOrder()
+        .accountsPartial({
+          pool: pool.pool,
+          limitOrder,
+          orderVault: orderVault.publicKey,
+          userTokenOut: trader.tokenB,
+          poolVaultIn: pool.vaultA,
+          poolVaultOut: pool.vaultB,
+          sellTokenMint: pool.mintA,
+          buyTokenMint: pool.mintB,
+          sellTokenProgram: TOKEN_PROGRAM_ID,
+          buyTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      const poolData = await program.account.liquidityPool.fetch(pool.pool);
+      assert.equal(poolData.protocolFeesA.toNumber(), 2 * PROTOCOL_FEE);
+      assert.equal(await vaultSurplusA(), 2 * PROTOCOL_FEE);
+    });
+
+    it("collect_protocol_fees pays the treasury and resets the accrual", async () => {
+      const signature = await collect();
+
+      assert.equal(await getTokenBalance(connection, treasuryA), 2 * PROTOCOL_FEE);
+      assert.equal(await getTokenBalance(connection, treasuryB), 0);
+      const poolData = await program.account.liquidityPool.fetch(pool.pool);
+      assert.equal(poolData.protocolFeesA.toNumber(), 0);
+      assert.equal(await vaultSurplusA(), 0, "Reserves are untouched by the collection");
+
+      const event = (await getEvents(program, signature)).find((e) => e.name === "protocolFeesCollected");
+      assert.exists(event, "ProtocolFeesCollected emitted");
+      assert.isTrue(event!.data.pool.equals(pool.pool));
+      assert.isTrue(event!.data.treasury.equals(treasury));
+      assert.equal(event!.data.amountA.toNumber(), 2 * PROTOCOL_FEE);
+      assert.equal(event!.data.amountB.toNumber(), 0);
+
+      await expectError(collect(), "NoProtocolFees");
+    });
+  });
+
+  describe("Module 5.5 - Two-Step Authority Transfer", () => {
+    const RESERVE = 100_000_000;
+
+    const successor = Keypair.generate();
+    const stranger = Keypair.generate();
+
+    // Decoded event of the given name from a confirmed transaction
+    const eventIn = async (signature: string, name: string) => {
+      const event = (await getEvents(program, signature)).find((e) => e.name === name);
+      assert.exists(event, `${name} emitted`);
+      return event!.data;
+    };
+
+    const poolTransfers = (pool: PublicKey) => ({
+      propose: (newAuthority: PublicKey, authority?: Keypair) =>
+        program.methods
+          .proposePoolAuthority(newAuthority)
+          .accountsPartial({ pool, authority: (authority ?? payer.payer).publicKey })
+          .signers(authority ? [authority] : [])
+          .rpc({ commitment: "confirmed" }),
+      accept: (newAuthority: Keypair) =>
+        program.methods
+          .acceptPoolAuthority()
+          .accountsPartial({ pool, newAuthority: newAuthority.publicKey })
+          .signers([newAuthority])
+          .rpc({ commitment: "confirmed" }),
+      cancel: (authority?: Keypair) =>
+        program.methods
+          .cancelPoolAuthorityTransfer()
+          .accountsPartial({ pool, authority: (authority ?? payer.payer).publicKey })
+          .signers(authority ? [authority] : [])
+          .rpc({ commitment: "confirmed" }),
+    });
+
+    const rushTransfers = () => ({
+      propose: (newAuthority: PublicKey, authority?: Keypair) =>
+        program.methods
+          .proposeRushAuthority(newAuthority)
+          .accountsPartial({ rushConfig: rushConfigPda(program), authority: (authority ?? payer.payer).publicKey })
+          .signers(authority ? [authority] : [])
+          .rpc({ commitment: "confirmed" }),
+      accept: (newAuthority: Keypair) =>
+        program.methods
+          .acceptRushAuthority()
+          .accountsPartial({ rushConfig: rushConfigPda(program), newAuthority: newAuthority.publicKey })
+          .signers([newAuthority])
+          .rpc({ commitment: "confirmed" }),
+      cancel: (authority?: Keypair) =>
+        program.methods
+          .cancelRushAuthorityTransfer()
+          .accountsPartial({ rushConfig: rushConfigPda(program), authority: (authority ?? payer.payer).publicKey })
+          .signers(authority ? [authority] : [])
+          .rpc({ commitment: "confirmed" }),
+    });
+
+    it("Pool authority only moves once the proposed key accepts", async () => {
+      const { pool } = await createProviderPool(program, RESERVE, RESERVE);
+      const transfers = poolTransfers(pool.pool);
+
+      await expectError(transfers.propose(successor.publicKey, stranger), "InvalidAuthority");
+      await expectError(transfers.propose(PublicKey.default), "InvalidProposedAuthority");
+      await expectError(transfers.propose(payer.publicKey), "InvalidProposedAuthority");
+      await expectError(transfers.accept(successor), "NoPendingAuthority");
+
+      const proposed = await eventIn(await transfers.propose(successor.publicKey), "authorityProposed");
+      assert.isTrue(proposed.account.equals(pool.pool));
+      assert.isTrue(proposed.currentAuthority.equals(payer.publicKey));
+      assert.isTrue(proposed.proposedAuthority.equals(successor.publicKey));
+
+      let poolData = await program.account.liquidityPool.fetch(pool.pool);
+      assert.isTrue(poolData.authority.equals(payer.publicKey), "Authority unchanged after propose");
+      assert.isTrue(poolData.pendingAuthority.equals(successor.publicKey));
+
+      await expectError(transfers.accept(stranger), "NotPendingAuthority");
+      const transferred = await eventIn(await transfers.accept(successor), "authorityTransferred");
+      assert.isTrue(transferred.previousAuthority.equals(payer.publicKey));
+      assert.isTrue(transferred.newAuthority.equals(successor.publicKey));
+
+      poolData = await program.account.liquidityPool.fetch(pool.pool);
+      assert.isTrue(poolData.authority.equals(successor.publicKey));
+      assert.isTrue(poolData.pendingAuthority.equals(PublicKey.default), "Pending slot cleared");
+      await expectError(transfers.propose(stranger.publicKey), "InvalidAuthority");
+    });
+
+    it("A pending pool proposal can be cancelled or replaced", async () => {
+      const { pool } = await createProviderPool(program, RESERVE, RESERVE);
+      const transfers = poolTransfers(pool.pool);
+
+      await expectError(transfers.cancel(), "NoPendingAuthority");
+      await transfers.propose(stranger.publicKey);
+      await expectError(transfers.cancel(stranger), "InvalidAuthority");
+
+      const cancelled = await eventIn(await transfers.cancel(), "authorityProposalCancelled");
+      assert.isTrue(cancelled.authority.equals(payer.publicKey));
+      assert.isTrue(cancelled.cancelledAuthority.equals(stranger.publicKey));
+      await expectError(transfers.accept(stranger), "NoPendingAuthority");
+
+      // A second proposal replaces the first
+      await transfers.propose(stranger.publicKey);
+      await transfers.propose(successor.publicKey);
+      await expectError(transfers.accept(stranger), "NotPendingAuthority");
+      await transfers.accept(successor);
+      assert.isTrue((await program.account.liquidityPool.fetch(pool.pool)).authority.equals(successor.publicKey));
+    });
+
+    it("RUSH rewards authority follows the same flow", async () => {
+      await ensureRushConfig(program);
+      const transfers = rushTransfers();
+      const rushAuthority = async () => (await program.account.rushConfig.fetch(rushConfigPda(program))).authority;
+
+      await expectError(transfers.propose(successor.publicKey, stranger), "InvalidAuthority");
+      await transfers.propose(successor.publicKey);
+      await transfers.cancel();
+      await expectError(transfers.accept(successor), "NoPendingAuthority");
+
+      await transfers.propose(successor.publicKey);
+      await expectError(transfers.accept(stranger), "NotPendingAuthority");
+      const transferred = await eventIn(await transfers.accept(successor), "authorityTransferred");
+      assert.isTrue(transferred.account.equals(rushConfigPda(program)));
+      assert.isTrue((await rushAuthority()).equals(successor.publicKey));
+      await expectError(transfers.propose(stranger.publicKey), "InvalidAuthority");
+
+      // Hand the shared config back to the provider wallet for the other suites
+      await transfers.propose(payer.publicKey, successor);
+      await transfers.accept(payer.payer);
+      assert.isTrue((await rushAuthority()).equals(payer.publicKey));
+    });
+  });
+});
@@ -0,0 +1,241 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  transferChecked,
+} from "@solana/spl-token";
+import { SystemProgram } from "@solana/web3.js";
+import { assert } from "chai";
+
+import { SolrushDex } from "../target/types/solrush_dex";
+import {
+  PoolFixture,
+  UserFixture,
+  createFundedUser,
+  createProviderPool,
+  ensureRushConfig,
+  expectError,
+  getEvents,
+  getTokenBalance,
+  lpStakeVaultPda,
+  lpTokenAccount,
+  positionPda,
+  rushConfigPda,
+} from "./helpers/pool";
+
+// ============================================================================
+// TEST SUITE: LP STAKING AND RUSH CLAIMS AGAINST THE DEPLOYED PROGRAM (Module 4.7)
+// ============================================================================
+
+describe("Module 4.7 - LP Staking", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SolrushDex as Program<SolrushDex>;
+  const connection = provider.connection;
+  const payer = provider.wallet as anchor.Wallet;
+
+  const RESERVE = 100_000_000;
+  const LP_DECIMALS = 6;
+
+  let pool: PoolFixture;
+  let creator: UserFixture;
+  let recipient: UserFixture;
+  let rushMint: anchor.web3.PublicKey;
+  let stakeVault: anchor.web3.PublicKey;
+
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  const stakingAccounts = (owner: UserFixture) => ({
+    pool: pool.pool,
+    rushConfig: rushConfigPda(program),
+    lpTokenMint: pool.lpMint,
+    userPosition: positionPda(program, pool.pool, owner.user.publicKey),
+    lpStakeVault: stakeVault,
+    userLpTokenAccount: lpTokenAccount(pool, owner.user.publicKey),
+    user: owner.user.publicKey,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+  });
+
+  const stake = (owner: UserFixture, amount: number) =>
+    program.methods
+      .stakeLp(new anchor.BN(amount))
+      .accountsPartial({ ...stakingAccounts(owner), systemProgram: SystemProgram.programId })
+      .signers([owner.user])
+      .rpc({ commitment: "confirmed" });
+
+  const unstake = (owner: UserFixture, amount: number) =>
+    program.methods
+      .unstakeLp(new anchor.BN(amount))
+      .accountsPartial(stakingAccounts(owner))
+      .signers([owner.user])
+      .rpc({ commitment: "confirmed" });
+
+  const rushAccount = (owner: UserFixture) =>
+    getAssociatedTokenAddressSync(rushMint, owner.user.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  const claim = (owner: UserFixture) =>
+    program.methods
+      .claimRushRewards()
+      .accountsPartial({
+        position: positionPda(program, pool.pool, owner.user.publicKey),
+        pool: pool.pool,
+        rushConfig: rushConfigPda(program),
+        rushMint,
+        userRushAccount: rushAccount(owner),
+        user: owner.user.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner.user])
+      .rpc({ commitment: "confirmed" });
+
+  const position = (owner: UserFixture) =>
+    program.account.userLiquidityPosition.fetch(positionPda(program, pool.pool, owner.user.publicKey));
+
+  const rushBalance = async (owner: UserFixture) => {
+    const account = await connection.getAccountInfo(rushAccount(owner));
+    return account ? getTokenBalance(connection, rushAccount(owner)) : 0;
+  };
+
+  before(async () => {
+    rushMint = await ensureRushConfig(program);
+    // Another suite may have left rewards paused
+    if ((await program.account.rushConfig.fetch(rushConfigPda(program))).isPaused) {
+      await program.methods
+        .pauseRushRewards()
+        .accountsPartial({ rushConfig: rushConfigPda(program), authority: payer.publicKey })
+        .rpc();
+    }
+
+    ({ pool, creator } = await createProviderPool(program, RESERVE, RESERVE));
+    stakeVault = lpStakeVaultPda(program, pool.pool);
+    recipient = await createFundedUser(connection, payer.payer, pool.mintA, pool.mintB, 0, 0);
+  });
+
+  it("Staking escrows LP in the stake vault", async () => {
+    const walletBefore = await getTokenBalance(connection, lpTokenAccount(pool, creator.user.publicKey));
+    const amount = Math.floor(walletBefore / 2);
+
+    const signature = await stake(creator, amount);
+
+    assert.equal(await getTokenBalance(connection, stakeVault), amount, "Vault holds the staked LP");
+    assert.equal(
+      await getTokenBalance(connection, lpTokenAccount(pool, creator.user.publicKey)),
+      walletBefore - amount
+    );
+    assert.equal((await position(creator)).stakedLp.toNumber(), amount);
+    assert.equal((await program.account.liquidityPool.fetch(pool.pool)).totalStakedLp.toNumber(), amount);
+
+    const event = (await getEvents(program, signature)).find((e) => e.name === "lpStaked");
+    assert.exists(event, "LpStaked emitted");
+    assert.equal(event!.data.amount.toNumber(), amount);
+    assert.equal(event!.data.totalStaked.toNumber(), amount);
+  });
+
+  it("Transferred LP can be staked by a wallet that never deposited", async () => {
+    const amount = 1_000_000;
+    const destination = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer.payer,
+      pool.lpMint,
+      recipient.user.publicKey,
+      false,
+      "confirmed",
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await transferChecked(
+      connection,
+      payer.payer,
+      lpTokenAccount(pool, creator.user.publicKey),
+      pool.lpMint,
+      destination.address,
+      creator.user,
+      amount,
+      LP_DECIMALS,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    // Holding LP in the wallet opens no position and earns nothing
+    assert.isNull(
+      await program.account.userLiquidityPosition.fetchNullable(
+        positionPda(program, pool.pool, recipient.user.publicKey)
+      )
+    );
+
+    const vaultBefore = await getTokenBalance(connection, stakeVault);
+    await stake(recipient, amount);
+
+    const recipientPosition = await position(recipient);
+    assert.isTrue(recipientPosition.owner.equals(recipient.user.publicKey), "Position opened by stake_lp");
+    assert.equal(recipientPosition.stakedLp.toNumber(), amount);
+    assert.equal(await getTokenBalance(connection, stakeVault), vaultBefore + amount);
+
+    await sleep(2_000);
+    const pending = await program.methods
+      .calculatePendingRewards()
+      .accountsPartial({
+        position: positionPda(program, pool.pool, recipient.user.publicKey),
+        pool: pool.pool,
+        rushConfig: rushConfigPda(program),
+      })
+      .view();
+    assert.isTrue(pending.gtn(0), "Staked transferred LP earns RUSH");
+  });
+
+  it("Claiming mints the accrued RUSH and reports the staked balance", async () => {
+    await sleep(2_000);
+    const before = await rushBalance(creator);
+
+    const signature = await claim(creator);
+    const minted = (await rushBalance(creator)) - before;
+    assert.isAbove(minted, 0);
+
+    const event = (await getEvents(program, signature)).find((e) => e.name === "rewardsClaimed");
+    assert.exists(event, "RewardsClaimed emitted");
+    assert.equal(event!.data.rewardsAmount.toNumber(), minted);
+    assert.isTrue(event!.data.stakedLp.eq((await position(creator)).stakedLp));
+    assert.isTrue(event!.data.totalStakedLp.eq((await program.account.liquidityPool.fetch(pool.pool)).totalStakedLp));
+
+    const creatorPosition = await position(creator);
+    assert.equal(creatorPosition.unclaimedRewards.toNumber(), 0);
+    assert.equal(creatorPosition.totalRushClaimed.toNumber(), before + minted);
+  });
+
+  it("Unstaking returns LP, settles rewards, and stops accrual", async () => {
+    const staked = (await position(recipient)).stakedLp.toNumber();
+    const vaultBefore = await getTokenBalance(connection, stakeVault);
+    const walletBefore = await getTokenBalance(connection, lpTokenAccount(pool, recipient.user.publicKey));
+
+    await expectError(unstake(recipient, staked + 1), "InsufficientStakedBalance");
+    const signature = await unstake(recipient, staked);
+
+    assert.equal(await getTokenBalance(connection, stakeVault), vaultBefore - staked);
+    assert.equal(
+      await getTokenBalance(connection, lpTokenAccount(pool, recipient.user.publicKey)),
+      walletBefore + staked
+    );
+
+    const event = (await getEvents(program, signature)).find((e) => e.name === "lpUnstaked");
+    assert.exists(event, "LpUnstaked emitted");
+    const settled = event!.data.rewardsSettled.toNumber();
+    assert.isAbove(settled, 0, "Rewards earned while staked are settled, not forfeited");
+
+    const unstaked = await position(recipient);
+    assert.equal(unstaked.stakedLp.toNumber(), 0);
+    assert.equal(unstaked.unclaimedRewards.toNumber(), settled);
+
+    // The settled amount is paid exactly once, and nothing more accrues
+    await sleep(1_000);
+    await claim(recipient);
+    assert.equal(await rushBalance(recipient), settled);
+    await expectError(claim(recipient), "InvalidAmount");
+  });
+});
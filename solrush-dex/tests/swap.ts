@@ -120,7 +120,7 @@ describe("Swap", () => {
     );
 
     await program.methods
-      .swap(new anchor.BN(swapAmount), new anchor.BN(minimumAmountOut), true)
+      .swap(new anchor.BN(swapAmount), new anchor.BN(minimumAmountOut), true, null)
       .accounts({
         pool: poolAccount,
         userTokenIn: userTokenAAccount,
@@ -153,7 +153,7 @@ describe("Swap", () => {
     );
 
     await program.methods
-      .swap(new anchor.BN(swapAmount), new anchor.BN(minimumAmountOut), false)
+      .swap(new anchor.BN(swapAmount), new anchor.BN(minimumAmountOut), false, null)
       .accounts({
         pool: poolAccount,
         userTokenIn: userTokenBAccount,
@@ -183,7 +183,7 @@ describe("Swap", () => {
 
     try {
       await program.methods
-        .swap(new anchor.BN(swapAmount), new anchor.BN(minimumAmountOut), true)
+        .swap(new anchor.BN(swapAmount), new anchor.BN(minimumAmountOut), true, null)
         .accounts({
           pool: poolAccount,
           userTokenIn: userTokenAAccount,
@@ -208,7 +208,7 @@ describe("Swap", () => {
   it("should reject zero amount swap", async () => {
     try {
       await program.methods
-        .swap(new anchor.BN(0), new anchor.BN(0), true)
+        .swap(new anchor.BN(0), new anchor.BN(0), true, null)
         .accounts({
           pool: poolAccount,
           userTokenIn: userTokenAAccount,
@@ -236,7 +236,7 @@ describe("Swap", () => {
     const minOut1 = 200 * 10 ** TOKEN_B_DECIMALS;
 
     await program.methods
-      .swap(new anchor.BN(amount1), new anchor.BN(minOut1), true)
+      .swap(new anchor.BN(amount1), new anchor.BN(minOut1), true, null)
       .accounts({
         pool: poolAccount,
         userTokenIn: userTokenAAccount,
@@ -253,7 +253,7 @@ describe("Swap", () => {
     const minOut2 = 30 * 10 ** TOKEN_A_DECIMALS;
 
     await program.methods
-      .swap(new anchor.BN(amount2), new anchor.BN(minOut2), false)
+      .swap(new anchor.BN(amount2), new anchor.BN(minOut2), false, null)
       .accounts({
         pool: poolAccount,
         userTokenIn: userTokenBAccount,
@@ -270,7 +270,7 @@ describe("Swap", () => {
     const minOut3 = 250 * 10 ** TOKEN_B_DECIMALS;
 
     await program.methods
-      .swap(new anchor.BN(amount3), new anchor.BN(minOut3), true)
+      .swap(new anchor.BN(amount3), new anchor.BN(minOut3), true, null)
       .accounts({
         pool: poolAccount,
         userTokenIn: userTokenAAccount,
@@ -0,0 +1,48 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TOKEN_2022_PROGRAM_ID, getTokenMetadata } from "@solana/spl-token";
+import { assert } from "chai";
+
+import { SolrushDex } from "../target/types/solrush_dex";
+import { createProviderPool, ensureRushConfig, expectError, rushConfigPda } from "./helpers/pool";
+
+// ============================================================================
+// TEST SUITE: TOKEN-2022 METADATA ON THE LP AND RUSH MINTS
+// ============================================================================
+
+describe("Token Metadata", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SolrushDex as Program<SolrushDex>;
+  const connection = provider.connection;
+
+  const RESERVE = 10_000_000;
+
+  it("LP mint carries the pair name and symbol", async () => {
+    const { pool } = await createProviderPool(program, RESERVE, RESERVE, 0, { symbols: ["SOL", "USDC"] });
+
+    const metadata = await getTokenMetadata(connection, pool.lpMint, "confirmed", TOKEN_2022_PROGRAM_ID);
+    assert.exists(metadata, "Metadata extension initialized on the LP mint");
+    assert.equal(metadata!.name, "SolRush SOL-USDC LP");
+    assert.equal(metadata!.symbol, "SOL-USDC-LP");
+    assert.isTrue(metadata!.mint.equals(pool.lpMint));
+    assert.isTrue(metadata!.updateAuthority!.equals(pool.pool), "Pool PDA controls the metadata");
+  });
+
+  it("Pair symbols are validated", async () => {
+    for (const symbols of [["", "USDC"], ["TOOLONGSYMBOL", "USDC"], ["SOL LP", "USDC"]] as [string, string][]) {
+      await expectError(createProviderPool(program, RESERVE, RESERVE, 0, { symbols }), "InvalidTokenSymbol");
+    }
+  });
+
+  it("RUSH mint carries its name and symbol", async () => {
+    const rushMint = await ensureRushConfig(program);
+
+    const metadata = await getTokenMetadata(connection, rushMint, "confirmed", TOKEN_2022_PROGRAM_ID);
+    assert.exists(metadata, "Metadata extension initialized on the RUSH mint");
+    assert.equal(metadata!.name, "SolRush");
+    assert.equal(metadata!.symbol, "RUSH");
+    assert.isTrue(metadata!.updateAuthority!.equals(rushConfigPda(program)), "RUSH config controls the metadata");
+  });
+});
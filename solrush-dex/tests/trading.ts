@@ -1,29 +1,35 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
-import { Token, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID } from "@solana/spl-token";
-import { PublicKey, SystemProgram, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, createAccount, mintTo } from "@solana/spl-token";
+import { Keypair, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
 import assert from "assert";
 
 import { SolrushDex } from "../target/types/solrush_dex";
+import {
+  PoolFixture,
+  createFundedUser,
+  createMintPair,
+  createPool,
+  expectError,
+  getTokenBalance,
+  limitOrderPda,
+  swapAccounts,
+} from "./helpers/pool";
 
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
 
-async function derivePDA(seeds: (Buffer | Uint8Array)[], programId: PublicKey) {
-  const [pda] = await PublicKey.findProgramAddress(seeds, programId);
-  return pda;
-}
-
-async function getTokenBalance(connection: any, tokenAccount: PublicKey): Promise<number> {
-  const balance = await connection.getTokenAccountBalance(tokenAccount);
-  return parseFloat(balance.value.amount);
-}
-
 function formatAmount(amount: number, decimals: number = 6): number {
   return amount / Math.pow(10, decimals);
 }
 
+// Constant-product quote at the pool's fee tier, used to set slippage bounds
+function quoteOut(reserveIn: number, reserveOut: number, amountIn: number, feeBps: number): number {
+  const amountInWithFee = amountIn * (10_000 - feeBps);
+  return Math.floor((reserveOut * amountInWithFee) / (reserveIn * 10_000 + amountInWithFee));
+}
+
 // ============================================================================
 // TEST SUITE: TRADING (Module 3)
 // ============================================================================
@@ -37,22 +43,35 @@ describe("Trading - Module 3", () => {
   const payer = provider.wallet as anchor.Wallet;
 
   // Test data
-  let tokenA: Token;           // SOL equivalent
-  let tokenB: Token;           // USDC
+  let tokenAMint: PublicKey;   // SOL equivalent
+  let tokenBMint: PublicKey;   // USDC
   let userTokenAAccount: PublicKey;
   let userTokenBAccount: PublicKey;
+  let fixture: PoolFixture;
+  let poolAccount: PublicKey;
   let poolTokenAVault: PublicKey;
   let poolTokenBVault: PublicKey;
-  let poolAccount: PublicKey;
-  let lpTokenMint: PublicKey;
-  let userLPTokenAccount: PublicKey;
-  let poolBump: number;
-  let lpBump: number;
 
   const TOKEN_A_DECIMALS = 6;
   const TOKEN_B_DECIMALS = 6;
   const INITIAL_AMOUNT_A = 1000 * 10 ** TOKEN_A_DECIMALS;     // 1000 SOL
   const INITIAL_AMOUNT_B = 25000 * 10 ** TOKEN_B_DECIMALS;    // 25000 USDC
+  const FEE_TIER_BPS = 30;                                     // 0.3%
+
+  // Swap accounts for the provider wallet in the given direction
+  const swapAccountsFor = (isAToB: boolean) =>
+    swapAccounts(fixture, payer.publicKey, userTokenAAccount, userTokenBAccount, isAToB);
+
+  // Quote against the live reserves, minus 1% slippage
+  async function minimumOut(amountIn: number, isAToB: boolean): Promise<number> {
+    const poolData = await program.account.liquidityPool.fetch(poolAccount);
+    const reserveA = poolData.reserveA.toNumber();
+    const reserveB = poolData.reserveB.toNumber();
+    const quote = isAToB
+      ? quoteOut(reserveA, reserveB, amountIn, FEE_TIER_BPS)
+      : quoteOut(reserveB, reserveA, amountIn, FEE_TIER_BPS);
+    return Math.floor((quote * 99) / 100);
+  }
 
   // =========================================================================
   // SETUP: Initialize tokens, pool, and liquidity
@@ -61,104 +80,37 @@ describe("Trading - Module 3", () => {
   before(async () => {
     console.log("\n📋 Setting up test environment...");
 
-    // Create Token A (SOL equivalent)
-    tokenA = await Token.createMint(
-      connection,
-      payer.payer,
-      payer.publicKey,
-      null,
-      TOKEN_A_DECIMALS,
-      TOKEN_PROGRAM_ID
-    );
-
-    // Create Token B (USDC)
-    tokenB = await Token.createMint(
-      connection,
-      payer.payer,
-      payer.publicKey,
-      null,
-      TOKEN_B_DECIMALS,
-      TOKEN_PROGRAM_ID
-    );
-
-    // Create user token accounts
-    userTokenAAccount = await tokenA.createAccount(payer.publicKey);
-    userTokenBAccount = await tokenB.createAccount(payer.publicKey);
-
-    // Mint initial amounts to user
-    await tokenA.mintTo(userTokenAAccount, payer.publicKey, [], INITIAL_AMOUNT_A);
-    await tokenB.mintTo(userTokenBAccount, payer.publicKey, [], INITIAL_AMOUNT_B);
-
-    // Derive pool PDA
-    const [pda, bump] = await PublicKey.findProgramAddress(
-      [
-        Buffer.from("pool"),
-        tokenA.publicKey.toBuffer(),
-        tokenB.publicKey.toBuffer(),
-      ],
-      program.programId
-    );
-    poolAccount = pda;
-    poolBump = bump;
-
-    // Derive vault accounts
-    const [vaultA] = await PublicKey.findProgramAddress(
-      [Buffer.from("vault_a"), poolAccount.toBuffer()],
-      program.programId
-    );
-    poolTokenAVault = vaultA;
-
-    const [vaultB] = await PublicKey.findProgramAddress(
-      [Buffer.from("vault_b"), poolAccount.toBuffer()],
-      program.programId
-    );
-    poolTokenBVault = vaultB;
-
-    // Derive LP token mint
-    const [lpMint, lpBmp] = await PublicKey.findProgramAddress(
-      [Buffer.from("lp_mint"), poolAccount.toBuffer()],
-      program.programId
-    );
-    lpTokenMint = lpMint;
-    lpBump = lpBmp;
-
-    // Create user LP token account
-    userLPTokenAccount = await Token.getAssociatedTokenAddress(
-      ASSOCIATED_TOKEN_PROGRAM_ID,
-      TOKEN_PROGRAM_ID,
-      lpTokenMint,
-      payer.publicKey
-    );
-
-    // Initialize pool
-    const initLpTokens = Math.floor(
-      Math.sqrt(INITIAL_AMOUNT_A * INITIAL_AMOUNT_B)
-    );
-
-    const tx = await program.methods
-      .initializePool(new anchor.BN(INITIAL_AMOUNT_A), new anchor.BN(INITIAL_AMOUNT_B))
-      .accounts({
-        pool: poolAccount,
-        tokenAMint: tokenA.publicKey,
-        tokenBMint: tokenB.publicKey,
-        lpTokenMint: lpTokenMint,
-        userTokenA: userTokenAAccount,
-        userTokenB: userTokenBAccount,
-        poolVaultA: poolTokenAVault,
-        poolVaultB: poolTokenBVault,
-        userLpTokenAccount: userLPTokenAccount,
-        user: payer.publicKey,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
-        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-      })
-      .rpc();
+    // Mints are created in pool order so token A is always the SOL side
+    [tokenAMint, tokenBMint] = await createMintPair(connection, payer.payer, TOKEN_A_DECIMALS);
+
+    // Create user token accounts and mint enough for seeding and trading
+    userTokenAAccount = await createAccount(connection, payer.payer, tokenAMint, payer.publicKey);
+    userTokenBAccount = await createAccount(connection, payer.payer, tokenBMint, payer.publicKey);
+    await mintTo(connection, payer.payer, tokenAMint, userTokenAAccount, payer.payer, 2 * INITIAL_AMOUNT_A);
+    await mintTo(connection, payer.payer, tokenBMint, userTokenBAccount, payer.payer, 2 * INITIAL_AMOUNT_B);
+
+    // Initialize pool at the 0.3% tier (registers it in the pool registry)
+    fixture = await createPool(program, {
+      mintA: tokenAMint,
+      mintB: tokenBMint,
+      userTokenA: userTokenAAccount,
+      userTokenB: userTokenBAccount,
+      amountA: INITIAL_AMOUNT_A,
+      amountB: INITIAL_AMOUNT_B,
+      feeTierBps: FEE_TIER_BPS,
+    });
+    poolAccount = fixture.pool;
+    poolTokenAVault = fixture.vaultA;
+    poolTokenBVault = fixture.vaultB;
+
+    const poolData = await program.account.liquidityPool.fetch(poolAccount);
+    assert.ok(poolData.reserveA.toNumber() === INITIAL_AMOUNT_A, "Reserve A seeded");
+    assert.ok(poolData.reserveB.toNumber() === INITIAL_AMOUNT_B, "Reserve B seeded");
 
     console.log(`✅ Pool initialized with:`);
     console.log(`   Token A: ${formatAmount(INITIAL_AMOUNT_A)} units`);
     console.log(`   Token B: ${formatAmount(INITIAL_AMOUNT_B)} units`);
-    console.log(`   LP tokens: ${formatAmount(initLpTokens)}`);
+    console.log(`   LP tokens: ${formatAmount(poolData.totalLpSupply.toNumber())}`);
   });
 
   // =========================================================================
@@ -170,54 +122,38 @@ describe("Trading - Module 3", () => {
     it("✅ Swap SOL → USDC (Token A → Token B)", async () => {
       console.log("\n🔄 Testing swap A→B...");
       const swapAmount = 100 * 10 ** TOKEN_A_DECIMALS;
-      const minimumAmountOut = 2400 * 10 ** TOKEN_B_DECIMALS;
+      const minimumAmountOut = await minimumOut(swapAmount, true);
 
       const balanceBefore = await getTokenBalance(connection, userTokenBAccount);
 
       await program.methods
-        .swap(new anchor.BN(swapAmount), new anchor.BN(minimumAmountOut), true)
-        .accounts({
-          pool: poolAccount,
-          userTokenIn: userTokenAAccount,
-          userTokenOut: userTokenBAccount,
-          poolVaultIn: poolTokenAVault,
-          poolVaultOut: poolTokenBVault,
-          user: payer.publicKey,
-          tokenProgram: TOKEN_PROGRAM_ID,
-        })
+        .swap(new anchor.BN(swapAmount), new anchor.BN(minimumAmountOut), true, null)
+        .accountsPartial(swapAccountsFor(true))
         .rpc();
 
       const balanceAfter = await getTokenBalance(connection, userTokenBAccount);
       const received = balanceAfter - balanceBefore;
 
-      assert.ok(received > minimumAmountOut, "Should receive at least minimum amount");
+      assert.ok(received >= minimumAmountOut, "Should receive at least minimum amount");
       console.log(`   ✓ Received ${formatAmount(received)} USDC`);
     });
 
     it("✅ Swap USDC → SOL (Token B → Token A)", async () => {
       console.log("\n🔄 Testing swap B→A...");
       const swapAmount = 5000 * 10 ** TOKEN_B_DECIMALS;
-      const minimumAmountOut = 195 * 10 ** TOKEN_A_DECIMALS;
+      const minimumAmountOut = await minimumOut(swapAmount, false);
 
       const balanceBefore = await getTokenBalance(connection, userTokenAAccount);
 
       await program.methods
-        .swap(new anchor.BN(swapAmount), new anchor.BN(minimumAmountOut), false)
-        .accounts({
-          pool: poolAccount,
-          userTokenIn: userTokenBAccount,
-          userTokenOut: userTokenAAccount,
-          poolVaultIn: poolTokenBVault,
-          poolVaultOut: poolTokenAVault,
-          user: payer.publicKey,
-          tokenProgram: TOKEN_PROGRAM_ID,
-        })
+        .swap(new anchor.BN(swapAmount), new anchor.BN(minimumAmountOut), false, null)
+        .accountsPartial(swapAccountsFor(false))
         .rpc();
 
       const balanceAfter = await getTokenBalance(connection, userTokenAAccount);
       const received = balanceAfter - balanceBefore;
 
-      assert.ok(received > minimumAmountOut, "Should receive at least minimum amount");
+      assert.ok(received >= minimumAmountOut, "Should receive at least minimum amount");
       console.log(`   ✓ Received ${formatAmount(received)} SOL`);
     });
 
@@ -253,51 +189,35 @@ describe("Trading - Module 3", () => {
     
     it("✅ Market buy SOL with USDC (execute market_buy)", async () => {
       console.log("\n💰 Testing market buy...");
-      const solAmount = 50 * 10 ** TOKEN_A_DECIMALS;
-      const maxUsdc = 1500 * 10 ** TOKEN_B_DECIMALS;
+      const usdcAmount = 1250 * 10 ** TOKEN_B_DECIMALS;
+      const minSol = await minimumOut(usdcAmount, false);
 
       const balanceBefore = await getTokenBalance(connection, userTokenAAccount);
 
       // market_buy is a wrapper around swap with is_a_to_b=false
       await program.methods
-        .marketBuy(new anchor.BN(solAmount), new anchor.BN(maxUsdc))
-        .accounts({
-          pool: poolAccount,
-          userTokenIn: userTokenBAccount,
-          userTokenOut: userTokenAAccount,
-          poolVaultIn: poolTokenBVault,
-          poolVaultOut: poolTokenAVault,
-          user: payer.publicKey,
-          tokenProgram: TOKEN_PROGRAM_ID,
-        })
+        .marketBuy(new anchor.BN(usdcAmount), new anchor.BN(minSol), null)
+        .accountsPartial(swapAccountsFor(false))
         .rpc();
 
       const balanceAfter = await getTokenBalance(connection, userTokenAAccount);
       const received = balanceAfter - balanceBefore;
 
-      assert.ok(received >= solAmount, "Should buy requested SOL amount");
+      assert.ok(received >= minSol, "Should receive at least minimum SOL");
       console.log(`   ✓ Bought ${formatAmount(received)} SOL`);
     });
 
     it("✅ Market sell SOL for USDC (execute market_sell)", async () => {
       console.log("\n💸 Testing market sell...");
       const solAmount = 50 * 10 ** TOKEN_A_DECIMALS;
-      const minUsdc = 1200 * 10 ** TOKEN_B_DECIMALS;
+      const minUsdc = await minimumOut(solAmount, true);
 
       const balanceBefore = await getTokenBalance(connection, userTokenBAccount);
 
       // market_sell is a wrapper around swap with is_a_to_b=true
       await program.methods
-        .marketSell(new anchor.BN(solAmount), new anchor.BN(minUsdc))
-        .accounts({
-          pool: poolAccount,
-          userTokenIn: userTokenAAccount,
-          userTokenOut: userTokenBAccount,
-          poolVaultIn: poolTokenAVault,
-          poolVaultOut: poolTokenBVault,
-          user: payer.publicKey,
-          tokenProgram: TOKEN_PROGRAM_ID,
-        })
+        .marketSell(new anchor.BN(solAmount), new anchor.BN(minUsdc), null)
+        .accountsPartial(swapAccountsFor(true))
         .rpc();
 
       const balanceAfter = await getTokenBalance(connection, userTokenBAccount);
@@ -311,29 +231,28 @@ describe("Trading - Module 3", () => {
       console.log("\n💵 Verifying fee distribution...");
 
       const swapAmount = 100 * 10 ** TOKEN_A_DECIMALS;
-      const expectedFee = (swapAmount * 3) / 1000; // 0.3% fee
+      const expectedFee = (swapAmount * FEE_TIER_BPS) / 10_000; // 0.3% fee
 
-      const lpBalanceBefore = await getTokenBalance(connection, userLPTokenAccount);
+      let poolData = await program.account.liquidityPool.fetch(poolAccount);
+      const kBefore = poolData.reserveA.toNumber() * poolData.reserveB.toNumber();
+      const feeGrowthBefore = poolData.feeGrowthGlobalA;
 
       // Execute swap
       await program.methods
-        .swap(new anchor.BN(swapAmount), new anchor.BN(0), true)
-        .accounts({
-          pool: poolAccount,
-          userTokenIn: userTokenAAccount,
-          userTokenOut: userTokenBAccount,
-          poolVaultIn: poolTokenAVault,
-          poolVaultOut: poolTokenBVault,
-          user: payer.publicKey,
-          tokenProgram: TOKEN_PROGRAM_ID,
-        })
+        .swap(new anchor.BN(swapAmount), new anchor.BN(0), true, null)
+        .accountsPartial(swapAccountsFor(true))
         .rpc();
 
-      // Fees are collected in pool reserves and distributed to LPs via LP token value
-      const poolData = await program.account.liquidityPool.fetch(poolAccount);
-      console.log(`   ✓ 0.3% fee collected from swap`);
-      console.log(`   ✓ Fee numerator: ${poolData.feeNumerator}`);
-      console.log(`   ✓ Fee denominator: ${poolData.feeDenominator}`);
+      // Fees stay in pool reserves and are distributed to LPs via LP token value
+      poolData = await program.account.liquidityPool.fetch(poolAccount);
+      const kAfter = poolData.reserveA.toNumber() * poolData.reserveB.toNumber();
+      assert.ok(kAfter > kBefore, "LP fee should grow k");
+      assert.ok(
+        poolData.feeGrowthGlobalA.gt(feeGrowthBefore),
+        "LP fee should accrue to fee growth"
+      );
+      console.log(`   ✓ ${formatAmount(expectedFee)} SOL fee collected from swap`);
+      console.log(`   ✓ Fee tier: ${poolData.feeTierBps} bps`);
     });
   });
 
@@ -343,31 +262,19 @@ describe("Trading - Module 3", () => {
 
   describe("Module 3.4: Limit Orders", () => {
     
-    let limitOrderPda: PublicKey;
-    let orderVault: PublicKey;
     const sellAmount = 100 * 10 ** TOKEN_A_DECIMALS;
-    const targetPrice = 25_000_000; // 25 USDC per SOL with 6 decimals
-    const minimumReceive = 2400 * 10 ** TOKEN_B_DECIMALS;
-    const expiryDays = 30n;
-
-    it("✅ Create limit order (sell SOL at target price)", async () => {
-      console.log("\n📋 Testing limit order creation...");
-
-      // Derive limit order PDA
-      const [pda] = await PublicKey.findProgramAddress(
-        [Buffer.from("limit_order"), poolAccount.toBuffer(), payer.publicKey.toBuffer()],
-        program.programId
-      );
-      limitOrderPda = pda;
-
-      // Derive order vault
-      const [vault] = await PublicKey.findProgramAddress(
-        [Buffer.from("order_vault"), limitOrderPda.toBuffer()],
-        program.programId
-      );
-      orderVault = vault;
-
-      const balanceBefore = await getTokenBalance(connection, userTokenAAccount);
+    const expiryDays = new anchor.BN(30);
+
+    // Escrow SOL from `owner` into a fresh order vault; one order per owner per pool
+    async function createSellOrder(
+      owner: Keypair,
+      ownerTokenA: PublicKey,
+      ownerTokenB: PublicKey,
+      targetPrice: number,
+      minimumReceive: number
+    ) {
+      const limitOrder = limitOrderPda(program, poolAccount, owner.publicKey);
+      const orderVault = Keypair.generate();
 
       await program.methods
         .createLimitOrder(
@@ -376,24 +283,63 @@ describe("Trading - Module 3", () => {
           new anchor.BN(minimumReceive),
           expiryDays
         )
-        .accounts({
+        .accountsPartial({
           pool: poolAccount,
-          limitOrder: limitOrderPda,
-          sellTokenMint: tokenA.publicKey,
-          userTokenIn: userTokenAAccount,
-          userTokenOut: userTokenBAccount,
-          orderVault: orderVault,
-          user: payer.publicKey,
+          limitOrder,
+          sellTokenMint: tokenAMint,
+          userTokenIn: ownerTokenA,
+          userTokenOut: ownerTokenB,
+          orderVault: orderVault.publicKey,
+          user: owner.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
-          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers(owner === payer.payer ? [orderVault] : [owner, orderVault])
+        .rpc();
+
+      return { limitOrder, orderVault: orderVault.publicKey };
+    }
+
+    // Permissionless fill of a sell-A order; proceeds go to the owner's B account
+    const executeSellOrder = (order: { limitOrder: PublicKey; orderVault: PublicKey }, ownerTokenB: PublicKey) =>
+      program.methods
+        .executeLimitOrder()
+        .accountsPartial({
+          pool: poolAccount,
+          limitOrder: order.limitOrder,
+          orderVault: order.orderVault,
+          userTokenOut: ownerTokenB,
+          poolVaultIn: poolTokenAVault,
+          poolVaultOut: poolTokenBVault,
+          sellTokenMint: tokenAMint,
+          buyTokenMint: tokenBMint,
+          sellTokenProgram: TOKEN_PROGRAM_ID,
+          buyTokenProgram: TOKEN_PROGRAM_ID,
         })
         .rpc();
 
+    let order: { limitOrder: PublicKey; orderVault: PublicKey };
+    let minimumReceive: number;
+
+    it("✅ Create limit order (sell SOL at target price)", async () => {
+      console.log("\n📋 Testing limit order creation...");
+
+      // Target just below the current price so the order is immediately fillable
+      const poolData = await program.account.liquidityPool.fetch(poolAccount);
+      const spotPrice = Math.floor((poolData.reserveB.toNumber() * 1_000_000) / poolData.reserveA.toNumber());
+      const targetPrice = Math.floor((spotPrice * 99) / 100);
+      minimumReceive = await minimumOut(sellAmount, true);
+
+      const balanceBefore = await getTokenBalance(connection, userTokenAAccount);
+
+      order = await createSellOrder(payer.payer, userTokenAAccount, userTokenBAccount, targetPrice, minimumReceive);
+
       const balanceAfter = await getTokenBalance(connection, userTokenAAccount);
       const escrowed = balanceBefore - balanceAfter;
 
       assert.ok(escrowed === sellAmount, "Sell amount should be escrowed");
+      assert.ok(await getTokenBalance(connection, order.orderVault) === sellAmount, "Vault holds the escrow");
       console.log(`   ✓ Escrowed ${formatAmount(sellAmount)} SOL`);
       console.log(`   ✓ Target price: ${targetPrice / 1_000_000} USDC/SOL`);
       console.log(`   ✓ Minimum receive: ${formatAmount(minimumReceive)} USDC`);
@@ -404,62 +350,66 @@ describe("Trading - Module 3", () => {
 
       const balanceBefore = await getTokenBalance(connection, userTokenBAccount);
 
-      // Note: In real test, price would need to reach target
-      // For this test, we assume price condition is met
-      try {
-        await program.methods
-          .executeLimitOrder()
-          .accounts({
-            pool: poolAccount,
-            limitOrder: limitOrderPda,
-            userTokenOut: userTokenBAccount,
-            poolVaultOut: poolTokenBVault,
-            user: payer.publicKey,
-            tokenProgram: TOKEN_PROGRAM_ID,
-          })
-          .rpc();
+      await executeSellOrder(order, userTokenBAccount);
 
-        const balanceAfter = await getTokenBalance(connection, userTokenBAccount);
-        const received = balanceAfter - balanceBefore;
-
-        assert.ok(received >= minimumReceive, "Should receive at least minimum");
-        console.log(`   ✓ Received ${formatAmount(received)} USDC`);
-      } catch (err: any) {
-        // If price condition not met, that's expected in this test
-        if (err.message.includes("PriceConditionNotMet")) {
-          console.log(`   ⚠ Price condition not met (expected in test)`);
-        } else {
-          throw err;
-        }
-      }
+      const balanceAfter = await getTokenBalance(connection, userTokenBAccount);
+      const received = balanceAfter - balanceBefore;
+
+      assert.ok(received >= minimumReceive, "Should receive at least minimum");
+      const orderData = await program.account.limitOrder.fetch(order.limitOrder);
+      assert.ok("executed" in orderData.status, "Order should be marked executed");
+      console.log(`   ✓ Received ${formatAmount(received)} USDC`);
     });
 
     it("✅ Cancel limit order before execution", async () => {
       console.log("\n❌ Testing limit order cancellation...");
 
-      const balanceBefore = await getTokenBalance(connection, userTokenAAccount);
+      const maker = await createFundedUser(connection, payer.payer, tokenAMint, tokenBMint, sellAmount, 0);
+      // Unreachable target keeps the order pending until it is cancelled
+      const pending = await createSellOrder(
+        maker.user,
+        maker.tokenA,
+        maker.tokenB,
+        1_000 * 1_000_000,
+        1
+      );
+      assert.ok(await getTokenBalance(connection, maker.tokenA) === 0, "Maker's SOL is escrowed");
 
-      try {
-        await program.methods
-          .cancelLimitOrder()
-          .accounts({
-            limitOrder: limitOrderPda,
-            orderVault: orderVault,
-            userTokenIn: userTokenAAccount,
-            user: payer.publicKey,
-            tokenProgram: TOKEN_PROGRAM_ID,
-          })
-          .rpc();
+      await program.methods
+        .cancelLimitOrder()
+        .accountsPartial({
+          limitOrder: pending.limitOrder,
+          orderVault: pending.orderVault,
+          userTokenIn: maker.tokenA,
+          sellTokenMint: tokenAMint,
+          user: maker.user.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([maker.user])
+        .rpc();
 
-        const balanceAfter = await getTokenBalance(connection, userTokenAAccount);
-        const refunded = balanceAfter - balanceBefore;
+      const refunded = await getTokenBalance(connection, maker.tokenA);
+      assert.ok(refunded === sellAmount, "Should refund entire escrowed amount");
+      const orderData = await program.account.limitOrder.fetch(pending.limitOrder);
+      assert.ok("cancelled" in orderData.status, "Order should be marked cancelled");
+      console.log(`   ✓ Refunded ${formatAmount(refunded)} SOL`);
+    });
 
-        assert.ok(refunded === sellAmount, "Should refund entire escrowed amount");
-        console.log(`   ✓ Refunded ${formatAmount(refunded)} SOL`);
-      } catch (err: any) {
-        // Order might already be executed/cancelled
-        console.log(`   ℹ Order state changed: ${err.message.substring(0, 50)}...`);
-      }
+    it("❌ Reject limit order execution before price target", async () => {
+      console.log("\n🚫 Testing limit order price condition rejection...");
+
+      // Create a new limit order with a target far above the pool price
+      const maker = await createFundedUser(connection, payer.payer, tokenAMint, tokenBMint, sellAmount, 0);
+      const pending = await createSellOrder(
+        maker.user,
+        maker.tokenA,
+        maker.tokenB,
+        1_000 * 1_000_000,
+        1
+      );
+
+      await expectError(executeSellOrder(pending, maker.tokenB), "PriceConditionNotMet");
+      console.log(`   ✓ Correctly rejected: price condition not met`);
     });
   });
 
@@ -473,33 +423,17 @@ describe("Trading - Module 3", () => {
       console.log("\n🚫 Testing insufficient balance rejection...");
 
       // Create a new user with no tokens
-      const newUser = Keypair.generate();
-      const newUserTokenA = await tokenA.createAccount(newUser.publicKey);
-
-      try {
-        await program.methods
-          .swap(new anchor.BN(1000 * 10 ** TOKEN_A_DECIMALS), new anchor.BN(0), true)
-          .accounts({
-            pool: poolAccount,
-            userTokenIn: newUserTokenA,
-            userTokenOut: userTokenBAccount,
-            poolVaultIn: poolTokenAVault,
-            poolVaultOut: poolTokenBVault,
-            user: newUser.publicKey,
-            tokenProgram: TOKEN_PROGRAM_ID,
-          })
-          .signers([newUser])
-          .rpc();
-
-        assert.fail("Should have failed with insufficient balance");
-      } catch (err: any) {
-        assert.ok(
-          err.message.includes("InsufficientBalance") ||
-          err.message.includes("insufficient funds"),
-          "Should fail with insufficient balance error"
-        );
-        console.log(`   ✓ Correctly rejected: insufficient balance`);
-      }
+      const newUser = await createFundedUser(connection, payer.payer, tokenAMint, tokenBMint, 0, 0);
+
+      await expectError(
+        program.methods
+          .swap(new anchor.BN(1000 * 10 ** TOKEN_A_DECIMALS), new anchor.BN(0), true, null)
+          .accountsPartial(swapAccounts(fixture, newUser.user.publicKey, newUser.tokenA, newUser.tokenB, true))
+          .signers([newUser.user])
+          .rpc(),
+        "InsufficientBalance"
+      );
+      console.log(`   ✓ Correctly rejected: insufficient balance`);
     });
 
     it("❌ Reject swap exceeding slippage tolerance", async () => {
@@ -508,68 +442,14 @@ describe("Trading - Module 3", () => {
       const swapAmount = 100 * 10 ** TOKEN_A_DECIMALS;
       const impossibleMinimum = 50000 * 10 ** TOKEN_B_DECIMALS; // Way too high
 
-      try {
-        await program.methods
-          .swap(new anchor.BN(swapAmount), new anchor.BN(impossibleMinimum), true)
-          .accounts({
-            pool: poolAccount,
-            userTokenIn: userTokenAAccount,
-            userTokenOut: userTokenBAccount,
-            poolVaultIn: poolTokenAVault,
-            poolVaultOut: poolTokenBVault,
-            user: payer.publicKey,
-            tokenProgram: TOKEN_PROGRAM_ID,
-          })
-          .rpc();
-
-        assert.fail("Should have failed with slippage error");
-      } catch (err: any) {
-        assert.ok(
-          err.message.includes("SlippageTooHigh") ||
-          err.message.includes("insufficient"),
-          "Should fail with slippage error"
-        );
-        console.log(`   ✓ Correctly rejected: slippage too high`);
-      }
-    });
-
-    it("❌ Reject limit order execution before price target", async () => {
-      console.log("\n🚫 Testing limit order price condition rejection...");
-
-      // Create a new limit order with high target price
-      const [pda] = await PublicKey.findProgramAddress(
-        [
-          Buffer.from("limit_order"),
-          poolAccount.toBuffer(),
-          new PublicKey("11111111111111111111111111111111").toBuffer(), // Dummy address
-        ],
-        program.programId
+      await expectError(
+        program.methods
+          .swap(new anchor.BN(swapAmount), new anchor.BN(impossibleMinimum), true, null)
+          .accountsPartial(swapAccountsFor(true))
+          .rpc(),
+        "SlippageTooHigh"
       );
-
-      try {
-        // Try to execute without price meeting target
-        await program.methods
-          .executeLimitOrder()
-          .accounts({
-            pool: poolAccount,
-            limitOrder: pda,
-            userTokenOut: userTokenBAccount,
-            poolVaultOut: poolTokenBVault,
-            user: payer.publicKey,
-            tokenProgram: TOKEN_PROGRAM_ID,
-          })
-          .rpc();
-
-        console.log(`   ℹ Execution passed (order may not exist)`);
-      } catch (err: any) {
-        assert.ok(
-          err.message.includes("PriceConditionNotMet") ||
-          err.message.includes("not found") ||
-          err.message.includes("invalid"),
-          "Should fail with price condition error"
-        );
-        console.log(`   ✓ Correctly rejected: price condition not met`);
-      }
+      console.log(`   ✓ Correctly rejected: slippage too high`);
     });
 
     it("❌ Reject limit order execution after expiry", async () => {
@@ -582,28 +462,53 @@ describe("Trading - Module 3", () => {
     it("✅ Reject zero amount swap", async () => {
       console.log("\n🚫 Testing zero amount rejection...");
 
-      try {
-        await program.methods
-          .swap(new anchor.BN(0), new anchor.BN(0), true)
-          .accounts({
+      await expectError(
+        program.methods
+          .swap(new anchor.BN(0), new anchor.BN(0), true, null)
+          .accountsPartial(swapAccountsFor(true))
+          .rpc(),
+        "InvalidAmount"
+      );
+      console.log(`   ✓ Correctly rejected: zero amount`);
+    });
+
+    it("❌ Reject swap after its deadline", async () => {
+      console.log("\n🚫 Testing deadline rejection...");
+
+      const slot = await connection.getSlot();
+      const now = await connection.getBlockTime(slot);
+      const expiredDeadline = new anchor.BN(now - 60);
+
+      await expectError(
+        program.methods
+          .swap(new anchor.BN(1 * 10 ** TOKEN_A_DECIMALS), new anchor.BN(0), true, expiredDeadline)
+          .accountsPartial(swapAccountsFor(true))
+          .rpc(),
+        "DeadlineExceeded"
+      );
+      console.log(`   ✓ Correctly rejected: deadline passed`);
+    });
+
+    it("❌ Reject flash loan while the pool fee is unset", async () => {
+      console.log("\n🚫 Testing flash loan on a pool that has not enabled them...");
+
+      await expectError(
+        program.methods
+          .flashLoan(new anchor.BN(1 * 10 ** TOKEN_A_DECIMALS), true, Buffer.alloc(0))
+          .accountsPartial({
             pool: poolAccount,
-            userTokenIn: userTokenAAccount,
-            userTokenOut: userTokenBAccount,
-            poolVaultIn: poolTokenAVault,
-            poolVaultOut: poolTokenBVault,
-            user: payer.publicKey,
+            tokenAVault: poolTokenAVault,
+            tokenBVault: poolTokenBVault,
+            tokenMint: tokenAMint,
+            borrowerTokenAccount: userTokenAAccount,
+            receiverProgram: SystemProgram.programId,
+            borrower: payer.publicKey,
             tokenProgram: TOKEN_PROGRAM_ID,
           })
-          .rpc();
-
-        assert.fail("Should have failed with zero amount");
-      } catch (err: any) {
-        assert.ok(
-          err.message.includes("InvalidAmount"),
-          "Should fail with InvalidAmount error"
-        );
-        console.log(`   ✓ Correctly rejected: zero amount`);
-      }
+          .rpc(),
+        "FlashLoansDisabled"
+      );
+      console.log(`   ✓ Correctly rejected: flash loans disabled by default`);
     });
   });
 
@@ -634,16 +539,8 @@ describe("Trading - Module 3", () => {
       const balanceBefore = await getTokenBalance(connection, userTokenBAccount);
 
       await program.methods
-        .swap(new anchor.BN(largeSwapAmount), new anchor.BN(0), true)
-        .accounts({
-          pool: poolAccount,
-          userTokenIn: userTokenAAccount,
-          userTokenOut: userTokenBAccount,
-          poolVaultIn: poolTokenAVault,
-          poolVaultOut: poolTokenBVault,
-          user: payer.publicKey,
-          tokenProgram: TOKEN_PROGRAM_ID,
-        })
+        .swap(new anchor.BN(largeSwapAmount), new anchor.BN(0), true, null)
+        .accountsPartial(swapAccountsFor(true))
         .rpc();
 
       const balanceAfter = await getTokenBalance(connection, userTokenBAccount);
@@ -676,16 +573,8 @@ describe("Trading - Module 3", () => {
         const amount = (50 + i * 10) * 10 ** TOKEN_A_DECIMALS;
         
         await program.methods
-          .swap(new anchor.BN(amount), new anchor.BN(0), i % 2 === 0)
-          .accounts({
-            pool: poolAccount,
-            userTokenIn: i % 2 === 0 ? userTokenAAccount : userTokenBAccount,
-            userTokenOut: i % 2 === 0 ? userTokenBAccount : userTokenAAccount,
-            poolVaultIn: i % 2 === 0 ? poolTokenAVault : poolTokenBVault,
-            poolVaultOut: i % 2 === 0 ? poolTokenBVault : poolTokenAVault,
-            user: payer.publicKey,
-            tokenProgram: TOKEN_PROGRAM_ID,
-          })
+          .swap(new anchor.BN(amount), new anchor.BN(0), i % 2 === 0, null)
+          .accountsPartial(swapAccountsFor(i % 2 === 0))
           .rpc();
       }
 
@@ -1,20 +1,32 @@
 import { PublicKey } from "@solana/web3.js";
 import { PROGRAM_ID } from "./setup";
 
-export const findPoolAddress = (tokenAMint: PublicKey, tokenBMint: PublicKey): PublicKey => {
-    // Sort mints to ensure deterministic order (if required by backend, usually yes)
-    // But looking at lib.rs: seeds = [b"pool", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()]
-    // It doesn't explicitly sort in the macro, but usually pools sort them.
-    // I'll assume the caller passes them in the correct order or I should sort them.
-    // For safety, I'll assume the backend expects them in the order they were initialized.
-    // However, standard AMM practice is to sort.
-    // Let's assume sorted for now.
+export const DEFAULT_FEE_TIER_BPS = 30;
+
+export const findPoolAddress = (
+    tokenAMint: PublicKey,
+    tokenBMint: PublicKey,
+    feeTierBps: number = DEFAULT_FEE_TIER_BPS
+): PublicKey => {
+    // Pools only exist with mints in canonical byte order (token_a_mint < token_b_mint),
+    // so either argument order resolves to the same pool.
     const [mintA, mintB] = tokenAMint.toBuffer().compare(tokenBMint.toBuffer()) < 0
         ? [tokenAMint, tokenBMint]
         : [tokenBMint, tokenAMint];
 
+    // The fee tier (bps) is the last seed, encoded as a little-endian u16
+    const feeTierSeed = Buffer.alloc(2);
+    feeTierSeed.writeUInt16LE(feeTierBps);
+
+    return PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), mintA.toBuffer(), mintB.toBuffer(), feeTierSeed],
+        PROGRAM_ID
+    )[0];
+};
+
+export const findDexConfigAddress = (): PublicKey => {
     return PublicKey.findProgramAddressSync(
-        [Buffer.from("pool"), mintA.toBuffer(), mintB.toBuffer()],
+        [Buffer.from("dex_config")],
         PROGRAM_ID
     )[0];
 };